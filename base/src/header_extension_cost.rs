@@ -0,0 +1,136 @@
+//! Estimation of the time taken to build the header extension.
+//!
+//! The Kate commitments are built at `finalize_block`, once the extrinsics are applied, and their
+//! cost grows with the padded grid. The block author uses [`estimate`] to keep that time out of
+//! the budget it spends on extrinsics.
+//!
+//! The estimate is linear in the number of cells of the grid. It starts from
+//! [`DEFAULT_NANOS_PER_CELL`] and follows the builds of the blocks authored by this node. Imported
+//! blocks are built on the import path, often several at once, so their builds are not observed.
+
+use core::{
+	cell::Cell,
+	sync::atomic::{AtomicU64, Ordering},
+	time::Duration,
+};
+
+/// Build time of a cell of the original grid, measured on the reference hardware.
+pub const DEFAULT_NANOS_PER_CELL: u64 = 25_000;
+/// Smaller grids are dominated by fixed costs and are not used to calibrate the estimate.
+const MIN_OBSERVED_CELLS: u64 = 1024;
+/// Weight of the last build in the calibrated cost, in percent.
+const SMOOTHING_PERCENT: u64 = 20;
+/// The estimate is increased by this percentage to absorb the variance between builds.
+const SAFETY_MARGIN_PERCENT: u64 = 25;
+
+static NANOS_PER_CELL: AtomicU64 = AtomicU64::new(DEFAULT_NANOS_PER_CELL);
+
+thread_local! {
+	/// Whether this thread is building a block authored by this node.
+	static AUTHORING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `build`, the build of a block authored by this node. The header extensions built by it
+/// on this thread calibrate the estimate.
+pub fn authoring<R>(build: impl FnOnce() -> R) -> R {
+	struct Reset(bool);
+	impl Drop for Reset {
+		fn drop(&mut self) {
+			AUTHORING.with(|authoring| authoring.set(self.0));
+		}
+	}
+
+	let _reset = Reset(AUTHORING.with(|authoring| authoring.replace(true)));
+	build()
+}
+
+/// Dimensions `(rows, cols)` of the grid holding `scalars` padded scalars, laid out like the
+/// header builder does.
+pub fn grid_dims(scalars: u32, min_width: u32, max_rows: u32, max_cols: u32) -> (u32, u32) {
+	if scalars < max_cols {
+		let cols = scalars
+			.max(min_width)
+			.checked_next_power_of_two()
+			.unwrap_or(max_cols);
+		return (1, cols.min(max_cols));
+	}
+
+	let rows = scalars
+		.div_ceil(max_cols.max(1))
+		.checked_next_power_of_two()
+		.unwrap_or(max_rows);
+	(rows.min(max_rows), max_cols)
+}
+
+/// Calibrates the estimate with the build of a `rows` x `cols` grid which took `elapsed`.
+/// Builds outside of [`authoring`] are ignored.
+pub fn observe(rows: u32, cols: u32, elapsed: Duration) {
+	let cells = u64::from(rows) * u64::from(cols);
+	if cells < MIN_OBSERVED_CELLS || !AUTHORING.with(Cell::get) {
+		return;
+	}
+
+	let observed = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX) / cells;
+	let _ = NANOS_PER_CELL.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+		let smoothed = current.saturating_mul(100 - SMOOTHING_PERCENT)
+			+ observed.saturating_mul(SMOOTHING_PERCENT);
+		Some(smoothed / 100)
+	});
+}
+
+/// Estimated time to build the header extension of a `rows` x `cols` grid.
+pub fn estimate(rows: u32, cols: u32) -> Duration {
+	let cells = u64::from(rows) * u64::from(cols);
+	let nanos = cells
+		.saturating_mul(NANOS_PER_CELL.load(Ordering::Relaxed))
+		.saturating_mul(100 + SAFETY_MARGIN_PERCENT)
+		/ 100;
+	Duration::from_nanos(nanos)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use test_case::test_case;
+
+	#[test_case(0 => (1, 4); "empty grid uses the minimal width")]
+	#[test_case(3 => (1, 4); "small data uses the minimal width")]
+	#[test_case(100 => (1, 128); "width is a power of two")]
+	#[test_case(256 => (1, 256); "a full row")]
+	#[test_case(257 => (2, 256); "a second row")]
+	#[test_case(3 * 256 => (4, 256); "height is a power of two")]
+	#[test_case(u32::MAX => (256, 256); "height is capped")]
+	fn grid_dims_follow_the_builder(scalars: u32) -> (u32, u32) {
+		grid_dims(scalars, 4, 256, 256)
+	}
+
+	#[test]
+	fn estimate_grows_with_the_grid() {
+		assert_eq!(estimate(0, 0), Duration::ZERO);
+		assert!(estimate(1, 256) < estimate(2, 256));
+		assert!(estimate(128, 256) < estimate(256, 256));
+	}
+
+	#[test]
+	fn observe_calibrates_with_authored_builds_only() {
+		let cells = 64 * 256;
+		let estimate_at = |nanos_per_cell: u64| {
+			Duration::from_nanos(cells * nanos_per_cell * (100 + SAFETY_MARGIN_PERCENT) / 100)
+		};
+		let twice_slower = Duration::from_nanos(cells * 2 * DEFAULT_NANOS_PER_CELL);
+		assert_eq!(estimate(64, 256), estimate_at(DEFAULT_NANOS_PER_CELL));
+
+		// imported blocks and small grids are ignored
+		observe(64, 256, twice_slower);
+		authoring(|| observe(1, 4, twice_slower));
+		assert_eq!(estimate(64, 256), estimate_at(DEFAULT_NANOS_PER_CELL));
+
+		// the last build weighs `SMOOTHING_PERCENT` in the calibrated cost
+		authoring(|| observe(64, 256, twice_slower));
+		assert_eq!(estimate(64, 256), estimate_at(30_000));
+
+		// the thread is no longer authoring after the build
+		observe(64, 256, twice_slower);
+		assert_eq!(estimate(64, 256), estimate_at(30_000));
+	}
+}
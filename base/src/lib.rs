@@ -4,6 +4,9 @@
 #[cfg(feature = "std")]
 pub mod metrics;
 
+#[cfg(feature = "std")]
+pub mod header_extension_cost;
+
 pub mod mem_tmp_storage;
 pub use mem_tmp_storage::{MemoryTemporaryStorage, StorageMap};
 
@@ -1,6 +1,7 @@
 use once_cell::sync::OnceCell;
 use substrate_prometheus_endpoint::{
-	exponential_buckets, register, Histogram, HistogramOpts, Opts, PrometheusError, Registry,
+	exponential_buckets, register, Counter, Histogram, HistogramOpts, Opts, PrometheusError,
+	Registry, U64,
 };
 
 const LOG_TARGET: &str = "avail::base::metrics";
@@ -86,3 +87,15 @@ fn custom_histogram(
 
 	Ok(histogram)
 }
+
+fn counter(registry: &Registry, name: &str, help: &str) -> Result<Counter<U64>, PrometheusError> {
+	let counter = Counter::new(name, help)?;
+	register(counter.clone(), registry)?;
+	log::trace!(
+		target: LOG_TARGET,
+		"Added counter metric `{0}` to prometheus",
+		name
+	);
+
+	Ok(counter)
+}
@@ -1,10 +1,10 @@
 use core::time::Duration;
 
-use substrate_prometheus_endpoint::{Histogram, PrometheusError, Registry};
+use substrate_prometheus_endpoint::{Counter, Histogram, PrometheusError, Registry, U64};
 
 use crate::metrics::LOG_TARGET;
 
-use super::{custom_histogram, AVAIL_METRICS};
+use super::{counter, custom_histogram, AVAIL_METRICS};
 
 /// Avail metrics.
 pub struct AvailMetrics {
@@ -171,6 +171,7 @@ impl HeaderExtensionBuilderMetrics {
 pub struct KateRpcMetrics {
 	pub query_rows_execution_time: Histogram,
 	pub query_app_data_execution_time: Histogram,
+	pub query_app_data_proof_execution_time: Histogram,
 	pub query_proof_execution_time: Histogram,
 	pub query_block_length_execution_time: Histogram,
 	pub query_data_proof_execution_time: Histogram,
@@ -202,6 +203,26 @@ impl KateRpcMetrics {
 			buckets.to_vec(),
 		)?;
 
+		let buckets = [
+			100_000.0,
+			250_000.0,
+			500_000.0,
+			1_000_000.0, //  100ms, 250ms, 500ms, 1s
+			2_500_000.0,
+			5_000_000.0,
+			7_500_000.0,
+			10_000_000.0, // 2.5s, 5s, 7.5s, 10s
+			15_000_000.0,
+			20_000_000.0,
+			30_000_000.0, // 15s, 20s, 30s
+		];
+		let query_app_data_proof_execution_time = custom_histogram(
+			registry,
+			"avail_kate_rpc_query_app_data_proof_execution_time",
+			"Kate RPC - Query App Data Proof Time in microseconds",
+			buckets.to_vec(),
+		)?;
+
 		let buckets = [
 			100_000.0,
 			250_000.0,
@@ -249,6 +270,7 @@ impl KateRpcMetrics {
 		Ok(Self {
 			query_rows_execution_time,
 			query_app_data_execution_time,
+			query_app_data_proof_execution_time,
 			query_proof_execution_time,
 			query_block_length_execution_time,
 			query_data_proof_execution_time,
@@ -273,6 +295,15 @@ impl KateRpcMetrics {
 		}
 	}
 
+	pub fn observe_query_app_data_proof_execution_time(duration: Duration) {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics
+				.kate_rpc
+				.query_app_data_proof_execution_time
+				.observe(duration.as_micros() as f64);
+		}
+	}
+
 	pub fn observe_query_proof_execution_time(duration: Duration) {
 		if let Some(metrics) = AVAIL_METRICS.get() {
 			metrics
@@ -303,6 +334,12 @@ impl KateRpcMetrics {
 
 pub struct ImportBlockMetrics {
 	pub total_execution_time: Histogram,
+	pub full_da_checks: Counter<U64>,
+	pub sampled_da_checks: Counter<U64>,
+	pub sampled_da_rows: Counter<U64>,
+	pub sampled_da_fallbacks: Counter<U64>,
+	pub pipelined_da_checks: Counter<U64>,
+	pub pipelined_da_fallbacks: Counter<U64>,
 }
 impl ImportBlockMetrics {
 	pub fn new(registry: &Registry) -> Result<Self, PrometheusError> {
@@ -329,8 +366,45 @@ impl ImportBlockMetrics {
 			buckets.to_vec(),
 		)?;
 
+		let full_da_checks = counter(
+			registry,
+			"avail_import_block_full_da_checks",
+			"Import Block - Blocks whose header extension was fully rebuilt",
+		)?;
+		let sampled_da_checks = counter(
+			registry,
+			"avail_import_block_sampled_da_checks",
+			"Import Block - Blocks whose data root was checked during a sampled sync",
+		)?;
+		let sampled_da_rows = counter(
+			registry,
+			"avail_import_block_sampled_da_rows",
+			"Import Block - Commitment rows rebuilt during a sampled sync",
+		)?;
+		let sampled_da_fallbacks = counter(
+			registry,
+			"avail_import_block_sampled_da_fallbacks",
+			"Import Block - Sampled checks which fell back to a full rebuild",
+		)?;
+		let pipelined_da_checks = counter(
+			registry,
+			"avail_import_block_pipelined_da_checks",
+			"Import Block - Blocks whose header extension was rebuilt ahead of import",
+		)?;
+		let pipelined_da_fallbacks = counter(
+			registry,
+			"avail_import_block_pipelined_da_fallbacks",
+			"Import Block - Blocks checked at import as their pipelined check was missing or failed",
+		)?;
+
 		Ok(Self {
 			total_execution_time,
+			full_da_checks,
+			sampled_da_checks,
+			sampled_da_rows,
+			sampled_da_fallbacks,
+			pipelined_da_checks,
+			pipelined_da_fallbacks,
 		})
 	}
 
@@ -342,4 +416,35 @@ impl ImportBlockMetrics {
 				.observe(duration.as_micros() as f64);
 		}
 	}
+
+	pub fn inc_full_da_checks() {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics.import_block.full_da_checks.inc();
+		}
+	}
+
+	pub fn inc_sampled_da_checks(rows: u64) {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics.import_block.sampled_da_checks.inc();
+			metrics.import_block.sampled_da_rows.inc_by(rows);
+		}
+	}
+
+	pub fn inc_sampled_da_fallbacks() {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics.import_block.sampled_da_fallbacks.inc();
+		}
+	}
+
+	pub fn inc_pipelined_da_checks() {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics.import_block.pipelined_da_checks.inc();
+		}
+	}
+
+	pub fn inc_pipelined_da_fallbacks() {
+		if let Some(metrics) = AVAIL_METRICS.get() {
+			metrics.import_block.pipelined_da_fallbacks.inc();
+		}
+	}
 }
@@ -19,9 +19,15 @@
 //! A consensus proposer for "basic" chains which use the primitive inherent-data.
 
 // FIXME #1021 move this into sp-consensus
-use avail_base::{PostInherentsBackend, PostInherentsProvider};
+use crate::packing::{self, PackingStrategy, Reordered};
+use avail_base::{header_extension_cost, PostInherentsBackend, PostInherentsProvider};
 
 use codec::Encode;
+use da_runtime::apis::DataAvailApi;
+use frame_system::{
+	header_builder::{precompute::GridPrecomputer, Seed, MIN_WIDTH},
+	limits::BlockLength,
+};
 use futures::{
 	channel::oneshot,
 	future,
@@ -31,7 +37,7 @@ use futures::{
 use log::{debug, error, info, trace, warn};
 use sc_block_builder::{BlockBuilderApi, BlockBuilderBuilder};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_INFO};
-use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sc_transaction_pool_api::{InPoolTransaction, ReadyTransactions, TransactionPool};
 use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
 use sp_blockchain::{ApplyExtrinsicFailed::Validity, Error::ApplyExtrinsicFailed, HeaderBackend};
 use sp_consensus::{DisableProofRecording, EnableProofRecording, ProofRecording, Proposal};
@@ -39,6 +45,7 @@ use sp_core::traits::SpawnNamed;
 use sp_inherents::InherentData;
 use sp_runtime::{
 	traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
 	Digest, Percent, SaturatedConversion,
 };
 use std::{marker::PhantomData, pin::Pin, sync::Arc, time};
@@ -84,6 +91,13 @@ pub struct ProposerFactory<A, C, PR> {
 	telemetry: Option<TelemetryHandle>,
 	/// When estimating the block size, should the proof be included?
 	include_proof_in_block_size_estimation: bool,
+	/// Order in which the ready transactions are pushed.
+	packing_strategy: PackingStrategy,
+	/// Should the estimated build time of the header extension be kept out of the time spent on
+	/// transactions?
+	reserve_header_extension_time: bool,
+	/// Should the grid be built while the transactions are pushed?
+	precompute_header_extension: bool,
 	/// phantom member to pin the `ProofRecording` type.
 	_phantom: PhantomData<PR>,
 }
@@ -109,6 +123,9 @@ impl<A, C> ProposerFactory<A, C, DisableProofRecording> {
 			telemetry,
 			client,
 			include_proof_in_block_size_estimation: false,
+			packing_strategy: PackingStrategy::default(),
+			reserve_header_extension_time: false,
+			precompute_header_extension: false,
 			_phantom: PhantomData,
 		}
 	}
@@ -137,6 +154,9 @@ impl<A, C> ProposerFactory<A, C, EnableProofRecording> {
 			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			include_proof_in_block_size_estimation: true,
+			packing_strategy: PackingStrategy::default(),
+			reserve_header_extension_time: false,
+			precompute_header_extension: false,
 			_phantom: PhantomData,
 		}
 	}
@@ -174,6 +194,35 @@ impl<A, C, PR> ProposerFactory<A, C, PR> {
 	pub fn set_soft_deadline(&mut self, percent: Percent) {
 		self.soft_deadline_percent = percent;
 	}
+
+	/// Set the order in which the ready transactions are pushed.
+	///
+	/// The default is [`PackingStrategy::Priority`], the order of the transaction pool. The other
+	/// strategies share the grid between the applications submitting data, see
+	/// [`PackingStrategy`].
+	pub fn set_packing_strategy(&mut self, strategy: PackingStrategy) {
+		self.packing_strategy = strategy;
+	}
+
+	/// Set whether the estimated build time of the header extension is reserved.
+	///
+	/// The Kate commitments are built when the block is finalized and take longer as the grid
+	/// grows. When enabled, the proposer stops adding data once the estimated build time of the
+	/// resulting grid would not fit before the deadline. The estimate is calibrated by the blocks
+	/// authored by this node, see `avail_base::header_extension_cost`. Disabled by default.
+	pub fn set_reserve_header_extension_time(&mut self, reserve: bool) {
+		self.reserve_header_extension_time = reserve;
+	}
+
+	/// Set whether the grid and its commitments are built while the transactions are pushed.
+	///
+	/// When enabled, the data pushed so far is built on a worker thread each time a row of the
+	/// grid is completed, and `finalize_block` reuses the latest build if no data was pushed
+	/// after it. This cuts the finalization of large blocks at the cost of speculative builds.
+	/// It is disabled by default.
+	pub fn set_precompute_header_extension(&mut self, precompute: bool) {
+		self.precompute_header_extension = precompute;
+	}
 }
 
 impl<Block, C, A, PR> ProposerFactory<A, C, PR>
@@ -186,7 +235,8 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	C::Api: ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block>,
+	C::Api:
+		ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block> + DataAvailApi<Block>,
 {
 	fn init_with_now(
 		&mut self,
@@ -213,6 +263,9 @@ where
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			packing_strategy: self.packing_strategy.clone(),
+			reserve_header_extension_time: self.reserve_header_extension_time,
+			precompute_header_extension: self.precompute_header_extension,
 		};
 
 		proposer
@@ -230,7 +283,8 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	C::Api: ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block>,
+	C::Api:
+		ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block> + DataAvailApi<Block>,
 	PR: ProofRecording,
 {
 	type CreateProposer = future::Ready<Result<Self::Proposer, Self::Error>>;
@@ -257,6 +311,9 @@ pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR> {
 	include_proof_in_block_size_estimation: bool,
 	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
+	packing_strategy: PackingStrategy,
+	reserve_header_extension_time: bool,
+	precompute_header_extension: bool,
 	_phantom: PhantomData<PR>,
 }
 
@@ -271,7 +328,8 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	C::Api: ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block>,
+	C::Api:
+		ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block> + DataAvailApi<Block>,
 	PR: ProofRecording,
 {
 	type Proposal =
@@ -328,7 +386,8 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	C::Api: ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block>,
+	C::Api:
+		ApiExt<Block> + BlockBuilderApi<Block> + PostInherentsProvider<Block> + DataAvailApi<Block>,
 	PR: ProofRecording,
 {
 	async fn propose_with(
@@ -358,7 +417,8 @@ where
 
 		self.apply_post_inherents(&mut block_builder)?;
 
-		let (block, storage_changes, proof) = block_builder.build()?.into_inner();
+		let (block, storage_changes, proof) =
+			header_extension_cost::authoring(|| block_builder.build())?.into_inner();
 		let block_took = block_timer.elapsed();
 
 		let proof =
@@ -475,7 +535,7 @@ where
 		let mut t2 =
 			futures_timer::Delay::new(deadline.saturating_duration_since((self.now)()) / 8).fuse();
 
-		let mut pending_iterator = select! {
+		let pending_iterator = select! {
 			res = t1 => res,
 			_ = t2 => {
 				warn!(target: LOG_TARGET,
@@ -487,6 +547,32 @@ where
 			},
 		};
 
+		let block_length = self
+			.client
+			.runtime_api()
+			.block_length(self.parent_hash)
+			.map_err(sp_blockchain::Error::RuntimeApiError)?;
+
+		// Collecting the ready transactions gets the same share of the time left as waiting for
+		// the pool, so that most of it is still left to push them.
+		let reordered = self.packing_strategy != PackingStrategy::Priority;
+		let mut pending_iterator: Box<dyn ReadyTransactions<Item = _>> = if reordered {
+			let reorder_deadline =
+				(self.now)() + deadline.saturating_duration_since((self.now)()) / 8;
+			Box::new(self.reorder(pending_iterator, &block_length, reorder_deadline))
+		} else {
+			pending_iterator
+		};
+
+		// Padded scalars pushed so far and the estimated build time of their header extension.
+		let mut da_scalars = 0u32;
+		let mut header_extension_time = time::Duration::ZERO;
+		// The runtime fills the padding with the default seed unless `secure_padding_fill` is
+		// enabled, in which case the precomputed grid is not used.
+		let mut precomputer = self
+			.precompute_header_extension
+			.then(|| GridPrecomputer::new(block_length.clone(), Seed::default()));
+
 		let block_size_limit = block_size_limit.unwrap_or(self.default_block_size_limit);
 
 		debug!(target: LOG_TARGET, "Attempting to push transactions from the pool.");
@@ -506,7 +592,7 @@ where
 			};
 
 			let now = (self.now)();
-			if now > deadline {
+			if now + header_extension_time > deadline {
 				debug!(
 					target: LOG_TARGET,
 					"Consensus deadline reached when pushing block transactions, \
@@ -518,6 +604,25 @@ where
 			let pending_tx_data = pending_tx.data().clone();
 			let pending_tx_hash = pending_tx.hash().clone();
 
+			let da_scalars_with_tx = self
+				.reserve_header_extension_time
+				.then(|| packing::da_transaction_info(&pending_tx_data))
+				.flatten()
+				.map(|(_, scalars)| da_scalars.saturating_add(scalars));
+			if let Some(scalars) = da_scalars_with_tx {
+				let time_with_tx = Self::header_extension_time(&block_length, scalars);
+				if now + time_with_tx > deadline {
+					pending_iterator.report_invalid(&pending_tx);
+					debug!(
+						target: LOG_TARGET,
+						"[{:?}] Not enough time left to commit to its data ({:?}), skipping.",
+						pending_tx_hash,
+						time_with_tx,
+					);
+					continue;
+				}
+			}
+
 			let block_size =
 				block_builder.estimate_block_size(self.include_proof_in_block_size_estimation);
 			if block_size + pending_tx_data.encoded_size() > block_size_limit {
@@ -548,10 +653,22 @@ where
 				}
 			}
 
+			let submitted = precomputer
+				.as_ref()
+				.and_then(|_| packing::submitted_data(&pending_tx_data));
+
 			trace!(target: LOG_TARGET, "[{:?}] Pushing to the block.", pending_tx_hash);
 			match sc_block_builder::BlockBuilder::push(block_builder, pending_tx_data) {
 				Ok(()) => {
 					transaction_pushed = true;
+					if let (Some(precomputer), Some((app_id, data))) = (&mut precomputer, submitted)
+					{
+						precomputer.push(app_id, data);
+					}
+					if let Some(scalars) = da_scalars_with_tx {
+						da_scalars = scalars;
+						header_extension_time = Self::header_extension_time(&block_length, scalars);
+					}
 					debug!(target: LOG_TARGET, "[{:?}] Pushed to the block.", pending_tx_hash);
 				},
 				Err(ApplyExtrinsicFailed(Validity(e))) if e.exhausted_resources() => {
@@ -575,6 +692,15 @@ where
 						break EndProposingReason::HitBlockWeightLimit;
 					}
 				},
+				Err(ApplyExtrinsicFailed(Validity(TransactionValidityError::Invalid(
+					InvalidTransaction::Future,
+				)))) if reordered => {
+					debug!(
+						target: LOG_TARGET,
+						"[{:?}] Reordered before the transaction it depends on, skipping.",
+						pending_tx_hash
+					);
+				},
 				Err(e) => {
 					pending_iterator.report_invalid(&pending_tx);
 					debug!(
@@ -593,10 +719,57 @@ where
 			);
 		}
 
+		if let Some(precomputer) = precomputer {
+			precomputer.finish();
+		}
+
 		self.transaction_pool.remove_invalid(&unqueue_invalid);
 		Ok(end_reason)
 	}
 
+	/// Collects the ready transactions until `deadline` and orders them following the
+	/// packing strategy, within the grid of the parent block. Transactions which are not
+	/// collected by then stay in the pool for the next blocks.
+	fn reorder<I>(
+		&self,
+		ready: I,
+		block_length: &BlockLength,
+		deadline: time::Instant,
+	) -> Reordered<I::Item>
+	where
+		I: Iterator<Item = Arc<A::InPoolTransaction>>,
+	{
+		let capacity = block_length.rows.0.saturating_mul(block_length.cols.0);
+
+		let ready = ready
+			.take_while(|_| (self.now)() < deadline)
+			.collect::<Vec<_>>();
+		let ready_len = ready.len();
+		let ordered = packing::reorder(ready, &self.packing_strategy, capacity, |tx| {
+			packing::da_transaction_info(tx.data())
+		});
+		debug!(
+			target: LOG_TARGET,
+			"Reordered ready transactions with {:?}, {} of {} kept.",
+			self.packing_strategy,
+			ordered.len(),
+			ready_len,
+		);
+
+		Reordered(ordered.into_iter())
+	}
+
+	/// Estimated build time of the header extension of a grid holding `scalars` padded scalars.
+	fn header_extension_time(block_length: &BlockLength, scalars: u32) -> time::Duration {
+		let (rows, cols) = header_extension_cost::grid_dims(
+			scalars,
+			MIN_WIDTH as u32,
+			block_length.rows.0,
+			block_length.cols.0,
+		);
+		header_extension_cost::estimate(rows, cols)
+	}
+
 	/// Prints a summary and does telemetry + metrics.
 	///
 	/// - `block`: The block that was build.
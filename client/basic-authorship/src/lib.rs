@@ -17,5 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 mod basic_authorship;
+mod packing;
 
-pub use crate::basic_authorship::{Proposer, ProposerFactory, DEFAULT_BLOCK_SIZE_LIMIT};
+pub use crate::{
+	basic_authorship::{Proposer, ProposerFactory, DEFAULT_BLOCK_SIZE_LIMIT},
+	packing::PackingStrategy,
+};
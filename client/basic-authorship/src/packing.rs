@@ -0,0 +1,286 @@
+//! DA-aware ordering of the ready transactions.
+//!
+//! By default the proposer pushes the transactions in the priority order of the pool, so a
+//! single busy application can fill the whole grid. With [`PackingStrategy::WeightedRoundRobin`]
+//! or [`PackingStrategy::MaxMinFairness`], the ready `submit_data` transactions are grouped by
+//! [`AppId`] and interleaved until the capacity of the grid is reached. Other transactions keep
+//! their position in the priority order.
+
+use avail_core::{traits::GetAppId, AppId};
+use codec::{Decode, Encode};
+use da_runtime::{Extrinsic as DaExtrinsic, RuntimeCall};
+use frame_system::header_builder::precompute::padded_scalars;
+use sc_transaction_pool_api::ReadyTransactions;
+use std::collections::{BTreeMap, VecDeque};
+
+/// How the proposer orders the ready transactions of the pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PackingStrategy {
+	/// Transactions are pushed in the priority order of the pool.
+	#[default]
+	Priority,
+	/// Each application in turn gets as many `submit_data` transactions as its weight, `1` for
+	/// applications without a weight.
+	WeightedRoundRobin { weights: BTreeMap<AppId, u32> },
+	/// The next `submit_data` transaction is taken from the application that has been given the
+	/// fewest scalars so far.
+	MaxMinFairness,
+}
+
+impl PackingStrategy {
+	/// Plain round robin, every application having the same weight.
+	pub fn round_robin() -> Self {
+		Self::WeightedRoundRobin {
+			weights: BTreeMap::new(),
+		}
+	}
+}
+
+/// Returns the application and the number of scalars used by `xt` if it submits data.
+pub fn da_transaction_info<E: Encode>(xt: &E) -> Option<(AppId, u32)> {
+	let (xt, len) = xt
+		.using_encoded(|encoded| {
+			DaExtrinsic::decode(&mut &encoded[..]).map(|xt| (xt, encoded.len()))
+		})
+		.ok()?;
+	if !matches!(
+		xt.function,
+		RuntimeCall::DataAvailability(da_control::Call::submit_data { .. })
+	) {
+		return None;
+	}
+
+	// Like `CheckAppId`, the whole extrinsic is padded to scalars.
+	let scalars = padded_scalars(len);
+	Some((xt.app_id(), scalars.try_into().unwrap_or(u32::MAX)))
+}
+
+/// Returns the application and the data submitted by `xt`, as committed in the header.
+pub fn submitted_data<E: Encode>(xt: &E) -> Option<(AppId, Vec<u8>)> {
+	let xt = xt
+		.using_encoded(|mut encoded| DaExtrinsic::decode(&mut encoded))
+		.ok()?;
+	let app_id = xt.app_id();
+	match xt.function {
+		RuntimeCall::DataAvailability(da_control::Call::submit_data { data })
+			if !data.is_empty() =>
+		{
+			Some((app_id, data.into_inner()))
+		},
+		_ => None,
+	}
+}
+
+/// Orders `transactions` following `strategy`, leaving the non-DA ones at their position.
+///
+/// `da_info` classifies the transactions, see [`da_transaction_info`]. DA transactions that do
+/// not fit in the `capacity` of the grid, in scalars, are left out.
+pub fn reorder<T>(
+	transactions: Vec<T>,
+	strategy: &PackingStrategy,
+	capacity: u32,
+	da_info: impl Fn(&T) -> Option<(AppId, u32)>,
+) -> Vec<T> {
+	if *strategy == PackingStrategy::Priority {
+		return transactions;
+	}
+
+	let mut scheduler = FairScheduler::new(strategy.clone(), capacity);
+	let slots = transactions
+		.into_iter()
+		.map(|tx| match da_info(&tx) {
+			Some((app_id, scalars)) => {
+				scheduler.push(app_id, scalars, tx);
+				None
+			},
+			None => Some(tx),
+		})
+		.collect::<Vec<_>>();
+
+	// DA transactions take the slots of DA transactions, in the order of the scheduler.
+	slots
+		.into_iter()
+		.filter_map(|slot| slot.or_else(|| scheduler.pop()))
+		.collect()
+}
+
+/// Ready transactions in the order given by [`reorder`].
+///
+/// Dependencies between the transactions are not tracked anymore, so a transaction pushed
+/// before the one it depends on simply fails and is retried in a later block.
+pub(crate) struct Reordered<T>(pub std::vec::IntoIter<T>);
+
+impl<T> Iterator for Reordered<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<T> {
+		self.0.next()
+	}
+}
+
+impl<T> ReadyTransactions for Reordered<T> {
+	fn report_invalid(&mut self, _tx: &Self::Item) {}
+}
+
+/// Interleaves the transactions of several applications within the capacity of the grid.
+pub struct FairScheduler<T> {
+	strategy: PackingStrategy,
+	remaining: u32,
+	queues: BTreeMap<AppId, VecDeque<(u32, T)>>,
+	/// Applications in round robin order, the current one first.
+	ring: VecDeque<AppId>,
+	/// Transactions the current application can still take in this round.
+	credit: Option<u32>,
+	/// Scalars given to each application so far.
+	allocated: BTreeMap<AppId, u64>,
+}
+
+impl<T> FairScheduler<T> {
+	pub fn new(strategy: PackingStrategy, capacity: u32) -> Self {
+		Self {
+			strategy,
+			remaining: capacity,
+			queues: BTreeMap::new(),
+			ring: VecDeque::new(),
+			credit: None,
+			allocated: BTreeMap::new(),
+		}
+	}
+
+	/// Queues a transaction of `app_id` using `scalars` scalars, after the previous ones of the
+	/// same application.
+	pub fn push(&mut self, app_id: AppId, scalars: u32, tx: T) {
+		let queue = self.queues.entry(app_id).or_default();
+		if queue.is_empty() && !self.ring.contains(&app_id) {
+			self.ring.push_back(app_id);
+		}
+		queue.push_back((scalars, tx));
+	}
+
+	/// Returns the next transaction to push, `None` when no queued transaction fits anymore.
+	pub fn pop(&mut self) -> Option<T> {
+		match self.strategy {
+			PackingStrategy::Priority | PackingStrategy::WeightedRoundRobin { .. } => {
+				self.next_round_robin()
+			},
+			PackingStrategy::MaxMinFairness => self.next_max_min(),
+		}
+	}
+
+	fn weight(&self, app_id: &AppId) -> u32 {
+		match &self.strategy {
+			PackingStrategy::WeightedRoundRobin { weights } => {
+				weights.get(app_id).copied().unwrap_or(1).max(1)
+			},
+			_ => 1,
+		}
+	}
+
+	/// Pops the first transaction of `app_id` that still fits, dropping the ones before it.
+	fn pop_fitting(&mut self, app_id: &AppId) -> Option<(u32, T)> {
+		let queue = self.queues.get_mut(app_id)?;
+		while let Some((scalars, _)) = queue.front() {
+			if *scalars <= self.remaining {
+				let (scalars, tx) = queue.pop_front()?;
+				self.remaining -= scalars;
+				*self.allocated.entry(*app_id).or_default() += u64::from(scalars);
+				return Some((scalars, tx));
+			}
+			queue.pop_front();
+		}
+		None
+	}
+
+	fn next_round_robin(&mut self) -> Option<T> {
+		while let Some(app_id) = self.ring.front().copied() {
+			let Some((_, tx)) = self.pop_fitting(&app_id) else {
+				self.ring.pop_front();
+				self.credit = None;
+				continue;
+			};
+
+			let weight = self.weight(&app_id);
+			let credit = self.credit.get_or_insert(weight);
+			*credit -= 1;
+			if *credit == 0 {
+				self.credit = None;
+				self.ring.rotate_left(1);
+			}
+			return Some(tx);
+		}
+		None
+	}
+
+	fn next_max_min(&mut self) -> Option<T> {
+		loop {
+			let app_id = self
+				.queues
+				.iter()
+				.filter(|(_, queue)| !queue.is_empty())
+				.map(|(app_id, _)| *app_id)
+				.min_by_key(|app_id| self.allocated.get(app_id).copied().unwrap_or_default())?;
+
+			if let Some((_, tx)) = self.pop_fitting(&app_id) {
+				return Some(tx);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn schedule(strategy: PackingStrategy, capacity: u32, txs: &[(u32, u32)]) -> Vec<usize> {
+		let txs = txs.iter().copied().enumerate().collect::<Vec<_>>();
+		reorder(txs, &strategy, capacity, |(_, (app, scalars))| {
+			(*app != 0).then_some((AppId(*app), *scalars))
+		})
+		.into_iter()
+		.map(|(idx, _)| idx)
+		.collect()
+	}
+
+	#[test]
+	fn priority_keeps_pool_order() {
+		let txs = [(1, 1), (1, 1), (2, 1), (0, 0)];
+		assert_eq!(
+			schedule(PackingStrategy::Priority, 0, &txs),
+			vec![0, 1, 2, 3]
+		);
+	}
+
+	#[test]
+	fn round_robin_interleaves_applications() {
+		// App 1 submitted first and most, app 0 is used for non-DA transactions.
+		let txs = [(1, 1), (1, 1), (1, 1), (0, 0), (2, 1), (3, 1)];
+		assert_eq!(
+			schedule(PackingStrategy::round_robin(), 100, &txs),
+			vec![0, 4, 5, 3, 1, 2]
+		);
+
+		let weights = BTreeMap::from([(AppId(1), 2)]);
+		assert_eq!(
+			schedule(PackingStrategy::WeightedRoundRobin { weights }, 100, &txs),
+			vec![0, 1, 4, 3, 5, 2]
+		);
+	}
+
+	#[test]
+	fn max_min_favours_smaller_allocations() {
+		let txs = [(1, 10), (1, 10), (2, 3), (2, 3), (2, 3), (3, 20)];
+		assert_eq!(
+			schedule(PackingStrategy::MaxMinFairness, 100, &txs),
+			vec![0, 2, 5, 3, 4, 1]
+		);
+	}
+
+	#[test]
+	fn transactions_beyond_capacity_are_left_out() {
+		let txs = [(1, 4), (2, 4), (1, 4), (2, 1), (0, 0)];
+		assert_eq!(
+			schedule(PackingStrategy::round_robin(), 9, &txs),
+			vec![0, 1, 3, 4]
+		);
+	}
+}
@@ -0,0 +1,507 @@
+//! # Per-application blob index
+//!
+//! Optional index of the submitted blobs, kept in the aux store of the client. It maps each
+//! application to the blocks containing its blobs, and the hash of each blob to its locations.
+//! Blocks are indexed on import, and the blocks imported while the index was disabled are indexed
+//! by a background backfill.
+//!
+//! The aux store has no prefix iteration, so the locations of a blob are stored under their index
+//! next to a count, each location being keyed by `(blob_hash, block_hash, tx_index)` so that it
+//! is indexed once. Indexing a blob writes a fixed number of entries however often it was
+//! submitted.
+//!
+//! Blocks of every fork are indexed, queries only return the ones of the canonical chain.
+use crate::da_cmd::native_tx_data;
+
+use avail_core::OpaqueExtrinsic;
+use codec::{Decode, Encode};
+use da_runtime::NodeBlock as Block;
+use parking_lot::Mutex;
+use sc_client_api::{AuxStore, BlockBackend};
+use serde::Serialize;
+use sp_blockchain::{Error as ClientError, HeaderBackend, Result as ClientResult};
+use sp_core::{blake2_256, H256};
+use std::{
+	collections::{btree_map::Entry, BTreeMap, BTreeSet},
+	sync::Arc,
+};
+
+mod rpc;
+pub use rpc::{BlobIndexApiServer, BlobIndexRpc};
+
+/// Blocks of an application listed under the same key.
+const BUCKET_SIZE: u32 = 1_024;
+/// Blocks backfilled between two progress logs.
+const BACKFILL_PROGRESS_EVERY: u32 = 10_000;
+
+const APP_BUCKET_PREFIX: &[u8] = b"blob_index_app";
+const BLOCK_BLOBS_PREFIX: &[u8] = b"blob_index_block";
+/// Number of locations of a blob.
+const BLOB_COUNT_PREFIX: &[u8] = b"blob_index_blob_count";
+/// Location of a blob by its index.
+const BLOB_PREFIX: &[u8] = b"blob_index_blob";
+/// Index of a location of a blob, by block and transaction.
+const BLOB_LOCATION_PREFIX: &[u8] = b"blob_index_blob_location";
+/// Highest block number indexed on import or scheduled for backfill.
+const COVERED_KEY: &[u8] = b"blob_index_covered";
+/// Ranges of blocks still to backfill.
+const BACKFILL_KEY: &[u8] = b"blob_index_backfill";
+
+/// Blob submitted in a block.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedBlob {
+	pub app_id: u32,
+	pub tx_index: u32,
+	/// `blake2_256` of the data, as in the `DataSubmitted` event.
+	pub hash: H256,
+}
+
+/// Block of the canonical chain containing blobs of an application.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppBlock {
+	pub number: u32,
+	pub hash: H256,
+	pub blobs: Vec<IndexedBlob>,
+}
+
+/// Location of a blob.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobLocation {
+	pub block_number: u32,
+	pub block_hash: H256,
+	pub app_id: u32,
+	pub tx_index: u32,
+}
+
+/// Chain read by the index, implemented by the client.
+pub trait BlobChain: AuxStore {
+	/// Number of the best block.
+	fn best_number(&self) -> u32;
+	/// Hash of the block `number` of the canonical chain.
+	fn canonical_hash(&self, number: u32) -> ClientResult<Option<H256>>;
+	/// Blobs submitted in the block `hash`, `None` if its body was pruned.
+	fn block_blobs(&self, number: u32, hash: H256) -> ClientResult<Option<Vec<IndexedBlob>>>;
+}
+
+impl<C> BlobChain for C
+where
+	C: AuxStore + HeaderBackend<Block> + BlockBackend<Block>,
+{
+	fn best_number(&self) -> u32 {
+		self.info().best_number
+	}
+
+	fn canonical_hash(&self, number: u32) -> ClientResult<Option<H256>> {
+		self.hash(number)
+	}
+
+	fn block_blobs(&self, number: u32, hash: H256) -> ClientResult<Option<Vec<IndexedBlob>>> {
+		Ok(self
+			.block_body(hash)?
+			.map(|body| submitted_blobs(number, body)))
+	}
+}
+
+pub struct BlobIndex<C> {
+	client: Arc<C>,
+	/// Serializes the updates of the index, guarding the value of `COVERED_KEY`.
+	covered: Mutex<u32>,
+}
+
+/// Blobs submitted in `extrinsics`, extracted with the native runtime.
+pub fn submitted_blobs(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>) -> Vec<IndexedBlob> {
+	native_tx_data(block_number, extrinsics)
+		.submitted
+		.into_iter()
+		.map(|submitted| IndexedBlob {
+			app_id: submitted.id.0,
+			tx_index: submitted.tx_index,
+			hash: H256(blake2_256(&submitted.data)),
+		})
+		.collect()
+}
+
+impl<C: AuxStore> BlobIndex<C> {
+	/// Indexes the blobs of a block which has just been imported.
+	pub fn on_import(&self, number: u32, hash: H256, blobs: &[IndexedBlob]) -> ClientResult<()> {
+		let mut covered = self.covered.lock();
+		let mut writes = self.index_writes(number, hash, blobs)?;
+		if number > *covered {
+			writes.push((COVERED_KEY.to_vec(), number.encode()));
+		}
+		self.write(writes)?;
+		*covered = (*covered).max(number);
+		Ok(())
+	}
+
+	/// Aux store entries adding the blobs of block `hash` to the index.
+	fn index_writes(
+		&self,
+		number: u32,
+		hash: H256,
+		blobs: &[IndexedBlob],
+	) -> ClientResult<Vec<(Vec<u8>, Vec<u8>)>> {
+		let mut by_app = BTreeMap::<u32, Vec<IndexedBlob>>::new();
+		let mut counts = BTreeMap::<H256, u32>::new();
+		let mut indexed = BTreeSet::new();
+		let mut writes = Vec::new();
+		for blob in blobs {
+			by_app.entry(blob.app_id).or_default().push(blob.clone());
+
+			let location_key = blob_location_key(blob.hash, hash, blob.tx_index);
+			if !indexed.insert(location_key.clone()) || self.read::<u32>(&location_key)?.is_some() {
+				continue;
+			}
+			let count = match counts.entry(blob.hash) {
+				Entry::Occupied(count) => count.into_mut(),
+				Entry::Vacant(vacant) => {
+					vacant.insert(self.read(&blob_count_key(blob.hash))?.unwrap_or_default())
+				},
+			};
+			let location = BlobLocation {
+				block_number: number,
+				block_hash: hash,
+				app_id: blob.app_id,
+				tx_index: blob.tx_index,
+			};
+			writes.push((blob_key(blob.hash, *count), location.encode()));
+			writes.push((location_key, count.encode()));
+			*count += 1;
+		}
+
+		for (app_id, blobs) in by_app {
+			let bucket_key = app_bucket_key(app_id, number / BUCKET_SIZE);
+			let mut bucket = self
+				.read::<Vec<(u32, H256)>>(&bucket_key)?
+				.unwrap_or_default();
+			if !bucket.contains(&(number, hash)) {
+				bucket.push((number, hash));
+				bucket.sort();
+			}
+			writes.push((bucket_key, bucket.encode()));
+			writes.push((block_blobs_key(app_id, hash), blobs.encode()));
+		}
+		writes.extend(
+			counts
+				.into_iter()
+				.map(|(blob_hash, count)| (blob_count_key(blob_hash), count.encode())),
+		);
+		Ok(writes)
+	}
+
+	fn read<T: Decode>(&self, key: &[u8]) -> ClientResult<Option<T>> {
+		self.client
+			.get_aux(key)?
+			.map(|value| {
+				T::decode(&mut &value[..])
+					.map_err(|e| ClientError::Backend(format!("Corrupted blob index entry: {e}")))
+			})
+			.transpose()
+	}
+
+	fn write(&self, writes: Vec<(Vec<u8>, Vec<u8>)>) -> ClientResult<()> {
+		let insert = writes
+			.iter()
+			.map(|(key, value)| (&key[..], &value[..]))
+			.collect::<Vec<_>>();
+		self.client.insert_aux(&insert, &[])
+	}
+}
+
+impl<C: BlobChain> BlobIndex<C> {
+	/// Opens the index, scheduling the backfill of the blocks imported since it was last enabled.
+	pub fn open(client: Arc<C>) -> ClientResult<Self> {
+		let index = Self {
+			client,
+			covered: Mutex::new(0),
+		};
+		let best = index.client.best_number();
+		let covered = index.read::<u32>(COVERED_KEY)?;
+		let mut pending = index.pending()?;
+
+		let start = covered.map_or(1, |covered| covered + 1);
+		if start <= best {
+			log::info!("Blob index: blocks #{start}..=#{best} scheduled for backfill");
+			pending.push((start, best));
+			index.write(vec![
+				(BACKFILL_KEY.to_vec(), pending.encode()),
+				(COVERED_KEY.to_vec(), best.encode()),
+			])?;
+		}
+		*index.covered.lock() = covered.unwrap_or_default().max(best);
+		Ok(index)
+	}
+
+	/// Ranges of blocks not indexed yet.
+	pub fn pending(&self) -> ClientResult<Vec<(u32, u32)>> {
+		Ok(self.read(BACKFILL_KEY)?.unwrap_or_default())
+	}
+
+	/// Indexes the blocks of the canonical chain scheduled for backfill, resuming where it was
+	/// interrupted. Blocks whose body was pruned are skipped.
+	pub fn backfill(&self) -> ClientResult<()> {
+		let mut skipped = 0u32;
+		while let Some(&(start, end)) = self.pending()?.first() {
+			for number in start..=end {
+				let hash = self.client.canonical_hash(number)?;
+				let blobs = match hash {
+					Some(hash) => self.client.block_blobs(number, hash)?,
+					None => None,
+				};
+				let indexed = match (hash, blobs) {
+					(Some(hash), Some(blobs)) => Some((hash, blobs)),
+					_ => {
+						skipped += 1;
+						None
+					},
+				};
+
+				// Progress is saved along with each block containing blobs.
+				let has_blobs = indexed.as_ref().is_some_and(|(_, blobs)| !blobs.is_empty());
+				if has_blobs || number == end || number % BACKFILL_PROGRESS_EVERY == 0 {
+					let _covered = self.covered.lock();
+					let mut writes = match &indexed {
+						Some((hash, blobs)) => self.index_writes(number, *hash, blobs)?,
+						None => Vec::new(),
+					};
+					let mut pending = self.pending()?;
+					if number == end {
+						pending.remove(0);
+					} else {
+						pending[0].0 = number + 1;
+					}
+					writes.push((BACKFILL_KEY.to_vec(), pending.encode()));
+					self.write(writes)?;
+				}
+				if number % BACKFILL_PROGRESS_EVERY == 0 {
+					log::info!("Blob index backfilled up to block #{number} of #{end}");
+				}
+			}
+		}
+
+		if skipped > 0 {
+			log::warn!("Blob index backfill skipped {skipped} blocks without body");
+		}
+		log::info!("Blob index backfill completed");
+		Ok(())
+	}
+
+	/// Blocks of the canonical chain in `from..=to` containing blobs of `app_id`.
+	pub fn app_blocks(&self, app_id: u32, from: u32, to: u32) -> ClientResult<Vec<AppBlock>> {
+		let mut blocks = Vec::new();
+		for bucket in from / BUCKET_SIZE..=to / BUCKET_SIZE {
+			let bucket = self
+				.read::<Vec<(u32, H256)>>(&app_bucket_key(app_id, bucket))?
+				.unwrap_or_default();
+			for (number, hash) in bucket {
+				if number < from || number > to || !self.is_canonical(number, hash)? {
+					continue;
+				}
+				let blobs = self
+					.read(&block_blobs_key(app_id, hash))?
+					.unwrap_or_default();
+				blocks.push(AppBlock {
+					number,
+					hash,
+					blobs,
+				});
+			}
+		}
+		Ok(blocks)
+	}
+
+	/// Locations in the canonical chain of the blobs whose hash is `blob_hash`.
+	pub fn find_blob(&self, blob_hash: H256) -> ClientResult<Vec<BlobLocation>> {
+		let count = self
+			.read::<u32>(&blob_count_key(blob_hash))?
+			.unwrap_or_default();
+		let mut locations = Vec::new();
+		for index in 0..count {
+			let Some(location) = self.read::<BlobLocation>(&blob_key(blob_hash, index))? else {
+				continue;
+			};
+			if self.is_canonical(location.block_number, location.block_hash)? {
+				locations.push(location);
+			}
+		}
+		Ok(locations)
+	}
+
+	fn is_canonical(&self, number: u32, hash: H256) -> ClientResult<bool> {
+		Ok(self.client.canonical_hash(number)? == Some(hash))
+	}
+}
+
+fn app_bucket_key(app_id: u32, bucket: u32) -> Vec<u8> {
+	(APP_BUCKET_PREFIX, app_id, bucket).encode()
+}
+
+fn block_blobs_key(app_id: u32, hash: H256) -> Vec<u8> {
+	(BLOCK_BLOBS_PREFIX, app_id, hash).encode()
+}
+
+fn blob_count_key(blob_hash: H256) -> Vec<u8> {
+	(BLOB_COUNT_PREFIX, blob_hash).encode()
+}
+
+fn blob_key(blob_hash: H256, index: u32) -> Vec<u8> {
+	(BLOB_PREFIX, blob_hash, index).encode()
+}
+
+fn blob_location_key(blob_hash: H256, block_hash: H256, tx_index: u32) -> Vec<u8> {
+	(BLOB_LOCATION_PREFIX, blob_hash, block_hash, tx_index).encode()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	#[derive(Default)]
+	struct MockChain {
+		aux: Mutex<BTreeMap<Vec<u8>, Vec<u8>>>,
+		/// Hashes of the canonical blocks, from block #1.
+		canonical: Vec<H256>,
+		blobs: HashMap<H256, Vec<IndexedBlob>>,
+		/// Block whose blobs can not be read, once.
+		fail_at: Mutex<Option<u32>>,
+	}
+
+	impl MockChain {
+		fn new(blocks: u8) -> Self {
+			Self {
+				canonical: (1..=blocks).map(H256::repeat_byte).collect(),
+				..Default::default()
+			}
+		}
+	}
+
+	impl AuxStore for MockChain {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item = &'a &'b [u8]>,
+		>(
+			&self,
+			insert: I,
+			delete: D,
+		) -> ClientResult<()> {
+			let mut aux = self.aux.lock();
+			for (key, value) in insert {
+				aux.insert(key.to_vec(), value.to_vec());
+			}
+			for key in delete {
+				aux.remove(*key);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+			Ok(self.aux.lock().get(key).cloned())
+		}
+	}
+
+	impl BlobChain for MockChain {
+		fn best_number(&self) -> u32 {
+			self.canonical.len() as u32
+		}
+
+		fn canonical_hash(&self, number: u32) -> ClientResult<Option<H256>> {
+			Ok(number
+				.checked_sub(1)
+				.and_then(|idx| self.canonical.get(idx as usize))
+				.copied())
+		}
+
+		fn block_blobs(&self, number: u32, hash: H256) -> ClientResult<Option<Vec<IndexedBlob>>> {
+			let mut fail_at = self.fail_at.lock();
+			if *fail_at == Some(number) {
+				*fail_at = None;
+				return Err(ClientError::Backend("Interrupted".into()));
+			}
+			Ok(Some(self.blobs.get(&hash).cloned().unwrap_or_default()))
+		}
+	}
+
+	fn blob(app_id: u32, tx_index: u32, data: u8) -> IndexedBlob {
+		IndexedBlob {
+			app_id,
+			tx_index,
+			hash: H256::repeat_byte(data),
+		}
+	}
+
+	fn numbers(blocks: Vec<AppBlock>) -> Vec<u32> {
+		blocks.into_iter().map(|block| block.number).collect()
+	}
+
+	#[test]
+	fn imported_blobs_are_indexed_once() {
+		let index = BlobIndex::open(Arc::new(MockChain::new(2))).unwrap();
+		let (h1, h2) = (H256::repeat_byte(1), H256::repeat_byte(2));
+
+		index
+			.on_import(1, h1, &[blob(1, 1, 0xA), blob(2, 2, 0xB)])
+			.unwrap();
+		index.on_import(2, h2, &[blob(1, 1, 0xA)]).unwrap();
+		// A block imported again is not indexed twice.
+		index.on_import(1, h1, &[blob(1, 1, 0xA)]).unwrap();
+
+		let locations = index.find_blob(H256::repeat_byte(0xA)).unwrap();
+		let blocks = locations
+			.iter()
+			.map(|location| (location.block_number, location.block_hash))
+			.collect::<Vec<_>>();
+		assert_eq!(blocks, vec![(1, h1), (2, h2)]);
+		assert_eq!(numbers(index.app_blocks(1, 0, 10).unwrap()), vec![1, 2]);
+		assert_eq!(numbers(index.app_blocks(2, 0, 10).unwrap()), vec![1]);
+		assert_eq!(index.read::<u32>(COVERED_KEY).unwrap(), Some(2));
+	}
+
+	#[test]
+	fn queries_only_return_the_canonical_chain() {
+		let index = BlobIndex::open(Arc::new(MockChain::new(1))).unwrap();
+		let (canonical, fork) = (H256::repeat_byte(1), H256::repeat_byte(0xF));
+
+		index.on_import(1, fork, &[blob(1, 1, 0xA)]).unwrap();
+		index.on_import(1, canonical, &[blob(1, 2, 0xA)]).unwrap();
+
+		let locations = index.find_blob(H256::repeat_byte(0xA)).unwrap();
+		assert_eq!(locations.len(), 1);
+		assert_eq!(locations[0].block_hash, canonical);
+		assert_eq!(locations[0].tx_index, 2);
+
+		let blocks = index.app_blocks(1, 0, 10).unwrap();
+		assert_eq!(blocks.len(), 1);
+		assert_eq!(blocks[0].hash, canonical);
+	}
+
+	#[test]
+	fn backfill_resumes_where_it_was_interrupted() {
+		let mut chain = MockChain::new(5);
+		chain
+			.blobs
+			.insert(H256::repeat_byte(3), vec![blob(1, 1, 0xA)]);
+		chain
+			.blobs
+			.insert(H256::repeat_byte(5), vec![blob(1, 1, 0xB)]);
+		*chain.fail_at.lock() = Some(4);
+
+		let index = BlobIndex::open(Arc::new(chain)).unwrap();
+		assert_eq!(index.pending().unwrap(), vec![(1, 5)]);
+
+		// Progress is saved with block #3, which contains blobs.
+		assert!(index.backfill().is_err());
+		assert_eq!(index.pending().unwrap(), vec![(4, 5)]);
+		assert_eq!(numbers(index.app_blocks(1, 1, 5).unwrap()), vec![3]);
+
+		index.backfill().unwrap();
+		assert!(index.pending().unwrap().is_empty());
+		assert_eq!(numbers(index.app_blocks(1, 1, 5).unwrap()), vec![3, 5]);
+	}
+}
@@ -0,0 +1,95 @@
+use super::{AppBlock, BlobChain, BlobIndex, BlobLocation};
+
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_core::H256;
+use std::sync::Arc;
+
+/// Maximal number of blocks of a `blobIndex_appBlocks` range.
+const MAX_RANGE: u32 = 10_000;
+
+/// Queries of the per-application blob index.
+#[rpc(server)]
+pub trait BlobIndexApi {
+	/// Blocks of the canonical chain in `from..=to` containing blobs of `app_id`.
+	#[method(name = "blobIndex_appBlocks")]
+	async fn app_blocks(&self, app_id: u32, from: u32, to: u32) -> RpcResult<Vec<AppBlock>>;
+
+	/// Blocks of the canonical chain and tx indices of the blobs whose `blake2_256` is
+	/// `blob_hash`.
+	#[method(name = "blobIndex_findBlob")]
+	async fn find_blob(&self, blob_hash: H256) -> RpcResult<Vec<BlobLocation>>;
+}
+
+pub struct BlobIndexRpc<C> {
+	index: Arc<BlobIndex<C>>,
+}
+
+impl<C> BlobIndexRpc<C> {
+	pub fn new(index: Arc<BlobIndex<C>>) -> Self {
+		Self { index }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The block range is invalid or not indexed yet.
+	InvalidRange,
+	/// The index could not be read.
+	IndexError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::InvalidRange => 1,
+			Error::IndexError => 2,
+		}
+	}
+}
+
+fn rpc_err(error: Error, message: String) -> ErrorObjectOwned {
+	ErrorObject::owned(error.into(), message, None::<()>)
+}
+
+fn index_err(e: sp_blockchain::Error) -> ErrorObjectOwned {
+	rpc_err(
+		Error::IndexError,
+		format!("Failed to read the blob index: {e}"),
+	)
+}
+
+#[async_trait]
+impl<C> BlobIndexApiServer for BlobIndexRpc<C>
+where
+	C: BlobChain + Send + Sync + 'static,
+{
+	async fn app_blocks(&self, app_id: u32, from: u32, to: u32) -> RpcResult<Vec<AppBlock>> {
+		if from > to || to - from >= MAX_RANGE {
+			return Err(rpc_err(
+				Error::InvalidRange,
+				format!("Range #{from}..=#{to} must contain 1 to {MAX_RANGE} blocks"),
+			));
+		}
+
+		let pending = self.index.pending().map_err(index_err)?;
+		if let Some((start, end)) = pending
+			.into_iter()
+			.find(|(start, end)| *start <= to && from <= *end)
+		{
+			return Err(rpc_err(
+				Error::InvalidRange,
+				format!("Blocks #{start}..=#{end} are not indexed yet"),
+			));
+		}
+
+		self.index.app_blocks(app_id, from, to).map_err(index_err)
+	}
+
+	async fn find_blob(&self, blob_hash: H256) -> RpcResult<Vec<BlobLocation>> {
+		self.index.find_blob(blob_hash).map_err(index_err)
+	}
+}
@@ -7,9 +7,9 @@ use da_runtime::{
 };
 use frame_system::limits::BlockLength;
 use pallet_vector::constants::{
-	get_poseidon_hash_for_period, BROADCASTER, BROADCASTER_DOMAIN, FINALITY_THRESHOLD,
-	GENESIS_TIME, GENESIS_VALIDATOR_ROOT, PERIOD, ROTATE_FUNCTION_ID, ROTATE_VK, SECONDS_PER_SLOT,
-	SLOTS_PER_PERIOD, SOURCE_CHAIN_ID, STEP_FUNCTION_ID, STEP_VK,
+	get_poseidon_hash_for_period, BROADCASTER, BROADCASTER_DOMAIN, CHALLENGE_DELAY,
+	FINALITY_THRESHOLD, GENESIS_TIME, GENESIS_VALIDATOR_ROOT, PERIOD, ROTATE_FUNCTION_ID,
+	ROTATE_VK, SECONDS_PER_SLOT, SLOTS_PER_PERIOD, SOURCE_CHAIN_ID, STEP_FUNCTION_ID, STEP_VK,
 };
 use sc_telemetry::TelemetryEndpoints;
 use serde_json::{json, Value};
@@ -52,7 +52,7 @@ pub fn to_telemetry_endpoint(s: String) -> TelemetryEndpoints {
 }
 
 /// Generates a default endowed accounts.
-fn dev_endowed_accounts() -> Vec<(AccountId, Balance)> {
+pub(super) fn dev_endowed_accounts() -> Vec<(AccountId, Balance)> {
 	DEFAULT_ENDOWED_SEEDS
 		.iter()
 		.map(|seed| {
@@ -64,7 +64,7 @@ fn dev_endowed_accounts() -> Vec<(AccountId, Balance)> {
 		.collect()
 }
 
-fn make_data_avail_config(owner: AccountId) -> DataAvailabilityConfig {
+pub(super) fn make_data_avail_config(owner: AccountId) -> DataAvailabilityConfig {
 	let app_keys = INIT_APP_IDS
 		.iter()
 		.map(|(id, app)| (app.as_bytes().to_vec(), (owner.clone(), *id)))
@@ -134,6 +134,7 @@ pub fn runtime_genesis_config(
 		"vector": {
 			"broadcaster": BROADCASTER,
 			"broadcasterDomain": BROADCASTER_DOMAIN,
+			"challengeDelay": CHALLENGE_DELAY,
 			"finalityThreshold": FINALITY_THRESHOLD,
 			"functionIds": (STEP_FUNCTION_ID, ROTATE_FUNCTION_ID),
 			"genesisTime": GENESIS_TIME,
@@ -0,0 +1,451 @@
+//! Local networks described by a TOML or JSON file, built by the `build-devnet` sub-command.
+//!
+//! The output directory receives the plain and raw chain specs, the keys of each validator in
+//! `keys/<node>.json` and a `<node>.sh` script which inserts the session keys of the validator
+//! into its keystore and starts it. All the nodes run on the local host and connect to the first
+//! one.
+use super::*;
+use avail_core::{
+	currency::AVAIL, BlockLengthColumns, BlockLengthRows, BLOCK_CHUNK_SIZE, DA_DISPATCH_RATIO,
+};
+use da_runtime::{
+	constants::{
+		self,
+		da::{MaxAppKeyLength, MaxBlockCols, MaxBlockRows, MinBlockCols, MinBlockRows},
+	},
+	wasm_binary_unwrap, AccountId, Balance, SessionKeys,
+};
+use frame_system::limits::BlockLength;
+use sc_chain_spec::ChainType;
+use serde::{Deserialize, Serialize};
+use sp_core::{crypto::Ss58Codec, sr25519, Get, Pair, H256};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// Seeds of the first validators, then named `Validator<N>`.
+const VALIDATOR_SEEDS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Node key of the first validator, the boot node of the others. Node keys of a devnet are not
+/// secret: the one of the validator `n` is `n + 1`.
+const BOOTNODE_KEY: u64 = 1;
+/// Peer id of `BOOTNODE_KEY`.
+const BOOTNODE_PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+/// Description of a local network.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DevnetSpec {
+	pub name: String,
+	pub id: String,
+	#[serde(default = "default_validators")]
+	pub validators: u32,
+	/// The first validator by default.
+	#[serde(default)]
+	pub sudo: Option<AccountRef>,
+	/// Funds added to the development accounts and the validators.
+	#[serde(default)]
+	pub accounts: Vec<FundedAccount>,
+	/// Registered next to the reserved application keys.
+	#[serde(default)]
+	pub app_keys: Vec<AppKey>,
+	/// The maximal dimensions by default.
+	#[serde(default)]
+	pub block_length: Option<BlockDimensions>,
+	#[serde(default)]
+	pub vector: VectorSpec,
+	#[serde(default)]
+	pub ports: Ports,
+}
+
+/// Account given by its SS58 address, or by a seed derived like `//<seed>`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AccountRef(pub AccountId);
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FundedAccount {
+	pub account: AccountRef,
+	/// In AVAIL.
+	pub balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppKey {
+	pub id: u32,
+	pub name: String,
+	pub owner: AccountRef,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockDimensions {
+	pub rows: u32,
+	pub cols: u32,
+}
+
+/// Overrides of the genesis config of the vector bridge.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VectorSpec {
+	pub step_function_id: Option<H256>,
+	pub rotate_function_id: Option<H256>,
+	/// Path of the JSON verification key, relative to the description.
+	pub step_verification_key: Option<PathBuf>,
+	/// Path of the JSON verification key, relative to the description.
+	pub rotate_verification_key: Option<PathBuf>,
+	pub whitelisted_domains: Option<Vec<u32>>,
+}
+
+/// Ports of the first node, incremented for each next node.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Ports {
+	pub p2p: u16,
+	pub rpc: u16,
+	pub prometheus: u16,
+}
+
+impl Default for Ports {
+	fn default() -> Self {
+		Self {
+			p2p: 30333,
+			rpc: 9944,
+			prometheus: 9615,
+		}
+	}
+}
+
+fn default_validators() -> u32 {
+	1
+}
+
+impl TryFrom<String> for AccountRef {
+	type Error = String;
+
+	fn try_from(account: String) -> Result<Self, Self::Error> {
+		if let Ok(id) = AccountId::from_ss58check(&account) {
+			return Ok(Self(id));
+		}
+		let pair = sr25519::Pair::from_string(&format!("//{account}"), None)
+			.map_err(|e| format!("Invalid account `{account}`: {e:?}"))?;
+		Ok(Self(pair.public().into()))
+	}
+}
+
+/// Keys of a validator, written to `keys/<node>.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeKeys {
+	name: String,
+	suri: String,
+	controller: AccountId,
+	stash: AccountId,
+	session_keys: SessionKeys,
+	node_key: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	peer_id: Option<&'static str>,
+}
+
+/// Builds the network described in `spec_path` into `output`, the start scripts running
+/// `node_binary`.
+pub fn build(spec_path: &Path, output: &Path, node_binary: &Path) -> Result<(), String> {
+	let spec = DevnetSpec::load(spec_path)?;
+	if spec.validators == 0 {
+		return Err("A devnet needs at least one validator".into());
+	}
+	let seeds = (0..spec.validators).map(validator_seed).collect::<Vec<_>>();
+	let base_dir = spec_path.parent().unwrap_or(Path::new("."));
+	let chain_spec = ChainSpec::builder(wasm_binary_unwrap(), Default::default())
+		.with_name(&spec.name)
+		.with_id(&spec.id)
+		.with_chain_type(ChainType::Local)
+		.with_genesis_config_patch(spec.genesis(&seeds, base_dir)?)
+		.with_protocol_id(PROTOCOL_ID)
+		.with_properties(chain_properties())
+		.build();
+
+	fs::create_dir_all(output.join("keys")).map_err(|e| io_fail(output, e))?;
+	write(&output.join("chain-spec.json"), &chain_spec.as_json(false)?)?;
+	write(
+		&output.join("chain-spec.raw.json"),
+		&chain_spec.as_json(true)?,
+	)?;
+
+	for (idx, seed) in seeds.iter().enumerate() {
+		let name = seed.to_lowercase();
+		let keys = AuthorityKeys::from_seed(seed);
+		let node_keys = NodeKeys {
+			name: name.clone(),
+			suri: format!("//{seed}"),
+			controller: keys.controller,
+			stash: keys.stash,
+			session_keys: keys.session_keys,
+			node_key: format!("{:064x}", BOOTNODE_KEY + idx as u64),
+			peer_id: (idx == 0).then_some(BOOTNODE_PEER_ID),
+		};
+		let json = serde_json::to_string_pretty(&node_keys)
+			.map_err(|e| format!("Keys serialization failed: {e}"))?;
+		write(&output.join("keys").join(format!("{name}.json")), &json)?;
+
+		let script_path = output.join(format!("{name}.sh"));
+		write(
+			&script_path,
+			&spec.start_script(idx, &node_keys, node_binary),
+		)?;
+		make_executable(&script_path)?;
+	}
+
+	println!(
+		"Devnet `{}` with {} validators written to {}",
+		spec.name,
+		spec.validators,
+		output.display()
+	);
+	Ok(())
+}
+
+impl DevnetSpec {
+	/// Parses a `.toml` or a `.json` description.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| io_fail(path, e))?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+			Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+			_ => Err(format!(
+				"Unknown format of {}, expected a `.toml` or a `.json` file",
+				path.display()
+			)),
+		}
+		.map_err(|e| format!("Invalid devnet description {}: {e}", path.display()))
+	}
+
+	/// Genesis config patch of the network, verification keys being read relative to `base_dir`.
+	fn genesis(&self, seeds: &[String], base_dir: &Path) -> Result<Value, String> {
+		let validators = seeds
+			.iter()
+			.map(|seed| AuthorityKeys::from_seed(seed))
+			.collect::<Vec<_>>();
+		let sudo = match &self.sudo {
+			Some(sudo) => sudo.0.clone(),
+			None => validators[0].controller.clone(),
+		};
+
+		let mut balances = dev_endowed_accounts()
+			.into_iter()
+			.collect::<BTreeMap<AccountId, Balance>>();
+		for keys in &validators {
+			for account in [&keys.controller, &keys.stash] {
+				balances
+					.entry(account.clone())
+					.or_insert(constants::staking::MIN_VALIDATOR_BOND * 100);
+			}
+		}
+		for funded in &self.accounts {
+			let balance = balances.entry(funded.account.0.clone()).or_default();
+			*balance = balance.saturating_add(Balance::from(funded.balance) * AVAIL);
+		}
+
+		let mut genesis = runtime_genesis_config(
+			sudo.clone(),
+			vec![sudo.clone()],
+			vec![sudo.clone()],
+			validators,
+		);
+		genesis["balances"]["balances"] = to_value(balances.into_iter().collect::<Vec<_>>())?;
+		genesis["dataAvailability"]["appKeys"] = to_value(self.app_keys(sudo)?)?;
+		if let Some(dimensions) = &self.block_length {
+			genesis["system"]["blockLength"] = to_value(dimensions.block_length()?)?;
+		}
+		self.vector.patch(&mut genesis["vector"], base_dir)?;
+		Ok(genesis)
+	}
+
+	/// The reserved application keys, owned by `sudo`, and the described ones.
+	fn app_keys(&self, sudo: AccountId) -> Result<Vec<(Vec<u8>, (AccountId, u32))>, String> {
+		let mut app_keys = make_data_avail_config(sudo).app_keys;
+		let mut ids = app_keys
+			.iter()
+			.map(|(_, (_, id))| *id)
+			.collect::<BTreeSet<_>>();
+		let mut names = app_keys
+			.iter()
+			.map(|(name, _)| name.clone())
+			.collect::<BTreeSet<_>>();
+
+		for key in &self.app_keys {
+			let name = key.name.as_bytes().to_vec();
+			if name.is_empty() || name.len() > MaxAppKeyLength::get() as usize {
+				return Err(format!(
+					"The name of app {} must have 1 to {} bytes",
+					key.id,
+					MaxAppKeyLength::get()
+				));
+			}
+			if !ids.insert(key.id) {
+				return Err(format!("App id {} is already registered", key.id));
+			}
+			if !names.insert(name.clone()) {
+				return Err(format!("App key `{}` is already registered", key.name));
+			}
+			app_keys.push((name, (key.owner.0.clone(), key.id)));
+		}
+		Ok(app_keys)
+	}
+
+	/// Script inserting the session keys of the validator `idx` and starting it.
+	fn start_script(&self, idx: usize, keys: &NodeKeys, node_binary: &Path) -> String {
+		let idx = idx as u16;
+		let bootnodes = match idx {
+			0 => String::new(),
+			_ => format!(
+				" \\\n\t--bootnodes /ip4/127.0.0.1/tcp/{}/p2p/{BOOTNODE_PEER_ID}",
+				self.ports.p2p
+			),
+		};
+		let name = shell_quote(&keys.name);
+		let suri = shell_quote(&keys.suri);
+
+		format!(
+			r#"#!/bin/sh
+# Starts the validator {name} of `{chain}`. Extra arguments are passed to the node, whose
+# binary can be overridden with `$AVAIL_NODE`.
+set -e
+DIR=$(cd "$(dirname "$0")" && pwd)
+NODE=${{AVAIL_NODE:-{binary}}}
+CHAIN="$DIR/chain-spec.raw.json"
+BASE_PATH="$DIR/data/"{name}
+
+for key in babe:sr25519 imon:sr25519 audi:sr25519 gran:ed25519; do
+	"$NODE" key insert --chain "$CHAIN" --base-path "$BASE_PATH" \
+		--key-type "${{key%%:*}}" --scheme "${{key#*:}}" --suri {suri}
+done
+
+exec "$NODE" --chain "$CHAIN" --base-path "$BASE_PATH" --name {name} --validator \
+	--port {p2p} --rpc-port {rpc} --prometheus-port {prometheus} \
+	--node-key {node_key}{bootnodes} \
+	"$@"
+"#,
+			chain = self.name.replace('\n', " "),
+			binary = shell_quote(&node_binary.to_string_lossy()),
+			p2p = self.ports.p2p.saturating_add(idx),
+			rpc = self.ports.rpc.saturating_add(idx),
+			prometheus = self.ports.prometheus.saturating_add(idx),
+			node_key = keys.node_key,
+		)
+	}
+}
+
+impl BlockDimensions {
+	/// Block length of these dimensions, within the bounds of the runtime.
+	fn block_length(&self) -> Result<BlockLength, String> {
+		let (rows, cols) = (BlockLengthRows(self.rows), BlockLengthColumns(self.cols));
+		if rows < MinBlockRows::get()
+			|| rows > MaxBlockRows::get()
+			|| cols < MinBlockCols::get()
+			|| cols > MaxBlockCols::get()
+		{
+			return Err(format!(
+				"Block dimensions {}x{} out of the bounds {}..={} rows, {}..={} cols",
+				self.rows,
+				self.cols,
+				MinBlockRows::get().0,
+				MaxBlockRows::get().0,
+				MinBlockCols::get().0,
+				MaxBlockCols::get().0,
+			));
+		}
+		BlockLength::with_normal_ratio(rows, cols, BLOCK_CHUNK_SIZE, DA_DISPATCH_RATIO)
+			.map_err(|e| format!("Invalid block dimensions: {e:?}"))
+	}
+}
+
+impl VectorSpec {
+	fn patch(&self, vector: &mut Value, base_dir: &Path) -> Result<(), String> {
+		if let Some(id) = self.step_function_id {
+			vector["functionIds"][0] = to_value(id)?;
+		}
+		if let Some(id) = self.rotate_function_id {
+			vector["functionIds"][1] = to_value(id)?;
+		}
+		if let Some(path) = &self.step_verification_key {
+			vector["stepVerificationKey"] = to_value(read_verification_key(base_dir, path)?)?;
+		}
+		if let Some(path) = &self.rotate_verification_key {
+			vector["rotateVerificationKey"] = to_value(read_verification_key(base_dir, path)?)?;
+		}
+		if let Some(domains) = &self.whitelisted_domains {
+			vector["whitelistedDomains"] = to_value(domains)?;
+		}
+		Ok(())
+	}
+}
+
+fn validator_seed(idx: u32) -> String {
+	match VALIDATOR_SEEDS.get(idx as usize) {
+		Some(seed) => seed.to_string(),
+		None => format!("Validator{}", idx + 1),
+	}
+}
+
+fn read_verification_key(base_dir: &Path, path: &Path) -> Result<Vec<u8>, String> {
+	let path = base_dir.join(path);
+	let key = fs::read_to_string(&path).map_err(|e| io_fail(&path, e))?;
+	serde_json::from_str::<Value>(&key)
+		.map_err(|e| format!("Invalid verification key {}: {e}", path.display()))?;
+	Ok(key.into_bytes())
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, String> {
+	serde_json::to_value(value).map_err(|e| format!("Genesis serialization failed: {e}"))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+	fs::write(path, content).map_err(|e| io_fail(path, e))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+	use std::os::unix::fs::PermissionsExt;
+	fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| io_fail(path, e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+	Ok(())
+}
+
+fn io_fail(path: &Path, e: std::io::Error) -> String {
+	format!("{}: {e}", path.display())
+}
+
+/// Single-quotes `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+	format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_example_devnet_creation() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../misc/devnet/example.toml");
+		let spec = DevnetSpec::load(&path).unwrap();
+		let seeds = (0..spec.validators).map(validator_seed).collect::<Vec<_>>();
+		let genesis = spec.genesis(&seeds, path.parent().unwrap()).unwrap();
+
+		ChainSpec::builder(wasm_binary_unwrap(), Default::default())
+			.with_name(&spec.name)
+			.with_id(&spec.id)
+			.with_chain_type(ChainType::Local)
+			.with_genesis_config_patch(genesis)
+			.build()
+			.build_storage()
+			.unwrap();
+	}
+}
@@ -1,5 +1,6 @@
 mod common;
 mod definitions;
+pub mod devnet;
 
 use common::*;
 pub use definitions::*;
@@ -16,6 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::{
+	da_block_import::SampledDaSync,
+	da_cmd::{AuditDaCmd, DaSubcommand, ExportAppDataCmd},
+};
+use std::path::PathBuf;
+
 /// An overarching CLI command definition.
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -41,6 +47,47 @@ pub struct Cli {
 	#[arg(long)] /*, conflicts_with_all = &["validator"] */
 	pub unsafe_da_sync: bool,
 
+	/// Check only a sample of the commitment rows of imported blocks during sync.
+	///
+	/// The data root of every block is still checked. Blocks are fully checked again once the
+	/// node reaches the tip of the chain.
+	#[arg(long, conflicts_with = "unsafe_da_sync")]
+	pub sampled_da_sync: bool,
+
+	/// Number of random commitment rows checked in a block by `--sampled-da-sync`.
+	#[arg(long, default_value_t = 4, requires = "sampled_da_sync")]
+	pub sampled_da_sync_rows: u32,
+
+	/// Check the commitment rows of one block out of this number with `--sampled-da-sync`.
+	#[arg(
+		long,
+		default_value_t = 1,
+		requires = "sampled_da_sync",
+		value_parser = clap::value_parser!(u32).range(1..)
+	)]
+	pub sampled_da_sync_every: u32,
+
+	/// Rebuild the header extension of blocks queued during sync on a pool of workers, ahead of
+	/// their import.
+	///
+	/// Blocks whose native rebuild does not match their header are checked again at import.
+	#[arg(long, conflicts_with_all = ["unsafe_da_sync", "sampled_da_sync"])]
+	pub parallel_da_sync: bool,
+
+	/// Number of workers of `--parallel-da-sync`, the number of CPUs by default.
+	#[arg(
+		long,
+		requires = "parallel_da_sync",
+		value_parser = clap::value_parser!(u32).range(1..)
+	)]
+	pub parallel_da_sync_workers: Option<u32>,
+
+	/// Index the blobs of each application, queried by the `blobIndex_*` RPCs.
+	///
+	/// Blocks imported while the index was disabled are indexed in the background.
+	#[arg(long)]
+	pub blob_index: bool,
+
 	/// Provides storage monitoring options on the node
 	#[clap(flatten)]
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
@@ -58,12 +105,71 @@ pub struct Cli {
 	/// Max size cannot exceed 10_000
 	#[arg(long, default_value_t = 64, value_parser=kate_max_cells_size_upper_bound)]
 	pub kate_max_cells_size: usize,
+
+	/// Order in which the block author pushes the ready `submit_data` transactions.
+	#[arg(long, value_enum, default_value_t = DaPacking::Priority)]
+	pub da_packing: DaPacking,
+
+	/// Weight of an application for the `round-robin` packing, as `APP_ID=WEIGHT`.
+	///
+	/// Applications without a weight have a weight of 1.
+	#[arg(long, value_parser = parse_app_weight)]
+	pub da_packing_weight: Vec<(u32, u32)>,
+
+	/// Build the grid and its commitments while authoring a block, instead of when it is
+	/// finalized.
+	#[arg(long)]
+	pub precompute_header_extension: bool,
+
+	/// Keep the estimated build time of the header extension out of the time the block author
+	/// spends on transactions.
+	#[arg(long)]
+	pub reserve_header_extension_time: bool,
+}
+
+/// Packing strategies of the block author, see `sc_basic_authorship::PackingStrategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DaPacking {
+	/// Transaction pool priority order.
+	Priority,
+	/// Weighted round robin between applications.
+	RoundRobin,
+	/// Max-min fairness between applications, in padded scalars.
+	MaxMin,
+}
+
+impl Cli {
+	/// Sampled check of imported blocks during sync, if enabled.
+	pub fn sampled_da_sync(&self) -> Option<SampledDaSync> {
+		self.sampled_da_sync.then_some(SampledDaSync {
+			rows: self.sampled_da_sync_rows,
+			every: self.sampled_da_sync_every,
+		})
+	}
+
+	/// Number of workers checking the blocks queued during sync, if enabled.
+	pub fn parallel_da_sync(&self) -> Option<usize> {
+		self.parallel_da_sync
+			.then(|| match self.parallel_da_sync_workers {
+				Some(workers) => workers as usize,
+				None => std::thread::available_parallelism().map_or(1, |cpus| cpus.get()),
+			})
+	}
 }
 
 fn kate_max_cells_size_upper_bound(s: &str) -> Result<usize, String> {
 	clap_num::number_range(s, 0, 10_000)
 }
 
+fn parse_app_weight(s: &str) -> Result<(u32, u32), String> {
+	let (app_id, weight) = s
+		.split_once('=')
+		.ok_or_else(|| format!("Expected APP_ID=WEIGHT, got `{s}`"))?;
+	let app_id = app_id.parse().map_err(|e| format!("Invalid app id: {e}"))?;
+	let weight = weight.parse().map_err(|e| format!("Invalid weight: {e}"))?;
+	Ok((app_id, weight))
+}
+
 /// Possible subcommands of the main binary.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, clap::Subcommand)]
@@ -76,6 +182,10 @@ pub enum Subcommand {
 	)]
 	Inspect(node_inspect::cli::InspectCmd),
 	*/
+	/// Data Availability tools working on the local database.
+	#[command(subcommand)]
+	Da(DaSubcommand),
+
 	/// Sub-commands concerned with benchmarking.
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[command(subcommand)]
@@ -105,15 +215,24 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Build the chain spec, the keys and the start scripts of a local network.
+	BuildDevnet(BuildDevnetCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
+	/// Check the data roots and header extensions of a range of blocks of the local database.
+	AuditDa(AuditDaCmd),
+
 	/// Export blocks.
 	ExportBlocks(sc_cli::ExportBlocksCmd),
 
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the blobs of an application, with their data proofs, from the local database.
+	ExportAppData(ExportAppDataCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -126,3 +245,18 @@ pub enum Subcommand {
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
 }
+
+/// The `build-devnet` command, see `chains::devnet`.
+#[derive(Debug, clap::Parser)]
+pub struct BuildDevnetCmd {
+	/// Description of the network, a `.toml` or a `.json` file.
+	pub spec: PathBuf,
+
+	/// Directory receiving the chain specs, the keys and the start scripts.
+	#[arg(long, default_value = "devnet")]
+	pub output: PathBuf,
+
+	/// Node binary run by the start scripts, this binary by default.
+	#[arg(long)]
+	pub node_binary: Option<PathBuf>,
+}
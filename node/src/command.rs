@@ -31,6 +31,7 @@ use {
 
 use crate::{
 	cli::{Cli, Subcommand},
+	da_cmd::DaSubcommand,
 	service::{self, new_partial, FullClient},
 };
 
@@ -99,6 +100,10 @@ pub fn run() -> Result<()> {
 
 			runner.sync_run(|config| cmd.run::<Block, RuntimeApi, ExecutorDispatch>(config))
 		},*/
+		Some(Subcommand::Da(DaSubcommand::Inspect(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config))
+		},
 		Some(Subcommand::Benchmark(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 
@@ -197,6 +202,13 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
 		},
+		Some(Subcommand::BuildDevnet(cmd)) => {
+			let node_binary = match &cmd.node_binary {
+				Some(path) => path.clone(),
+				None => std::env::current_exe()?,
+			};
+			chains::devnet::build(&cmd.spec, &cmd.output, &node_binary).map_err(Into::into)
+		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -208,6 +220,9 @@ pub fn run() -> Result<()> {
 				} = new_partial(
 					&config,
 					cli.unsafe_da_sync,
+					cli.sampled_da_sync(),
+					cli.parallel_da_sync(),
+					false,
 					cli.kate_max_cells_size,
 					cli.kate_rpc_enabled,
 					cli.kate_rpc_metrics_enabled,
@@ -215,6 +230,10 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
+		Some(Subcommand::AuditDa(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config))
+		},
 		Some(Subcommand::ExportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -225,6 +244,9 @@ pub fn run() -> Result<()> {
 				} = new_partial(
 					&config,
 					cli.unsafe_da_sync,
+					cli.sampled_da_sync(),
+					cli.parallel_da_sync(),
+					false,
 					cli.kate_max_cells_size,
 					cli.kate_rpc_enabled,
 					cli.kate_rpc_metrics_enabled,
@@ -242,6 +264,9 @@ pub fn run() -> Result<()> {
 				} = new_partial(
 					&config,
 					cli.unsafe_da_sync,
+					cli.sampled_da_sync(),
+					cli.parallel_da_sync(),
+					false,
 					cli.kate_max_cells_size,
 					cli.kate_rpc_enabled,
 					cli.kate_rpc_metrics_enabled,
@@ -249,6 +274,10 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportAppData(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config))
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -260,6 +289,9 @@ pub fn run() -> Result<()> {
 				} = new_partial(
 					&config,
 					cli.unsafe_da_sync,
+					cli.sampled_da_sync(),
+					cli.parallel_da_sync(),
+					false,
 					cli.kate_max_cells_size,
 					cli.kate_rpc_enabled,
 					cli.kate_rpc_metrics_enabled,
@@ -282,6 +314,9 @@ pub fn run() -> Result<()> {
 				} = new_partial(
 					&config,
 					cli.unsafe_da_sync,
+					cli.sampled_da_sync(),
+					cli.parallel_da_sync(),
+					false,
 					cli.kate_max_cells_size,
 					cli.kate_rpc_enabled,
 					cli.kate_rpc_metrics_enabled,
@@ -4,17 +4,33 @@
 /// to Babe and Grandpa.
 /// It double-checks the **extension header** which contains the `Kate Commitment` and `Data
 /// Root`.
+use crate::{
+	blob_index::{submitted_blobs, BlobIndex},
+	da_cmd::native_tx_data,
+	da_pipeline::DaCheckPipeline,
+};
+
 use avail_base::metrics::avail::ImportBlockMetrics;
 use avail_core::{
-	ensure, header::HeaderExtension, BlockLengthColumns, BlockLengthRows, OpaqueExtrinsic,
-	BLOCK_CHUNK_SIZE,
+	ensure, header::HeaderExtension, AppExtrinsic, BlockLengthColumns, BlockLengthRows,
+	OpaqueExtrinsic, BLOCK_CHUNK_SIZE,
 };
+use da_control::kate::completeness::{COMMITMENT_SIZE, EXTENSION_FACTOR};
 use da_runtime::{
 	apis::{DataAvailApi, ExtensionBuilder},
 	Header as DaHeader,
 };
-use frame_system::limits::BlockLength;
+use frame_system::{
+	header_builder::{
+		hosted_header_builder,
+		sampled::{build_sampled_rows, SampledRows},
+		Seed,
+	},
+	limits::BlockLength,
+};
+use rand::seq::index::sample;
 
+use sc_client_api::AuxStore;
 use sc_consensus::{
 	block_import::{BlockCheckParams, BlockImport as BlockImportT, BlockImportParams},
 	ImportResult,
@@ -26,11 +42,28 @@ use sp_core::H256;
 use sp_runtime::traits::Block as BlockT;
 use std::{marker::PhantomData, sync::Arc, time::Instant};
 
+/// Sampled check of the header extension during the initial sync.
+///
+/// The data root and the length of the commitments are checked in every block, and the
+/// commitments of `rows` random rows of the extended grid are rebuilt in one block out of `every`.
+/// Once the node reaches the tip of the chain, blocks are fully checked again.
+#[derive(Clone, Copy, Debug)]
+pub struct SampledDaSync {
+	pub rows: u32,
+	pub every: u32,
+}
+
 pub struct BlockImport<B, C, I> {
 	client: Arc<C>,
 	inner: I,
 	// If true, it skips the DA block import check during sync only.
 	unsafe_da_sync: bool,
+	// If set, the DA block import check is sampled during sync only.
+	sampled_da_sync: Option<SampledDaSync>,
+	// If set, the DA block import check is done ahead of import during sync only.
+	da_pipeline: Option<Arc<DaCheckPipeline>>,
+	// If set, the blobs of imported blocks are indexed.
+	blob_index: Option<Arc<BlobIndex<C>>>,
 	_block: PhantomData<B>,
 }
 
@@ -42,11 +75,21 @@ where
 	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync,
 	C::Api: DataAvailApi<B> + ExtensionBuilder<B>,
 {
-	pub fn new(client: Arc<C>, inner: I, unsafe_da_sync: bool) -> Self {
+	pub fn new(
+		client: Arc<C>,
+		inner: I,
+		unsafe_da_sync: bool,
+		sampled_da_sync: Option<SampledDaSync>,
+		da_pipeline: Option<Arc<DaCheckPipeline>>,
+		blob_index: Option<Arc<BlobIndex<C>>>,
+	) -> Self {
 		Self {
 			client,
 			inner,
 			unsafe_da_sync,
+			sampled_da_sync,
+			da_pipeline,
+			blob_index,
 			_block: PhantomData,
 		}
 	}
@@ -107,8 +150,100 @@ where
 			block.header.extension == extension,
 			extension_mismatch(&block.header.extension, &extension)
 		);
+		ImportBlockMetrics::inc_full_da_checks();
 		Ok(())
 	}
+
+	/// Checks the data root and the commitments of some rows only.
+	///
+	/// The grid is rebuilt natively, so any difference with the header falls back to
+	/// `ensure_valid_header_extension`, which decides whether the block is invalid.
+	fn ensure_sampled_header_extension(
+		&self,
+		block: &BlockImportParams<B>,
+		sampled: SampledDaSync,
+	) -> Result<(), ConsensusError> {
+		let extrinsics = || block.body.clone().unwrap_or_default();
+		let block_number: u32 = block.header.number;
+		let parent_hash = <B as BlockT>::Hash::from(block.header.parent_hash);
+		let api = self.client.runtime_api();
+
+		// The data root is always checked.
+		let data_root = api
+			.build_data_root(parent_hash, block_number, extrinsics())
+			.map_err(data_root_fail)?;
+		let header_data_root = header_data_root(&block.header.extension);
+		let commitment = match &block.header.extension {
+			HeaderExtension::V3(ext) => &ext.commitment.commitment,
+		};
+		ensure!(
+			header_data_root == data_root,
+			data_root_mismatch(header_data_root, data_root)
+		);
+		let rows = u32::from(block.header.extension.rows());
+		let extended_rows = rows.saturating_mul(EXTENSION_FACTOR);
+		ensure!(
+			commitment.len() == extended_rows as usize * COMMITMENT_SIZE,
+			commitment_len_mismatch(extended_rows, commitment.len())
+		);
+
+		if block_number % sampled.every.max(1) != 0 {
+			ImportBlockMetrics::inc_sampled_da_checks(0);
+			return Ok(());
+		}
+
+		let Some(submitted) = sampled_submitted(block_number, extrinsics(), data_root, rows) else {
+			ImportBlockMetrics::inc_sampled_da_fallbacks();
+			return self.ensure_valid_header_extension(block);
+		};
+
+		// Odd rows are sampled too, the original rows do not check their commitments.
+		let amount = sampled.rows.min(extended_rows) as usize;
+		let sample_rows = sample(&mut rand::thread_rng(), extended_rows as usize, amount)
+			.into_iter()
+			.map(|row| row as u32)
+			.collect::<Vec<_>>();
+		let block_len = extension_block_len(&block.header.extension);
+		let built = build_sampled_rows(submitted, block_len, Seed::default(), &sample_rows)
+			.map_err(|e| ConsensusError::ClientImport(format!("Sampled rows failed: {e}")))?;
+
+		if !sampled_rows_match(&block.header.extension, &built) {
+			ImportBlockMetrics::inc_sampled_da_fallbacks();
+			return self.ensure_valid_header_extension(block);
+		}
+
+		ImportBlockMetrics::inc_sampled_da_checks(built.commitments.len() as u64);
+		Ok(())
+	}
+
+	/// Uses the result of the pipelined check of the block, if it matches its header.
+	///
+	/// Blocks which were not checked ahead of their import, whose native rebuild differs from
+	/// their header, or whose data root differs from the one built by the runtime of their parent,
+	/// are checked by `ensure_valid_header_extension`.
+	fn ensure_pipelined_header_extension(
+		&self,
+		block: &BlockImportParams<B>,
+		pipeline: &DaCheckPipeline,
+	) -> Result<(), ConsensusError> {
+		let body = block.body.as_deref().unwrap_or_default();
+		let hash = block.post_hash();
+		if pipeline.take(hash, block.header.number, body) == Some(true) {
+			let parent_hash = <B as BlockT>::Hash::from(block.header.parent_hash);
+			let data_root = self
+				.client
+				.runtime_api()
+				.build_data_root(parent_hash, block.header.number, body.to_vec())
+				.map_err(data_root_fail)?;
+			if header_data_root(&block.header.extension) == data_root {
+				ImportBlockMetrics::inc_pipelined_da_checks();
+				return Ok(());
+			}
+		}
+
+		ImportBlockMetrics::inc_pipelined_da_fallbacks();
+		self.ensure_valid_header_extension(block)
+	}
 }
 
 #[async_trait::async_trait]
@@ -117,7 +252,7 @@ where
 	B: BlockT<Extrinsic = OpaqueExtrinsic, Header = DaHeader, Hash = H256>,
 	I: BlockImportT<B> + Clone + Send + Sync,
 	I::Error: Into<ConsensusError>,
-	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + AuxStore + Send + Sync,
 	C::Api: DataAvailApi<B> + ExtensionBuilder<B>,
 {
 	type Error = ConsensusError;
@@ -140,12 +275,37 @@ where
 		let skip_sync = self.unsafe_da_sync && is_sync;
 		if !is_own && !skip_sync {
 			self.ensure_last_extrinsic_is_failed_send_message_txs(&block)?;
-			self.ensure_valid_header_extension(&block)?;
+			let pipeline = self.da_pipeline.as_deref().filter(|_| is_sync);
+			match (self.sampled_da_sync.filter(|_| is_sync), pipeline) {
+				(Some(sampled), _) => self.ensure_sampled_header_extension(&block, sampled)?,
+				(None, Some(pipeline)) => {
+					self.ensure_pipelined_header_extension(&block, pipeline)?
+				},
+				(None, None) => self.ensure_valid_header_extension(&block)?,
+			}
 		}
 
+		let blobs = self.blob_index.as_ref().and_then(|index| {
+			let body = block.body.clone()?;
+			let number = block.header.number;
+			Some((
+				index,
+				number,
+				block.post_hash(),
+				submitted_blobs(number, body),
+			))
+		});
+
 		// Next import block stage & metrics
 		let result = self.inner.import_block(block).await;
 		ImportBlockMetrics::observe_total_execution_time(import_block_start.elapsed());
+		if let (Ok(ImportResult::Imported(_)), Some((index, number, hash, blobs))) =
+			(&result, blobs)
+		{
+			if let Err(e) = index.on_import(number, hash, &blobs) {
+				log::warn!("Failed to index the blobs of block #{number} ({hash:?}): {e}");
+			}
+		}
 		result.map_err(Into::into)
 	}
 
@@ -163,13 +323,52 @@ impl<B, C, I: Clone> Clone for BlockImport<B, C, I> {
 			client: self.client.clone(),
 			inner: self.inner.clone(),
 			unsafe_da_sync: self.unsafe_da_sync,
+			sampled_da_sync: self.sampled_da_sync,
+			da_pipeline: self.da_pipeline.clone(),
+			blob_index: self.blob_index.clone(),
 			_block: PhantomData,
 		}
 	}
 }
 
+/// Data root committed in `extension`.
+pub(crate) fn header_data_root(extension: &HeaderExtension) -> H256 {
+	match extension {
+		HeaderExtension::V3(ext) => ext.commitment.data_root,
+	}
+}
+
+/// Data of the block to sample, extracted with the native runtime.
+///
+/// Returns `None` if the block has to be fully checked: without data, or if the native runtime does
+/// not extract the `data_root` built by the runtime of the chain.
+fn sampled_submitted(
+	block_number: u32,
+	extrinsics: Vec<OpaqueExtrinsic>,
+	data_root: H256,
+	rows: u32,
+) -> Option<Vec<AppExtrinsic>> {
+	let tx_data = native_tx_data(block_number, extrinsics);
+	let submitted = tx_data.to_app_extrinsics();
+	(!submitted.is_empty() && rows != 0 && tx_data.root() == data_root).then_some(submitted)
+}
+
+/// Whether the natively `built` rows match the header `extension`.
+fn sampled_rows_match(extension: &HeaderExtension, built: &SampledRows) -> bool {
+	let (app_lookup, commitment) = match extension {
+		HeaderExtension::V3(ext) => (&ext.app_lookup, &ext.commitment.commitment),
+	};
+	built.rows == extension.rows()
+		&& built.cols == extension.cols()
+		&& &built.lookup == app_lookup
+		&& built.commitments.iter().all(|(row, built)| {
+			let start = (*row as usize) * COMMITMENT_SIZE;
+			commitment.get(start..start + COMMITMENT_SIZE) == Some(built.as_slice())
+		})
+}
+
 /// Calculate block length from `extension`.
-fn extension_block_len(extension: &HeaderExtension) -> BlockLength {
+pub(crate) fn extension_block_len(extension: &HeaderExtension) -> BlockLength {
 	BlockLength::with_normal_ratio(
 		BlockLengthRows(extension.rows() as u32),
 		BlockLengthColumns(extension.cols() as u32),
@@ -185,6 +384,17 @@ fn extension_mismatch(imported: &HeaderExtension, generated: &HeaderExtension) -
 	ConsensusError::ClientImport(msg)
 }
 
+fn data_root_mismatch(imported: H256, generated: H256) -> ConsensusError {
+	let msg = format!("Data root does NOT match\nExpected: {imported:?}\nGenerated: {generated:?}");
+	ConsensusError::ClientImport(msg)
+}
+
+fn commitment_len_mismatch(extended_rows: u32, len: usize) -> ConsensusError {
+	let expected = extended_rows as usize * COMMITMENT_SIZE;
+	let msg = format!("Commitment length does NOT match\nExpected: {expected}\nFound: {len}");
+	ConsensusError::ClientImport(msg)
+}
+
 fn data_root_fail(e: ApiError) -> ConsensusError {
 	let msg = format!("Data root cannot be calculated: {e:?}");
 	ConsensusError::ClientImport(msg)
@@ -199,3 +409,83 @@ fn block_doesnt_contain_post_inherent() -> ConsensusError {
 	let msg = "Block does not contain post inherent".to_string();
 	ConsensusError::ClientImport(msg)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::da_cmd::tests::submit_data;
+	use avail_core::AppId;
+
+	fn block_len() -> BlockLength {
+		BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			BLOCK_CHUNK_SIZE,
+			sp_runtime::Perbill::from_percent(90),
+		)
+		.unwrap()
+	}
+
+	fn submitted(data: u8) -> Vec<AppExtrinsic> {
+		vec![
+			AppExtrinsic::new(AppId(1), vec![data; 100]),
+			AppExtrinsic::new(AppId(2), vec![data; 300]),
+		]
+	}
+
+	fn extension(submitted: Vec<AppExtrinsic>) -> HeaderExtension {
+		hosted_header_builder::build(submitted, H256::zero(), block_len(), 1, Seed::default())
+	}
+
+	fn sampled(submitted: Vec<AppExtrinsic>, rows: &[u32]) -> SampledRows {
+		build_sampled_rows(submitted, block_len(), Seed::default(), rows).unwrap()
+	}
+
+	#[test]
+	fn sampled_rows_of_the_same_data_match() {
+		let extension = extension(submitted(1));
+		let extended_rows = u32::from(extension.rows()) * EXTENSION_FACTOR;
+		let rows = (0..extended_rows).collect::<Vec<_>>();
+
+		assert!(sampled_rows_match(
+			&extension,
+			&sampled(submitted(1), &rows)
+		));
+	}
+
+	#[test]
+	fn mismatching_sampled_rows_fall_back_to_the_full_check() {
+		let extension = extension(submitted(1));
+
+		// Odd rows are not implied by the original ones.
+		let mut tampered = extension.clone();
+		match &mut tampered {
+			HeaderExtension::V3(ext) => {
+				ext.commitment.commitment[COMMITMENT_SIZE..2 * COMMITMENT_SIZE].fill(0)
+			},
+		}
+		assert!(sampled_rows_match(&tampered, &sampled(submitted(1), &[0])));
+		assert!(!sampled_rows_match(&tampered, &sampled(submitted(1), &[1])));
+
+		assert!(!sampled_rows_match(
+			&extension,
+			&sampled(submitted(2), &[0, 1])
+		));
+	}
+
+	#[test]
+	fn native_data_root_mismatch_falls_back_to_the_full_check() {
+		let extrinsics = vec![submit_data(1, b"abcd"), submit_data(2, b"efgh")];
+		let data_root = native_tx_data(1, extrinsics.clone()).root();
+
+		let submitted = sampled_submitted(1, extrinsics.clone(), data_root, 1).unwrap();
+		assert_eq!(submitted.len(), 2);
+
+		assert_eq!(
+			sampled_submitted(1, extrinsics.clone(), H256::repeat_byte(1), 1),
+			None
+		);
+		assert_eq!(sampled_submitted(1, extrinsics, data_root, 0), None);
+		assert_eq!(sampled_submitted(1, vec![], data_root, 1), None);
+	}
+}
@@ -0,0 +1,112 @@
+//! # Offline Data Availability tools
+//!
+//! Sub-commands which read the DA content of blocks straight from the local database, without
+//! starting the node.
+use crate::service::FullClient;
+
+use avail_base::data_root::{build_tx_data_from_opaque, TxData};
+use avail_core::OpaqueExtrinsic;
+use da_runtime::{apis::RuntimeApi, AccountId, NodeBlock as Block, Runtime};
+use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use std::sync::Arc;
+
+mod audit_da;
+mod export_app_data;
+mod inspect;
+pub use audit_da::AuditDaCmd;
+pub use export_app_data::ExportAppDataCmd;
+pub use inspect::InspectCmd;
+
+/// Data Availability sub-commands.
+#[derive(Debug, clap::Subcommand)]
+pub enum DaSubcommand {
+	/// Decode the header extension and the DA content of a block.
+	Inspect(InspectCmd),
+}
+
+/// Output format of the DA sub-commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+	/// Human-readable text.
+	Text,
+	/// JSON document.
+	Json,
+}
+
+/// Opens the client of the local database, without the network and consensus components.
+pub(crate) fn new_client(
+	config: &Configuration,
+) -> Result<(Arc<FullClient>, TaskManager), ServiceError> {
+	let executor = sc_service::new_native_or_wasm_executor(config);
+	let (client, _backend, _keystore, task_manager) =
+		sc_service::new_full_parts::<Block, RuntimeApi, _>(config, None, executor)?;
+	Ok((Arc::new(client), task_manager))
+}
+
+/// Extracts the DA content of `extrinsics` with the native runtime.
+pub(crate) fn native_tx_data(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>) -> TxData {
+	type Extractor = <Runtime as frame_system::Config>::TxDataExtractor;
+	type Extrinsic = <Runtime as frame_system::Config>::Extrinsic;
+	build_tx_data_from_opaque::<Extractor, Extrinsic, AccountId, _>(block_number, extrinsics)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use avail_core::AppId;
+	use codec::Encode;
+	use da_control::AppDataFor;
+	use da_runtime::{RuntimeCall, SignedExtra, SignedPayload, VERSION};
+	use pallet_transaction_payment::ChargeTransactionPayment;
+	use sp_core::{crypto::Pair, H256};
+	use sp_keyring::AccountKeyring::Alice;
+	use sp_runtime::generic::Era;
+
+	/// `submit_data` extrinsic of Alice, with `data` for `app_id`.
+	pub(crate) fn submit_data(app_id: u32, data: &[u8]) -> OpaqueExtrinsic {
+		let data = AppDataFor::<Runtime>::truncate_from(data.to_vec());
+		let function = RuntimeCall::DataAvailability(da_control::Call::submit_data { data });
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+			frame_system::CheckNonce::<Runtime>::from(0),
+			frame_system::CheckWeight::<Runtime>::new(),
+			ChargeTransactionPayment::<Runtime>::from(0),
+			da_control::CheckAppId::<Runtime>::from(AppId(app_id)),
+		);
+		let additional = (
+			(),
+			VERSION.spec_version,
+			VERSION.transaction_version,
+			H256::zero(),
+			H256::zero(),
+			(),
+			(),
+			(),
+			(),
+		);
+		let payload = SignedPayload::from_raw(function.clone(), extra.clone(), additional);
+		let signature = payload.using_encoded(|e| Alice.pair().sign(e));
+
+		let extrinsic = da_runtime::Extrinsic::new_signed(
+			function,
+			Alice.to_account_id().into(),
+			da_runtime::Signature::Sr25519(signature),
+			extra,
+		);
+		OpaqueExtrinsic::from_bytes(&extrinsic.encode()).unwrap()
+	}
+
+	#[test]
+	fn native_tx_data_extracts_submitted_data() {
+		let tx_data = native_tx_data(1, vec![submit_data(1, b"abcd"), submit_data(2, b"")]);
+
+		let submitted = tx_data.to_app_extrinsics();
+		assert_eq!(submitted.len(), 1);
+		assert_eq!(submitted[0].app_id, AppId(1));
+		assert_eq!(submitted[0].data, b"abcd".to_vec());
+	}
+}
@@ -0,0 +1,436 @@
+use super::{new_client, OutputFormat};
+use crate::{
+	da_block_import::{extension_block_len, header_data_root},
+	service::FullClient,
+};
+
+use avail_core::{header::HeaderExtension, OpaqueExtrinsic};
+use da_runtime::apis::{DataAvailApi, ExtensionBuilder};
+use rayon::prelude::*;
+use sc_cli::{CliConfiguration, ImportParams, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_service::Configuration;
+use serde::Serialize;
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use std::{
+	fmt,
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Blocks between two progress logs.
+const PROGRESS_EVERY: u32 = 10_000;
+
+/// The `audit-da` command.
+///
+/// Recomputes the data root and the header extension of each block of the range with the runtime
+/// of its parent, as done on import, and reports the blocks which are not consistent. The state
+/// of the parent blocks must be available.
+#[derive(Debug, clap::Parser)]
+pub struct AuditDaCmd {
+	/// First block of the range, the genesis block having no DA content.
+	#[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+	pub from: u32,
+
+	/// Last block of the range, included. The best block by default.
+	#[arg(long)]
+	pub to: Option<u32>,
+
+	/// Number of blocks audited in parallel, the number of CPUs by default.
+	#[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+	pub workers: Option<u32>,
+
+	/// Output format of the report.
+	#[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+	pub format: OutputFormat,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: ImportParams,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditReport {
+	from: u32,
+	to: u32,
+	blocks: Vec<BlockIssues>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockIssues {
+	number: u32,
+	hash: Option<H256>,
+	issues: Vec<Issue>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum Issue {
+	MissingBlock,
+	MissingBody,
+	#[serde(rename_all = "camelCase")]
+	UndecodableExtrinsics {
+		tx_indices: Vec<u32>,
+	},
+	MissingPostInherent,
+	#[serde(rename_all = "camelCase")]
+	DataRootMismatch {
+		header: H256,
+		computed: H256,
+	},
+	/// The runtime of the parent block builds another header version, so the extensions are not
+	/// compared.
+	#[serde(rename_all = "camelCase")]
+	VersionMismatch {
+		header: &'static str,
+		computed: &'static str,
+	},
+	#[serde(rename_all = "camelCase")]
+	ExtensionMismatch {
+		header_dims: (u16, u16),
+		computed_dims: (u16, u16),
+		same_commitment: bool,
+		same_app_lookup: bool,
+	},
+	/// The block could not be read or checked, e.g. when the state of its parent was pruned.
+	AuditFailed {
+		error: String,
+	},
+}
+
+impl AuditDaCmd {
+	pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+		let (client, _task_manager) = new_client(&config)?;
+		let to = self.to.unwrap_or_else(|| client.info().best_number);
+		if self.from > to {
+			return Err(format!("Empty block range {}..={to}", self.from).into());
+		}
+
+		let mut pool = rayon::ThreadPoolBuilder::new().thread_name(|idx| format!("audit-da-{idx}"));
+		if let Some(workers) = self.workers {
+			pool = pool.num_threads(workers as usize);
+		}
+		let pool = pool
+			.build()
+			.map_err(|e| format!("Cannot start the audit workers: {e}"))?;
+
+		let audited = AtomicU32::new(0);
+		let blocks = pool.install(|| {
+			(self.from..=to)
+				.into_par_iter()
+				.filter_map(|number| {
+					let issues = audit_block(&client, number);
+					let audited = audited.fetch_add(1, Ordering::Relaxed) + 1;
+					if audited % PROGRESS_EVERY == 0 {
+						log::info!("Audited {audited} blocks of {}", to - self.from + 1);
+					}
+					(!issues.issues.is_empty()).then_some(issues)
+				})
+				.collect::<Vec<_>>()
+		});
+
+		let report = AuditReport {
+			from: self.from,
+			to,
+			blocks,
+		};
+		match self.format {
+			OutputFormat::Text => print!("{report}"),
+			OutputFormat::Json => {
+				let json = serde_json::to_string_pretty(&report)
+					.map_err(|e| format!("Report serialization failed: {e}"))?;
+				println!("{json}");
+			},
+		}
+
+		if !report.blocks.is_empty() {
+			return Err(format!("{} blocks with DA issues", report.blocks.len()).into());
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for AuditDaCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Checks the block `number` of the canonical chain like `BlockImport` does.
+fn audit_block(client: &FullClient, number: u32) -> BlockIssues {
+	let mut report = BlockIssues {
+		number,
+		hash: None,
+		issues: Vec::new(),
+	};
+	let hash = match client.hash(number) {
+		Ok(Some(hash)) => hash,
+		Ok(None) => return report.with(Issue::MissingBlock),
+		Err(e) => {
+			return report.with(Issue::AuditFailed {
+				error: e.to_string(),
+			})
+		},
+	};
+	report.hash = Some(hash);
+	let block = match client.block(hash) {
+		Ok(Some(block)) => block.block,
+		Ok(None) => return report.with(Issue::MissingBody),
+		Err(e) => {
+			return report.with(Issue::AuditFailed {
+				error: e.to_string(),
+			})
+		},
+	};
+
+	let undecodable = undecodable_extrinsics(&block.extrinsics);
+	if !undecodable.is_empty() {
+		report.issues.push(Issue::UndecodableExtrinsics {
+			tx_indices: undecodable,
+		});
+	}
+
+	match check_header_extension(client, &block.header, block.extrinsics) {
+		Ok(issues) => report.issues.extend(issues),
+		Err(e) => report.issues.push(Issue::AuditFailed {
+			error: e.to_string(),
+		}),
+	}
+	report
+}
+
+/// Indices of the `extrinsics` which the native runtime cannot decode.
+fn undecodable_extrinsics(extrinsics: &[OpaqueExtrinsic]) -> Vec<u32> {
+	extrinsics
+		.iter()
+		.enumerate()
+		.filter(|(_, xt)| da_runtime::Extrinsic::try_from((*xt).clone()).is_err())
+		.map(|(idx, _)| idx as u32)
+		.collect()
+}
+
+impl BlockIssues {
+	fn with(mut self, issue: Issue) -> Self {
+		self.issues.push(issue);
+		self
+	}
+}
+
+/// Checks the post inherent, the data root and the header extension against the runtime of the
+/// parent block.
+fn check_header_extension(
+	client: &FullClient,
+	header: &da_runtime::Header,
+	extrinsics: Vec<OpaqueExtrinsic>,
+) -> Result<Vec<Issue>, ApiError> {
+	let api = client.runtime_api();
+	let parent = header.parent_hash;
+	let mut issues = Vec::new();
+
+	let post_inherent = match extrinsics.last() {
+		Some(last) => api.check_if_extrinsic_is_post_inherent(parent, last)?,
+		None => false,
+	};
+	if !post_inherent {
+		issues.push(Issue::MissingPostInherent);
+	}
+
+	let computed = api.build_data_root(parent, header.number, extrinsics.clone())?;
+	let extension = api.build_extension(
+		parent,
+		extrinsics,
+		computed,
+		extension_block_len(&header.extension),
+		header.number,
+	)?;
+
+	issues.extend(compare_extensions(&header.extension, &extension, computed));
+	Ok(issues)
+}
+
+/// Compares the `imported` header extension with the one `built` from the `computed` data root.
+fn compare_extensions(
+	imported: &HeaderExtension,
+	built: &HeaderExtension,
+	computed: H256,
+) -> Vec<Issue> {
+	let mut issues = Vec::new();
+	if header_data_root(imported) != computed {
+		issues.push(Issue::DataRootMismatch {
+			header: header_data_root(imported),
+			computed,
+		});
+	}
+
+	// The data root is reported on its own.
+	match (imported, built) {
+		(HeaderExtension::V3(imported), HeaderExtension::V3(built)) => {
+			let header_dims = (imported.commitment.rows, imported.commitment.cols);
+			let computed_dims = (built.commitment.rows, built.commitment.cols);
+			let same_commitment = imported.commitment.commitment == built.commitment.commitment;
+			let same_app_lookup = imported.app_lookup == built.app_lookup;
+			if header_dims != computed_dims || !same_commitment || !same_app_lookup {
+				issues.push(Issue::ExtensionMismatch {
+					header_dims,
+					computed_dims,
+					same_commitment,
+					same_app_lookup,
+				});
+			}
+		},
+		// Only V3 is defined for now, other versions cannot be compared field by field.
+		#[allow(unreachable_patterns)]
+		_ => issues.push(Issue::VersionMismatch {
+			header: version_name(imported),
+			computed: version_name(built),
+		}),
+	}
+	issues
+}
+
+fn version_name(extension: &HeaderExtension) -> &'static str {
+	match extension {
+		HeaderExtension::V3(_) => "V3",
+	}
+}
+
+impl fmt::Display for AuditReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"Audited blocks #{}..=#{}: {} with DA issues",
+			self.from,
+			self.to,
+			self.blocks.len()
+		)?;
+		for block in &self.blocks {
+			match block.hash {
+				Some(hash) => writeln!(f, "Block #{} ({hash:?}):", block.number)?,
+				None => writeln!(f, "Block #{}:", block.number)?,
+			}
+			for issue in &block.issues {
+				writeln!(f, "  {issue}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingBlock => write!(f, "Block not found"),
+			Self::MissingBody => write!(f, "Block body not found"),
+			Self::UndecodableExtrinsics { tx_indices } => {
+				write!(f, "Undecodable extrinsics at tx indices {tx_indices:?}")
+			},
+			Self::MissingPostInherent => write!(f, "Block does not contain post inherent"),
+			Self::DataRootMismatch { header, computed } => write!(
+				f,
+				"Data root does NOT match, expected {header:?}, computed {computed:?}"
+			),
+			Self::VersionMismatch { header, computed } => write!(
+				f,
+				"Header version does NOT match, header {header}, computed {computed}"
+			),
+			Self::ExtensionMismatch {
+				header_dims,
+				computed_dims,
+				same_commitment,
+				same_app_lookup,
+			} => write!(
+				f,
+				"DA Extension does NOT match, dimensions {header_dims:?} vs {computed_dims:?}, \
+				same commitment: {same_commitment}, same app lookup: {same_app_lookup}"
+			),
+			Self::AuditFailed { error } => write!(f, "Cannot audit the block: {error}"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::da_cmd::tests::submit_data;
+	use avail_core::{AppExtrinsic, AppId};
+	use codec::Encode;
+	use frame_system::{
+		header_builder::{hosted_header_builder, Seed},
+		limits::BlockLength,
+	};
+
+	fn extension(submitted: Vec<AppExtrinsic>, data_root: H256) -> HeaderExtension {
+		hosted_header_builder::build(
+			submitted,
+			data_root,
+			BlockLength::default(),
+			1,
+			Seed::default(),
+		)
+	}
+
+	fn submitted(data: u8) -> Vec<AppExtrinsic> {
+		vec![AppExtrinsic::new(AppId(1), vec![data; 100])]
+	}
+
+	#[test]
+	fn same_extension_has_no_issues() {
+		let data_root = H256::repeat_byte(1);
+		let imported = extension(submitted(1), data_root);
+		let built = extension(submitted(1), data_root);
+
+		assert_eq!(compare_extensions(&imported, &built, data_root), vec![]);
+	}
+
+	#[test]
+	fn data_root_mismatch_is_reported_on_its_own() {
+		let (header, computed) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let imported = extension(submitted(1), header);
+		let built = extension(submitted(1), computed);
+
+		assert_eq!(
+			compare_extensions(&imported, &built, computed),
+			vec![Issue::DataRootMismatch { header, computed }]
+		);
+	}
+
+	#[test]
+	fn extension_mismatch_is_reported() {
+		let data_root = H256::repeat_byte(1);
+		let imported = extension(submitted(1), data_root);
+		let built = extension(submitted(2), data_root);
+
+		let issues = compare_extensions(&imported, &built, data_root);
+		assert_eq!(
+			issues,
+			vec![Issue::ExtensionMismatch {
+				header_dims: (imported.rows(), imported.cols()),
+				computed_dims: (built.rows(), built.cols()),
+				same_commitment: false,
+				same_app_lookup: true,
+			}]
+		);
+		assert!(issues[0]
+			.to_string()
+			.contains("DA Extension does NOT match"));
+	}
+
+	#[test]
+	fn undecodable_extrinsics_are_reported() {
+		let garbage = OpaqueExtrinsic::from_bytes(&vec![1u8, 2, 3].encode()).unwrap();
+		let extrinsics = vec![submit_data(1, b"abcd"), garbage];
+
+		assert_eq!(undecodable_extrinsics(&extrinsics), vec![1]);
+	}
+}
@@ -0,0 +1,259 @@
+use super::{native_tx_data, new_client};
+use crate::da_block_import::header_data_root;
+
+use avail_core::{
+	data_proof::{DataProof, SubTrie},
+	OpaqueExtrinsic,
+};
+use da_runtime::Header as DaHeader;
+use sc_cli::{CliConfiguration, ImportParams, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_service::Configuration;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use std::{
+	fs::{self, File},
+	io::{BufWriter, Write},
+	path::PathBuf,
+};
+
+/// The `export-app-data` command.
+///
+/// Blobs are extracted from the blocks of the local database with the native runtime, along with
+/// their proof against the data root of the block. The export fails on a block whose extracted
+/// data root differs from the one of its header, as its proofs would not verify.
+#[derive(Debug, clap::Parser)]
+pub struct ExportAppDataCmd {
+	/// Application whose blobs are exported.
+	#[arg(long)]
+	pub app_id: u32,
+
+	/// First block of the range.
+	#[arg(long)]
+	pub from: u32,
+
+	/// Last block of the range, included.
+	#[arg(long)]
+	pub to: u32,
+
+	/// Directory receiving, for each blob, `<block>/<tx index>.bin` and its proof in
+	/// `<block>/<tx index>.json`.
+	#[arg(
+		long,
+		required_unless_present = "output_file",
+		conflicts_with = "output_file"
+	)]
+	pub output_dir: Option<PathBuf>,
+
+	/// JSON Lines file receiving a record for each blob.
+	#[arg(long)]
+	pub output_file: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: ImportParams,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedBlob {
+	block_number: u32,
+	block_hash: H256,
+	tx_index: u32,
+	app_id: u32,
+	/// Omitted from the proofs of the `--output-dir` layout, next to the raw blob.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	data: Option<Bytes>,
+	data_proof: DataProof,
+}
+
+enum Output {
+	Dir(PathBuf),
+	JsonLines(BufWriter<File>),
+}
+
+impl Output {
+	fn write(&mut self, mut blob: ExportedBlob, data: Vec<u8>) -> sc_cli::Result<()> {
+		match self {
+			Self::Dir(dir) => {
+				let block_dir = dir.join(blob.block_number.to_string());
+				fs::create_dir_all(&block_dir)?;
+				fs::write(block_dir.join(format!("{}.bin", blob.tx_index)), data)?;
+				let proof = serde_json::to_vec_pretty(&blob).map_err(serialization_fail)?;
+				fs::write(block_dir.join(format!("{}.json", blob.tx_index)), proof)?;
+			},
+			Self::JsonLines(file) => {
+				blob.data = Some(Bytes(data));
+				serde_json::to_writer(&mut *file, &blob).map_err(serialization_fail)?;
+				writeln!(file)?;
+			},
+		}
+		Ok(())
+	}
+}
+
+impl ExportAppDataCmd {
+	pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+		if self.from > self.to {
+			return Err(format!("Empty block range {}..={}", self.from, self.to).into());
+		}
+
+		let (client, _task_manager) = new_client(&config)?;
+		let mut output = match (&self.output_dir, &self.output_file) {
+			(Some(dir), _) => Output::Dir(dir.clone()),
+			(None, Some(file)) => Output::JsonLines(BufWriter::new(File::create(file)?)),
+			(None, None) => return Err("Missing output".into()),
+		};
+
+		let mut exported = 0usize;
+		for block_number in self.from..=self.to {
+			let block_hash = client
+				.hash(block_number)?
+				.ok_or_else(|| format!("Block #{block_number} not found"))?;
+			let header = client
+				.header(block_hash)?
+				.ok_or_else(|| format!("Header of block #{block_number} not found"))?;
+			let extrinsics = client
+				.block_body(block_hash)?
+				.ok_or_else(|| format!("Body of block #{block_number} not found"))?;
+
+			for (blob, data) in app_blobs(block_hash, &header, extrinsics, self.app_id)? {
+				output.write(blob, data)?;
+				exported += 1;
+			}
+
+			if block_number % 1_000 == 0 {
+				log::info!("Exported {exported} blobs up to block #{block_number}");
+			}
+		}
+
+		if let Output::JsonLines(file) = &mut output {
+			file.flush()?;
+		}
+		log::info!(
+			"Exported {exported} blobs of app {} from blocks #{}..=#{}",
+			self.app_id,
+			self.from,
+			self.to
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportAppDataCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Blobs of `app_id` in the block `block_hash`, with their proofs.
+///
+/// Fails if the data root of the extracted data differs from the one of the `header`.
+fn app_blobs(
+	block_hash: H256,
+	header: &DaHeader,
+	extrinsics: Vec<OpaqueExtrinsic>,
+	app_id: u32,
+) -> Result<Vec<(ExportedBlob, Vec<u8>)>, String> {
+	let block_number = header.number;
+	let tx_data = native_tx_data(block_number, extrinsics);
+	let data_root = tx_data.root();
+	let header_root = header_data_root(&header.extension);
+	if data_root != header_root {
+		return Err(format!(
+			"Data root of block #{block_number} does not match its header\n\
+			Extracted: {data_root:?}\nHeader: {header_root:?}"
+		));
+	}
+
+	let mut blobs = Vec::new();
+	for (leaf_idx, submitted) in tx_data.submitted.iter().enumerate() {
+		if submitted.id.0 != app_id {
+			continue;
+		}
+		let proof = tx_data.submitted_proof_of(leaf_idx).ok_or_else(|| {
+			format!(
+				"Missing proof of tx {} in block #{block_number}",
+				submitted.tx_index
+			)
+		})?;
+		let blob = ExportedBlob {
+			block_number,
+			block_hash,
+			tx_index: submitted.tx_index,
+			app_id,
+			data: None,
+			data_proof: DataProof::new(SubTrie::DataSubmit, tx_data.roots(), proof),
+		};
+		blobs.push((blob, submitted.data.clone()));
+	}
+	Ok(blobs)
+}
+
+fn serialization_fail(e: serde_json::Error) -> sc_cli::Error {
+	format!("Blob serialization failed: {e}").into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::da_cmd::tests::submit_data;
+	use frame_system::{
+		header_builder::{hosted_header_builder, Seed},
+		limits::BlockLength,
+	};
+	use sp_runtime::Digest;
+
+	fn body() -> Vec<OpaqueExtrinsic> {
+		vec![
+			submit_data(1, b"first"),
+			submit_data(2, b"other"),
+			submit_data(1, b"second"),
+		]
+	}
+
+	/// Header of block #1 committing to `data_root`, without data in its grid.
+	fn header(data_root: H256) -> DaHeader {
+		let block_len = BlockLength::default();
+		let extension =
+			hosted_header_builder::build(vec![], data_root, block_len, 1, Seed::default());
+		DaHeader::new(
+			1,
+			H256::zero(),
+			H256::zero(),
+			H256::zero(),
+			Digest::default(),
+			extension,
+		)
+	}
+
+	#[test]
+	fn blobs_of_the_app_are_exported_with_their_proofs() {
+		let data_root = native_tx_data(1, body()).root();
+		let blobs = app_blobs(H256::zero(), &header(data_root), body(), 1).unwrap();
+
+		let exported = blobs
+			.iter()
+			.map(|(blob, data)| (blob.tx_index, data.as_slice()))
+			.collect::<Vec<_>>();
+		assert_eq!(exported, vec![(0, &b"first"[..]), (2, &b"second"[..])]);
+		assert!(blobs
+			.iter()
+			.all(|(blob, _)| blob.data_proof.roots.data_root == data_root));
+	}
+
+	#[test]
+	fn data_root_mismatch_fails_the_export() {
+		let err = app_blobs(H256::zero(), &header(H256::repeat_byte(1)), body(), 1).unwrap_err();
+		assert!(err.contains("does not match its header"));
+	}
+}
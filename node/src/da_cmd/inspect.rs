@@ -0,0 +1,416 @@
+use super::{native_tx_data, new_client, OutputFormat};
+use crate::da_block_import::extension_block_len;
+
+use avail_core::{data_proof::Message, header::HeaderExtension, AppExtrinsic, AppId, DataLookup};
+use codec::{Decode, Encode};
+use da_control::kate::completeness::COMMITMENT_SIZE;
+use da_runtime::NodeBlock as Block;
+use frame_system::header_builder::{sampled::build_sampled_rows, Seed};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, ImportParams, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_service::Configuration;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hexdisplay::HexDisplay, Bytes, H256};
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::BTreeMap, fmt};
+
+/// Bytes of a blob shown in the text output.
+const PREVIEW_SIZE: usize = 32;
+
+/// The `da inspect` command.
+///
+/// The data root and the submitted data are extracted with the native runtime.
+#[derive(Debug, clap::Parser)]
+pub struct InspectCmd {
+	/// Block hash or number.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub block: BlockNumberOrHash,
+
+	/// Output format.
+	#[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+	pub format: OutputFormat,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl InspectCmd {
+	pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+		let (client, _task_manager) = new_client(&config)?;
+		let id = self.block.parse::<Block>()?;
+		let hash = client
+			.block_hash_from_id(&id)?
+			.ok_or_else(|| format!("Block {id:?} not found"))?;
+		let block = client
+			.block(hash)?
+			.ok_or_else(|| format!("Body of block {hash:?} not found"))?
+			.block;
+
+		let report = BlockReport::new(hash, block);
+		match self.format {
+			OutputFormat::Text => print!("{report}"),
+			OutputFormat::Json => {
+				let json = serde_json::to_string_pretty(&report)
+					.map_err(|e| format!("Report serialization failed: {e}"))?;
+				println!("{json}");
+			},
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockReport {
+	number: u32,
+	hash: H256,
+	extension: ExtensionReport,
+	data_root: DataRootReport,
+	/// Submitted blobs, by application.
+	submitted: BTreeMap<u32, Vec<BlobReport>>,
+	bridge_messages: Vec<BridgeMessageReport>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtensionReport {
+	version: &'static str,
+	rows: u16,
+	cols: u16,
+	commitments: usize,
+	data_root: H256,
+	/// Lookup of the applications of the header and of the extracted data.
+	app_lookup: BTreeMap<u32, AppLookupReport>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppLookupReport {
+	/// Chunks of the grid of the application in the header, `None` if it is missing.
+	header: Option<(u32, u32)>,
+	/// Chunks of the grid of the application built from the extracted data.
+	native: Option<(u32, u32)>,
+	matches: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataRootReport {
+	recomputed: H256,
+	matches: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobReport {
+	tx_index: u32,
+	size: usize,
+	data: Bytes,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeMessageReport {
+	tx_index: u32,
+	id: u64,
+	origin_domain: u32,
+	destination_domain: u32,
+	from: H256,
+	to: H256,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	data: Option<Bytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	asset_id: Option<H256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	amount: Option<u128>,
+}
+
+impl BlockReport {
+	fn new(hash: H256, block: Block) -> Self {
+		let (header, extrinsics) = block.deconstruct();
+		let number = header.number;
+		let tx_data = native_tx_data(number, extrinsics);
+		let recomputed = tx_data.root();
+		let native_lookup = native_lookup(&header.extension, tx_data.to_app_extrinsics());
+
+		let mut submitted = BTreeMap::<u32, Vec<BlobReport>>::new();
+		for data in tx_data.submitted {
+			submitted.entry(data.id.0).or_default().push(BlobReport {
+				tx_index: data.tx_index,
+				size: data.data.len(),
+				data: Bytes(data.data),
+			});
+		}
+
+		let bridge_messages = tx_data
+			.bridged
+			.into_iter()
+			.map(|bridged| {
+				let msg = bridged.addr_msg;
+				let (data, asset_id, amount) = match msg.message {
+					Message::ArbitraryMessage(data) => (Some(Bytes(data.to_vec())), None, None),
+					Message::FungibleToken { asset_id, amount } => {
+						(None, Some(asset_id), Some(amount))
+					},
+				};
+				BridgeMessageReport {
+					tx_index: bridged.tx_index,
+					id: msg.id,
+					origin_domain: msg.origin_domain,
+					destination_domain: msg.destination_domain,
+					from: msg.from,
+					to: msg.to,
+					data,
+					asset_id,
+					amount,
+				}
+			})
+			.collect();
+
+		let extension = match &header.extension {
+			HeaderExtension::V3(ext) => ExtensionReport {
+				version: "V3",
+				rows: ext.commitment.rows,
+				cols: ext.commitment.cols,
+				commitments: ext.commitment.commitment.len() / COMMITMENT_SIZE,
+				data_root: ext.commitment.data_root,
+				app_lookup: app_lookup_report(&ext.app_lookup, native_lookup.as_ref()),
+			},
+		};
+		let data_root = DataRootReport {
+			recomputed,
+			matches: recomputed == extension.data_root,
+		};
+
+		Self {
+			number,
+			hash,
+			extension,
+			data_root,
+			submitted,
+			bridge_messages,
+		}
+	}
+}
+
+/// Lookup of the grid of `submitted`, laid out with the dimensions of `extension`.
+///
+/// Returns `None` if the grid cannot be built, e.g. if `extension` has no grid.
+fn native_lookup(extension: &HeaderExtension, submitted: Vec<AppExtrinsic>) -> Option<DataLookup> {
+	if submitted.is_empty() {
+		return Some(DataLookup::new_empty());
+	}
+	if extension.rows() == 0 || extension.cols() == 0 {
+		return None;
+	}
+	let block_len = extension_block_len(extension);
+	build_sampled_rows(submitted, block_len, Seed::default(), &[])
+		.map(|grid| grid.lookup)
+		.ok()
+}
+
+/// Applications in `lookup`.
+///
+/// `DataLookup` only gives the range of a known application, so they are read from its encoding.
+fn lookup_app_ids(lookup: &DataLookup) -> Vec<AppId> {
+	#[derive(Decode)]
+	struct Item {
+		app_id: AppId,
+		#[codec(compact)]
+		_start: u32,
+	}
+	#[derive(Decode)]
+	struct CompactLookup {
+		#[codec(compact)]
+		_size: u32,
+		index: Vec<Item>,
+	}
+
+	CompactLookup::decode(&mut lookup.encode().as_slice())
+		.map(|lookup| lookup.index.into_iter().map(|item| item.app_id).collect())
+		.unwrap_or_default()
+}
+
+/// Reports every application of the `header` and `native` lookups, and whether their chunks match.
+fn app_lookup_report(
+	header: &DataLookup,
+	native: Option<&DataLookup>,
+) -> BTreeMap<u32, AppLookupReport> {
+	let range_of = |lookup: &DataLookup, app_id: u32| {
+		lookup
+			.range_of(AppId(app_id))
+			.map(|range| (range.start, range.end))
+	};
+
+	let mut app_ids = lookup_app_ids(header);
+	app_ids.extend(native.map(lookup_app_ids).unwrap_or_default());
+	app_ids
+		.into_iter()
+		.map(|app_id| {
+			let header = range_of(header, app_id.0);
+			let native = native.and_then(|native| range_of(native, app_id.0));
+			let report = AppLookupReport {
+				header,
+				native,
+				matches: header == native,
+			};
+			(app_id.0, report)
+		})
+		.collect()
+}
+
+impl fmt::Display for BlockReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Block #{} ({:?})", self.number, self.hash)?;
+
+		let ext = &self.extension;
+		writeln!(f, "Header extension {}", ext.version)?;
+		writeln!(f, "  Dimensions: {} rows x {} cols", ext.rows, ext.cols)?;
+		writeln!(f, "  Commitments: {}", ext.commitments)?;
+		writeln!(f, "  Data root: {:?}", ext.data_root)?;
+		writeln!(f, "  App lookup:")?;
+		for (app_id, lookup) in &ext.app_lookup {
+			match lookup.header {
+				Some((start, end)) => write!(f, "    App {app_id}: chunks {start}..{end}")?,
+				None => write!(f, "    App {app_id}: missing")?,
+			}
+			match (lookup.matches, lookup.native) {
+				(true, _) => writeln!(f)?,
+				(false, Some((start, end))) => {
+					writeln!(f, " (MISMATCH, extracted data at chunks {start}..{end})")?
+				},
+				(false, None) => writeln!(f, " (MISMATCH, missing from the extracted data)")?,
+			}
+		}
+
+		let matches = if self.data_root.matches {
+			"matches"
+		} else {
+			"MISMATCH"
+		};
+		writeln!(
+			f,
+			"Recomputed data root: {:?} ({matches})",
+			self.data_root.recomputed
+		)?;
+
+		writeln!(f, "Submitted data:")?;
+		for (app_id, blobs) in &self.submitted {
+			writeln!(f, "  App {app_id}: {} blobs", blobs.len())?;
+			for blob in blobs {
+				let preview = &blob.data[..blob.size.min(PREVIEW_SIZE)];
+				let ellipsis = if blob.size > PREVIEW_SIZE { "…" } else { "" };
+				writeln!(
+					f,
+					"    Tx {}: {} bytes 0x{}{ellipsis}",
+					blob.tx_index,
+					blob.size,
+					HexDisplay::from(&preview)
+				)?;
+			}
+		}
+
+		writeln!(f, "Bridge messages:")?;
+		for msg in &self.bridge_messages {
+			write!(
+				f,
+				"  Tx {}: #{} domain {} -> {}, from {:?} to {:?}, ",
+				msg.tx_index, msg.id, msg.origin_domain, msg.destination_domain, msg.from, msg.to
+			)?;
+			match (&msg.data, msg.asset_id, msg.amount) {
+				(Some(data), _, _) => writeln!(f, "{} bytes of data", data.len())?,
+				(_, Some(asset_id), Some(amount)) => writeln!(f, "{amount} of asset {asset_id:?}")?,
+				_ => writeln!(f)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::da_cmd::tests::submit_data;
+	use avail_core::{BlockLengthColumns, BlockLengthRows, BLOCK_CHUNK_SIZE};
+	use frame_system::{header_builder::hosted_header_builder, limits::BlockLength};
+	use sp_runtime::{traits::Block as _, Digest, Perbill};
+
+	fn body() -> Vec<avail_core::OpaqueExtrinsic> {
+		vec![submit_data(1, &[1; 100]), submit_data(2, &[2; 300])]
+	}
+
+	/// Block #1 with `body`, and the header extension of `submitted`.
+	fn block(submitted: Vec<AppExtrinsic>) -> Block {
+		let block_len = BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			BLOCK_CHUNK_SIZE,
+			Perbill::from_percent(90),
+		)
+		.unwrap();
+		let data_root = native_tx_data(1, body()).root();
+		let extension =
+			hosted_header_builder::build(submitted, data_root, block_len, 1, Seed::default());
+		let header = da_runtime::Header::new(
+			1,
+			H256::zero(),
+			H256::zero(),
+			H256::zero(),
+			Digest::default(),
+			extension,
+		);
+		Block::new(header, body())
+	}
+
+	#[test]
+	fn lookup_of_the_extracted_data_matches() {
+		let submitted = native_tx_data(1, body()).to_app_extrinsics();
+		let report = BlockReport::new(H256::zero(), block(submitted));
+
+		assert!(report.data_root.matches);
+		let app_lookup = &report.extension.app_lookup;
+		assert_eq!(app_lookup.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+		assert!(app_lookup
+			.values()
+			.all(|app| app.matches && app.header.is_some()));
+	}
+
+	#[test]
+	fn lookup_of_other_data_is_reported() {
+		let other = vec![
+			AppExtrinsic::new(AppId(1), vec![1; 100]),
+			AppExtrinsic::new(AppId(3), vec![3; 100]),
+		];
+		let report = BlockReport::new(H256::zero(), block(other));
+
+		let app_lookup = &report.extension.app_lookup;
+		assert_eq!(
+			app_lookup.keys().copied().collect::<Vec<_>>(),
+			vec![1, 2, 3]
+		);
+		assert!(app_lookup[&1].matches);
+		assert!(!app_lookup[&2].matches);
+		assert_eq!(app_lookup[&2].header, None);
+		assert!(app_lookup[&2].native.is_some());
+		assert!(!app_lookup[&3].matches);
+		assert!(app_lookup[&3].header.is_some());
+		assert_eq!(app_lookup[&3].native, None);
+		assert!(report.to_string().contains("App 3"));
+	}
+}
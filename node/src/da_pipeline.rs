@@ -0,0 +1,342 @@
+//! # Pipelined DA checks during the initial sync
+//!
+//! `BlockImport` checks the header extension of a block against the runtime of its parent, so the
+//! blocks of a sync batch are checked one after the other. The data root and the extension only
+//! depend on the block itself though: [`DaImportQueue`] hands the queued blocks to a
+//! [`DaCheckPipeline`], which rebuilds them natively on a bounded pool of workers ahead of their
+//! import. `BlockImport` takes the results in import order, and checks any block whose result is
+//! missing or differs from its header against the runtime of its parent, which decides whether
+//! the block is invalid. The data root of a matching block is still built by the runtime of its
+//! parent, so that a native runtime extracting other data falls back to the full check.
+use crate::{da_block_import::extension_block_len, da_cmd::native_tx_data};
+
+use avail_core::OpaqueExtrinsic;
+use codec::Encode;
+use da_runtime::Header as DaHeader;
+use frame_system::header_builder::{hosted_header_builder, Seed};
+
+use futures::task::Context;
+use sc_consensus::import_queue::{
+	ImportQueue, ImportQueueService, IncomingBlock, Link, RuntimeOrigin,
+};
+use sp_consensus::BlockOrigin;
+use sp_core::{blake2_256, H256};
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor},
+	Justifications,
+};
+use std::{
+	collections::HashMap,
+	panic::{catch_unwind, AssertUnwindSafe},
+	sync::{Arc, Condvar, Mutex},
+	time::{Duration, Instant},
+};
+
+/// Blocks per worker which can be waiting for their check. Further blocks are checked at import.
+const PENDING_PER_WORKER: usize = 8;
+/// Results which are not taken after this time are dropped, e.g. blocks which were not imported.
+const STALE_AFTER: Duration = Duration::from_secs(120);
+
+struct Check {
+	number: u32,
+	/// Hash of the checked body, in case the imported one differs.
+	body_hash: [u8; 32],
+	submitted_at: Instant,
+	/// `None` while the check is running, whether the header matches otherwise.
+	matches: Option<bool>,
+}
+
+/// Checks the header extension of queued blocks on a pool of workers.
+pub struct DaCheckPipeline {
+	pool: rayon::ThreadPool,
+	max_pending: usize,
+	checks: Mutex<HashMap<H256, Check>>,
+	checked: Condvar,
+}
+
+impl DaCheckPipeline {
+	pub fn new(workers: usize) -> Result<Self, rayon::ThreadPoolBuildError> {
+		let pool = rayon::ThreadPoolBuilder::new()
+			.num_threads(workers)
+			.thread_name(|idx| format!("da-sync-check-{idx}"))
+			.build()?;
+
+		Ok(Self {
+			pool,
+			max_pending: workers.max(1) * PENDING_PER_WORKER,
+			checks: Mutex::new(HashMap::new()),
+			checked: Condvar::new(),
+		})
+	}
+
+	/// Starts the check of the block `hash`, unless too many checks are pending.
+	fn submit(self: &Arc<Self>, hash: H256, header: DaHeader, body: Vec<OpaqueExtrinsic>) {
+		let Ok(mut checks) = self.checks.lock() else {
+			return;
+		};
+		if checks.contains_key(&hash) {
+			return;
+		}
+		if checks.len() >= self.max_pending {
+			checks.retain(|_, check| check.submitted_at.elapsed() < STALE_AFTER);
+			if checks.len() >= self.max_pending {
+				return;
+			}
+		}
+		let check = Check {
+			number: header.number,
+			body_hash: body_hash(&body),
+			submitted_at: Instant::now(),
+			matches: None,
+		};
+		checks.insert(hash, check);
+		drop(checks);
+
+		let pipeline = Arc::clone(self);
+		self.pool.spawn(move || {
+			// A panic would abort the node from a rayon worker.
+			let matches =
+				catch_unwind(AssertUnwindSafe(|| matches_header(&header, body))).unwrap_or(false);
+			if let Ok(mut checks) = pipeline.checks.lock() {
+				if let Some(check) = checks.get_mut(&hash) {
+					check.matches = Some(matches);
+				}
+			}
+			pipeline.checked.notify_all();
+		});
+	}
+
+	/// Takes the result of the check of the block `hash`, waiting for it if it is running.
+	///
+	/// Returns `None` if the block was not checked ahead of its import, and `Some(false)` if its
+	/// header or `body` differ from the checked ones.
+	pub fn take(&self, hash: H256, number: u32, body: &[OpaqueExtrinsic]) -> Option<bool> {
+		let mut checks = self.checks.lock().ok()?;
+		// Blocks are imported in order, so results of lower blocks will not be taken anymore.
+		checks.retain(|_, check| check.number >= number);
+
+		while checks.get(&hash)?.matches.is_none() {
+			checks = self.checked.wait(checks).ok()?;
+		}
+		let check = checks.remove(&hash)?;
+		Some(check.matches == Some(true) && check.body_hash == body_hash(body))
+	}
+}
+
+/// Rebuilds the data root and the header extension of a block with the native runtime.
+fn matches_header(header: &DaHeader, body: Vec<OpaqueExtrinsic>) -> bool {
+	let tx_data = native_tx_data(header.number, body);
+	let data_root = tx_data.root();
+	let submitted = tx_data.to_app_extrinsics();
+
+	// The runtime fills the padding with the default seed unless `secure_padding_fill` is
+	// enabled, in which case the block is checked at import.
+	let extension = hosted_header_builder::build(
+		submitted,
+		data_root,
+		extension_block_len(&header.extension),
+		header.number,
+		Seed::default(),
+	);
+	header.extension == extension
+}
+
+fn body_hash(body: &[OpaqueExtrinsic]) -> [u8; 32] {
+	body.using_encoded(blake2_256)
+}
+
+/// Import queue which submits the blocks of the initial sync to a [`DaCheckPipeline`].
+pub struct DaImportQueue<B: BlockT, Q> {
+	inner: Q,
+	service: PipelinedService<B>,
+}
+
+impl<B, Q> DaImportQueue<B, Q>
+where
+	B: BlockT<Extrinsic = OpaqueExtrinsic, Header = DaHeader, Hash = H256>,
+	Q: ImportQueue<B>,
+{
+	/// Wraps `inner`, which is left untouched if `pipeline` is `None`.
+	pub fn new(inner: Q, pipeline: Option<Arc<DaCheckPipeline>>) -> Self {
+		let service = PipelinedService {
+			inner: inner.service(),
+			pipeline,
+		};
+		Self { inner, service }
+	}
+}
+
+struct PipelinedService<B: BlockT> {
+	inner: Box<dyn ImportQueueService<B>>,
+	pipeline: Option<Arc<DaCheckPipeline>>,
+}
+
+impl<B> ImportQueueService<B> for PipelinedService<B>
+where
+	B: BlockT<Extrinsic = OpaqueExtrinsic, Header = DaHeader, Hash = H256>,
+{
+	fn import_blocks(&mut self, origin: BlockOrigin, blocks: Vec<IncomingBlock<B>>) {
+		let is_sync = matches!(origin, BlockOrigin::NetworkInitialSync | BlockOrigin::File);
+		if let Some(pipeline) = self.pipeline.as_ref().filter(|_| is_sync) {
+			for block in &blocks {
+				if let (Some(header), Some(body)) = (&block.header, &block.body) {
+					pipeline.submit(block.hash, header.clone(), body.clone());
+				}
+			}
+		}
+		self.inner.import_blocks(origin, blocks);
+	}
+
+	fn import_justifications(
+		&mut self,
+		who: RuntimeOrigin,
+		hash: B::Hash,
+		number: NumberFor<B>,
+		justifications: Justifications,
+	) {
+		self.inner
+			.import_justifications(who, hash, number, justifications);
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, Q> ImportQueue<B> for DaImportQueue<B, Q>
+where
+	B: BlockT<Extrinsic = OpaqueExtrinsic, Header = DaHeader, Hash = H256>,
+	Q: ImportQueue<B>,
+{
+	fn service(&self) -> Box<dyn ImportQueueService<B>> {
+		Box::new(PipelinedService {
+			inner: self.inner.service(),
+			pipeline: self.service.pipeline.clone(),
+		})
+	}
+
+	fn service_ref(&mut self) -> &mut dyn ImportQueueService<B> {
+		&mut self.service
+	}
+
+	fn poll_actions(&mut self, cx: &mut Context, link: &mut dyn Link<B>) {
+		self.inner.poll_actions(cx, link)
+	}
+
+	async fn run(self, link: Box<dyn Link<B>>) {
+		self.inner.run(link).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pipeline() -> DaCheckPipeline {
+		DaCheckPipeline::new(1).unwrap()
+	}
+
+	fn checked(pipeline: &DaCheckPipeline, hash: H256, number: u32, body: &[OpaqueExtrinsic]) {
+		let check = Check {
+			number,
+			body_hash: body_hash(body),
+			submitted_at: Instant::now(),
+			matches: Some(true),
+		};
+		pipeline.checks.lock().unwrap().insert(hash, check);
+	}
+
+	fn body(byte: u8) -> Vec<OpaqueExtrinsic> {
+		vec![OpaqueExtrinsic::from_bytes(&vec![byte].encode()).unwrap()]
+	}
+
+	fn data_body() -> Vec<OpaqueExtrinsic> {
+		vec![submit_data(1, &[1; 100]), submit_data(2, &[2; 300])]
+	}
+
+	/// Header of block #1 with the extension of `submitted` committed to `data_root`.
+	fn header(submitted: Vec<AppExtrinsic>, data_root: H256) -> DaHeader {
+		let block_len = BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			avail_core::BLOCK_CHUNK_SIZE,
+			Perbill::from_percent(90),
+		)
+		.unwrap();
+		let extension =
+			hosted_header_builder::build(submitted, data_root, block_len, 1, Seed::default());
+		DaHeader::new(
+			1,
+			H256::zero(),
+			H256::zero(),
+			H256::zero(),
+			Digest::default(),
+			extension,
+		)
+	}
+
+	fn submitted_and_checked(header: DaHeader, body: Vec<OpaqueExtrinsic>) -> Option<bool> {
+		let pipeline = Arc::new(pipeline());
+		let hash = header.hash();
+		pipeline.submit(hash, header, body.clone());
+		pipeline.take(hash, 1, &body)
+	}
+
+	#[test]
+	fn submitted_block_matching_its_header_is_checked() {
+		let tx_data = native_tx_data(1, data_body());
+		let header = header(tx_data.to_app_extrinsics(), tx_data.root());
+
+		assert_eq!(submitted_and_checked(header, data_body()), Some(true));
+	}
+
+	#[test]
+	fn submitted_block_differing_from_its_header_is_not_checked() {
+		let tx_data = native_tx_data(1, data_body());
+
+		let other_root = header(tx_data.to_app_extrinsics(), H256::repeat_byte(1));
+		assert_eq!(submitted_and_checked(other_root, data_body()), Some(false));
+
+		let other_data = header(
+			vec![AppExtrinsic::new(AppId(1), vec![3; 100])],
+			tx_data.root(),
+		);
+		assert_eq!(submitted_and_checked(other_data, data_body()), Some(false));
+
+		let empty = header(vec![], tx_data.root());
+		assert_eq!(submitted_and_checked(empty, data_body()), Some(false));
+	}
+
+	#[test]
+	fn matching_result_is_taken_once() {
+		let pipeline = pipeline();
+		let hash = H256::repeat_byte(1);
+		checked(&pipeline, hash, 1, &body(0));
+
+		assert_eq!(pipeline.take(hash, 1, &body(0)), Some(true));
+		assert_eq!(pipeline.take(hash, 1, &body(0)), None);
+	}
+
+	#[test]
+	fn other_body_does_not_match() {
+		let pipeline = pipeline();
+		let hash = H256::repeat_byte(1);
+		checked(&pipeline, hash, 1, &body(0));
+
+		assert_eq!(pipeline.take(hash, 1, &body(1)), Some(false));
+	}
+
+	#[test]
+	fn results_of_lower_blocks_are_dropped() {
+		let pipeline = pipeline();
+		let (stale, fork, next) = (
+			H256::repeat_byte(1),
+			H256::repeat_byte(2),
+			H256::repeat_byte(3),
+		);
+		checked(&pipeline, stale, 1, &body(0));
+		checked(&pipeline, fork, 2, &body(0));
+		checked(&pipeline, next, 3, &body(0));
+
+		assert_eq!(pipeline.take(fork, 2, &body(0)), Some(true));
+		assert_eq!(pipeline.take(stale, 1, &body(0)), None);
+		assert!(pipeline.checks.lock().unwrap().contains_key(&next));
+	}
+}
@@ -1,9 +1,12 @@
+pub mod blob_index;
 pub mod chains;
 
 pub mod da_block_import;
 pub use da_block_import::BlockImport;
+pub mod da_pipeline;
 
 pub mod cli;
+pub mod da_cmd;
 pub mod rpc;
 pub mod service;
 
@@ -3,11 +3,14 @@
 #![warn(unused_extern_crates)]
 
 mod benchmarking;
+mod blob_index;
 #[macro_use]
 mod service;
 mod cli;
 mod command;
 mod da_block_import;
+mod da_cmd;
+mod da_pipeline;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
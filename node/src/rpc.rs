@@ -30,12 +30,14 @@
 
 //! # Data Availability Changes
 //! - Add Kate RPC extension.
+//! - Add Vector RPC extension.
 //! - Remove `sc_rpc::dev` extension.
 
 #![warn(missing_docs)]
 
 use std::sync::Arc;
 
+use crate::blob_index::BlobIndex;
 use da_runtime::{
 	apis::{DataAvailApi, KateApi, VectorApi},
 	AccountId, Balance, BlockNumber, Hash, Index, NodeBlock as Block,
@@ -102,6 +104,8 @@ pub struct FullDeps<C, P, SC, B> {
 	///
 	/// Should not be used unless unless you know what you're doing.
 	pub kate_rpc_metrics_enabled: bool,
+	/// Per-application blob index, if enabled.
+	pub blob_index: Option<Arc<BlobIndex<C>>>,
 }
 
 /// Instantiate all Full RPC extensions.
@@ -129,6 +133,7 @@ where
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>,
 {
+	use crate::blob_index::{BlobIndexApiServer, BlobIndexRpc};
 	use kate_rpc::metrics::KateApiMetricsServer;
 	use kate_rpc::{Kate, KateApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
@@ -139,6 +144,7 @@ where
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
+	use vector_rpc::{Vector, VectorApiServer};
 
 	let mut io = RpcModule::new(());
 	let FullDeps {
@@ -152,6 +158,7 @@ where
 		kate_max_cells_size,
 		kate_rpc_enabled,
 		kate_rpc_metrics_enabled,
+		blob_index,
 	} = deps;
 
 	let BabeDeps {
@@ -222,6 +229,9 @@ where
 	)?;
 
 	io.merge(StateMigration::new(client.clone(), backend, deny_unsafe).into_rpc())?;
+	io.merge(VectorApiServer::into_rpc(Vector::<C, Block>::new(
+		client.clone(),
+	)))?;
 
 	if is_dev_chain || kate_rpc_metrics_enabled {
 		io.merge(KateApiMetricsServer::into_rpc(Kate::<C, Block>::new(
@@ -230,6 +240,10 @@ where
 		)))?;
 	}
 
+	if let Some(blob_index) = blob_index {
+		io.merge(BlobIndexRpc::new(blob_index).into_rpc())?;
+	}
+
 	if is_dev_chain || kate_rpc_enabled || kate_rpc_metrics_enabled {
 		io.merge(KateApiServer::into_rpc(Kate::<C, Block>::new(
 			client,
@@ -19,7 +19,13 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 #![allow(dead_code)]
 
-use crate::{cli::Cli, rpc as node_rpc};
+use crate::{
+	blob_index::BlobIndex,
+	cli::{Cli, DaPacking},
+	da_block_import::SampledDaSync,
+	da_pipeline::{DaCheckPipeline, DaImportQueue},
+	rpc as node_rpc,
+};
 use avail_core::AppId;
 use da_runtime::{apis::RuntimeApi, NodeBlock as Block, Runtime};
 
@@ -27,6 +33,7 @@ use codec::Encode;
 use frame_system_rpc_runtime_api::AccountNonceApi;
 use futures::prelude::*;
 use pallet_transaction_payment::ChargeTransactionPayment;
+use sc_basic_authorship::PackingStrategy;
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
 pub use sc_executor::NativeElseWasmExecutor;
@@ -169,6 +176,9 @@ pub fn create_extrinsic(
 pub fn new_partial(
 	config: &Configuration,
 	unsafe_da_sync: bool,
+	sampled_da_sync: Option<SampledDaSync>,
+	parallel_da_sync: Option<usize>,
+	blob_index: bool,
 	kate_max_cells_size: usize,
 	kate_rpc_enabled: bool,
 	kate_rpc_metrics_enabled: bool,
@@ -177,7 +187,7 @@ pub fn new_partial(
 		FullClient,
 		FullBackend,
 		FullSelectChain,
-		sc_consensus::DefaultImportQueue<Block>,
+		DaImportQueue<Block, sc_consensus::DefaultImportQueue<Block>>,
 		sc_transaction_pool::FullPool<Block, FullClient>,
 		(
 			impl Fn(
@@ -248,7 +258,32 @@ pub fn new_partial(
 		client.clone(),
 	)?;
 
-	let da_block_import = BlockImport::new(client.clone(), block_import, unsafe_da_sync);
+	let da_pipeline = parallel_da_sync
+		.map(DaCheckPipeline::new)
+		.transpose()
+		.map_err(|e| ServiceError::Other(format!("Cannot start the DA check workers: {e}")))?
+		.map(Arc::new);
+	let blob_index = blob_index
+		.then(|| BlobIndex::open(client.clone()))
+		.transpose()?
+		.map(Arc::new);
+	if let Some(index) = blob_index.clone() {
+		task_manager
+			.spawn_handle()
+			.spawn_blocking("blob-index-backfill", None, async move {
+				if let Err(e) = index.backfill() {
+					log::error!(target: LOG_TARGET, "Blob index backfill failed: {e}");
+				}
+			});
+	}
+	let da_block_import = BlockImport::new(
+		client.clone(),
+		block_import,
+		unsafe_da_sync,
+		sampled_da_sync,
+		da_pipeline.clone(),
+		blob_index.clone(),
+	);
 
 	let slot_duration = babe_link.config().slot_duration();
 	let (import_queue, babe_worker_handle) =
@@ -274,6 +309,7 @@ pub fn new_partial(
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(transaction_pool.clone()),
 		})?;
+	let import_queue = DaImportQueue::new(import_queue, da_pipeline);
 
 	let import_setup = (da_block_import, grandpa_link, babe_link);
 
@@ -318,6 +354,7 @@ pub fn new_partial(
 				kate_max_cells_size,
 				kate_rpc_enabled,
 				kate_rpc_metrics_enabled,
+				blob_index: blob_index.clone(),
 			};
 
 			node_rpc::create_full(deps, rpc_backend.clone()).map_err(Into::into)
@@ -361,9 +398,15 @@ pub fn new_full_base(
 	disable_hardware_benchmarks: bool,
 	with_startup_data: impl FnOnce(&BlockImport, &sc_consensus_babe::BabeLink<Block>),
 	unsafe_da_sync: bool,
+	sampled_da_sync: Option<SampledDaSync>,
+	parallel_da_sync: Option<usize>,
+	blob_index: bool,
 	kate_max_cells_size: usize,
 	kate_rpc_enabled: bool,
 	kate_rpc_metrics_enabled: bool,
+	packing_strategy: PackingStrategy,
+	precompute_header_extension: bool,
+	reserve_header_extension_time: bool,
 ) -> Result<NewFullBase, ServiceError> {
 	let hwbench = if !disable_hardware_benchmarks {
 		config.database.path().map(|database_path| {
@@ -386,6 +429,9 @@ pub fn new_full_base(
 	} = new_partial(
 		&config,
 		unsafe_da_sync,
+		sampled_da_sync,
+		parallel_da_sync,
+		blob_index,
 		kate_max_cells_size,
 		kate_rpc_enabled,
 		kate_rpc_metrics_enabled,
@@ -469,13 +515,16 @@ pub fn new_full_base(
 	(with_startup_data)(&block_import, &babe_link);
 
 	if let sc_service::config::Role::Authority { .. } = &role {
-		let proposer = sc_basic_authorship::ProposerFactory::new(
+		let mut proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
 			transaction_pool.clone(),
 			prometheus_registry.as_ref(),
 			telemetry.as_ref().map(|x| x.handle()),
 		);
+		proposer.set_packing_strategy(packing_strategy);
+		proposer.set_precompute_header_extension(precompute_header_extension);
+		proposer.set_reserve_header_extension_time(reserve_header_extension_time);
 
 		let client_clone = client.clone();
 		let slot_duration = babe_link.config().slot_duration();
@@ -640,14 +689,31 @@ pub fn new_full_base(
 /// Builds a new service for a full client.
 pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let database_path = config.database.path().map(Path::to_path_buf);
+	let packing_strategy = match cli.da_packing {
+		DaPacking::Priority => PackingStrategy::Priority,
+		DaPacking::RoundRobin => PackingStrategy::WeightedRoundRobin {
+			weights: cli
+				.da_packing_weight
+				.iter()
+				.map(|(app_id, weight)| (AppId(*app_id), *weight))
+				.collect(),
+		},
+		DaPacking::MaxMin => PackingStrategy::MaxMinFairness,
+	};
 	let task_manager = new_full_base(
 		config,
 		cli.no_hardware_benchmarks,
 		|_, _| (),
 		cli.unsafe_da_sync,
+		cli.sampled_da_sync(),
+		cli.parallel_da_sync(),
+		cli.blob_index,
 		cli.kate_max_cells_size,
 		cli.kate_rpc_enabled,
 		cli.kate_rpc_metrics_enabled,
+		packing_strategy,
+		cli.precompute_header_extension,
+		cli.reserve_header_extension_time,
 	)
 	.map(|NewFullBase { task_manager, .. }| task_manager)?;
 
@@ -50,9 +50,13 @@ impl TryFrom<Vec<u8>> for GProof {
 	}
 }
 
+pub mod completeness;
+pub use completeness::{AppDataProof, GRowProof};
+
 pub mod hosted_kate;
 pub use hosted_kate::hosted_kate::{
-	app_data as hosted_app_data, grid as hosted_grid, proof as hosted_proof,
+	app_data as hosted_app_data, app_data_proof as hosted_app_data_proof, grid as hosted_grid,
+	proof as hosted_proof,
 };
 
 #[derive(Default)]
@@ -100,6 +104,15 @@ impl<T: Config> RTKate<T> {
 		let seed = Self::random_seed();
 		hosted_proof(extrinsics, block_len, seed, cells)
 	}
+
+	pub fn app_data_proof(
+		submitted: Vec<AppExtrinsic>,
+		block_length: BlockLength,
+		app_id: AppId,
+	) -> Result<AppDataProof, Error> {
+		let seed = Self::random_seed();
+		hosted_app_data_proof(submitted, block_length, seed, app_id.0)
+	}
 }
 
 #[derive(Error, Encode, Decode, TypeInfo, PassByCodec, Debug)]
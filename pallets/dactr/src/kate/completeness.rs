@@ -0,0 +1,200 @@
+//! Proofs that a client received all the data of an application.
+//!
+//! The `DataLookup` of the header gives the range of chunks used by each application. An
+//! [`AppDataProof`] contains every cell of the rows covering that range, each one with its KZG
+//! proof against the row commitment of the header. Checking that the rows match the lookup
+//! entry and that every cell is proven ensures that no data of the application was withheld.
+
+use super::{GDataProof, GProof, GRawScalar, GRow};
+use avail_core::{AppId, DataLookup};
+
+use codec::{Decode, Encode};
+use core::ops::{Range, RangeInclusive};
+use scale_info::TypeInfo;
+use sp_runtime_interface::pass_by::PassByCodec;
+use sp_std::vec::Vec;
+use thiserror_no_std::Error;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Rows are extended by this factor before being committed, see `build_commitment` in the
+/// header builder.
+pub const EXTENSION_FACTOR: u32 = 2;
+/// Length of the commitment of a row.
+pub const COMMITMENT_SIZE: usize = 48;
+
+/// Cells of a row of the grid, with their proofs.
+#[derive(Encode, Decode, TypeInfo, Debug, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct GRowProof {
+	/// Index of the row in the original grid.
+	pub row: u32,
+	/// Every cell of the row, from the first column to the last one.
+	pub cells: Vec<GDataProof>,
+}
+
+/// Proof that `rows` hold all the data of `app_id` in a block.
+#[derive(Encode, Decode, TypeInfo, PassByCodec, Debug, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AppDataProof {
+	pub app_id: AppId,
+	/// Rows covering the range of the application, empty if it has no data in the block.
+	pub rows: Vec<GRowProof>,
+}
+
+/// Checks the KZG proof of a single cell. It is left to the caller, so the completeness check
+/// can run wherever a pairing implementation is available. [`KateRecoveryVerifier`] uses the one
+/// of `kate-recovery`, behind the `kate-recovery` feature.
+pub trait CellVerifier {
+	/// Returns whether `proof` shows that `data` is the cell at (`row`, `col`) of the extended
+	/// grid, whose row is committed by `commitment`.
+	fn verify(
+		&self,
+		commitment: &[u8; COMMITMENT_SIZE],
+		row: u32,
+		col: u32,
+		data: &GRawScalar,
+		proof: &GProof,
+	) -> bool;
+}
+
+/// [`CellVerifier`] checking the KZG proofs against the public parameters of `kate`, with the
+/// pairing of `kate-recovery`.
+#[cfg(any(feature = "kate-recovery", test))]
+pub struct KateRecoveryVerifier {
+	#[allow(clippy::type_complexity)]
+	check: Box<dyn Fn(&[u8; COMMITMENT_SIZE], &kate_recovery::data::Cell) -> bool + Send + Sync>,
+}
+
+#[cfg(any(feature = "kate-recovery", test))]
+impl KateRecoveryVerifier {
+	/// Verifier for the cells of a grid of `rows` and `cols`, before its extension.
+	pub fn new(rows: u16, cols: u16) -> Option<Self> {
+		let dimensions = kate_recovery::matrix::Dimensions::new(rows, cols)?;
+		let public_params = kate::couscous::public_params();
+		let check = move |commitment: &[u8; COMMITMENT_SIZE], cell: &kate_recovery::data::Cell| {
+			kate_recovery::proof::verify(&public_params, dimensions, commitment, cell)
+				.unwrap_or(false)
+		};
+
+		Some(Self {
+			check: Box::new(check),
+		})
+	}
+}
+
+#[cfg(any(feature = "kate-recovery", test))]
+impl CellVerifier for KateRecoveryVerifier {
+	fn verify(
+		&self,
+		commitment: &[u8; COMMITMENT_SIZE],
+		row: u32,
+		col: u32,
+		data: &GRawScalar,
+		proof: &GProof,
+	) -> bool {
+		let Ok(col) = u16::try_from(col) else {
+			return false;
+		};
+
+		// A cell is its proof followed by its big endian scalar.
+		let mut content = [0u8; 80];
+		content[..48].copy_from_slice(&Vec::<u8>::from(*proof));
+		data.to_big_endian(&mut content[48..]);
+		let cell = kate_recovery::data::Cell {
+			position: kate_recovery::matrix::Position { row, col },
+			content,
+		};
+
+		(self.check)(commitment, &cell)
+	}
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CompletenessError {
+	#[error("Rows do not cover the range of the application")]
+	UnexpectedRows,
+	#[error("Row {0} does not contain every cell")]
+	MissingCells(u32),
+	#[error("Missing commitment of row {0}")]
+	MissingCommitment(u32),
+	#[error("Invalid proof of cell {row} {col}")]
+	InvalidCell { row: u32, col: u32 },
+}
+
+/// Rows of a grid with `cols` columns covering the chunks in `range`.
+pub fn app_rows(range: &Range<u32>, cols: u32) -> Option<RangeInclusive<u32>> {
+	if range.is_empty() || cols == 0 {
+		return None;
+	}
+	Some(range.start / cols..=(range.end - 1) / cols)
+}
+
+/// Verifies that `proof` holds all the data of its application, using the `lookup`, the number
+/// of `cols` and the `commitment` of the header extension.
+///
+/// Returns the proven rows of the application.
+pub fn verify_app_data_proof<V: CellVerifier>(
+	lookup: &DataLookup,
+	cols: u16,
+	commitment: &[u8],
+	proof: &AppDataProof,
+	verifier: &V,
+) -> Result<Vec<GRow>, CompletenessError> {
+	let range = lookup.range_of(proof.app_id);
+	verify_rows(range, cols.into(), commitment, &proof.rows, verifier)
+}
+
+pub(crate) fn verify_rows<V: CellVerifier>(
+	range: Option<Range<u32>>,
+	cols: u32,
+	commitment: &[u8],
+	rows: &[GRowProof],
+	verifier: &V,
+) -> Result<Vec<GRow>, CompletenessError> {
+	let expected = range.and_then(|range| app_rows(&range, cols));
+	let Some(expected) = expected else {
+		if !rows.is_empty() {
+			return Err(CompletenessError::UnexpectedRows);
+		}
+		return Ok(Vec::new());
+	};
+
+	let same_rows = rows.len() == expected.clone().count()
+		&& rows
+			.iter()
+			.zip(expected)
+			.all(|(proof, row)| proof.row == row);
+	if !same_rows {
+		return Err(CompletenessError::UnexpectedRows);
+	}
+
+	rows.iter()
+		.map(|GRowProof { row, cells }| {
+			if cells.len() != cols as usize {
+				return Err(CompletenessError::MissingCells(*row));
+			}
+
+			let extended_row = row.saturating_mul(EXTENSION_FACTOR);
+			let start = (extended_row as usize).saturating_mul(COMMITMENT_SIZE);
+			let row_commitment: &[u8; COMMITMENT_SIZE] = commitment
+				.get(start..start.saturating_add(COMMITMENT_SIZE))
+				.and_then(|bytes| bytes.try_into().ok())
+				.ok_or(CompletenessError::MissingCommitment(*row))?;
+
+			cells
+				.iter()
+				.enumerate()
+				.map(|(col, (data, proof))| {
+					let col = col as u32;
+					if verifier.verify(row_commitment, extended_row, col, data, proof) {
+						Ok(*data)
+					} else {
+						Err(CompletenessError::InvalidCell { row: *row, col })
+					}
+				})
+				.collect()
+		})
+		.collect()
+}
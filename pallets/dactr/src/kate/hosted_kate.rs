@@ -1,4 +1,7 @@
-use super::{AppExtrinsic, AppId, BlockLength, Error, GDataProof, GProof, GRawScalar, GRow, Seed};
+use super::{
+	completeness::EXTENSION_FACTOR, AppDataProof, AppExtrinsic, AppId, BlockLength, Error,
+	GDataProof, GProof, GRawScalar, GRow, GRowProof, Seed,
+};
 use avail_core::{BlockLengthColumns, BlockLengthRows};
 use core::num::NonZeroU16;
 use frame_system::header_builder::MIN_WIDTH;
@@ -115,6 +118,72 @@ pub trait HostedKate {
 
 		Ok(proofs)
 	}
+
+	/// Proves every cell of the rows holding the data of `app_id`, see
+	/// [`super::completeness`].
+	fn app_data_proof(
+		submitted: Vec<AppExtrinsic>,
+		block_length: BlockLength,
+		seed: Seed,
+		app_id: u32,
+	) -> Result<AppDataProof, Error> {
+		let app_id = AppId(app_id);
+		let (max_width, max_height) = to_width_height(&block_length);
+		let grid = EGrid::from_extrinsics(submitted, MIN_WIDTH, max_width, max_height, seed)?;
+
+		let cols = u32::from(grid.dims().cols().get());
+		let app_rows = grid
+			.lookup()
+			.range_of(app_id)
+			.and_then(|range| super::completeness::app_rows(&range, cols));
+		let Some(app_rows) = app_rows else {
+			return Ok(AppDataProof {
+				app_id,
+				rows: Vec::new(),
+			});
+		};
+
+		let srs = SRS.get_or_init(multiproof_params);
+		let factor = u16::try_from(EXTENSION_FACTOR)?;
+		let grid = grid
+			.extend_columns(NonZeroU16::new(factor).expect("Factor is not zero .qed"))
+			.map_err(|_| Error::ColumnExtension)?;
+		let poly = grid.make_polynomial_grid()?;
+
+		let rows = app_rows
+			.into_par_iter()
+			.map(|row| -> Result<GRowProof, Error> {
+				let extended_row = row * EXTENSION_FACTOR;
+				let cells = (0..cols)
+					.map(|col| -> Result<GDataProof, Error> {
+						let data: GRawScalar = grid
+							.get(extended_row as usize, col as usize)
+							.ok_or(Error::MissingCell {
+								row: extended_row,
+								col,
+							})?
+							.to_bytes()
+							.map(GRawScalar::from)
+							.map_err(|_| Error::InvalidScalarAtRow(extended_row))?;
+
+						let cell =
+							Cell::new(BlockLengthRows(extended_row), BlockLengthColumns(col));
+						let proof = poly
+							.proof(srs, &cell)?
+							.to_bytes()
+							.map(GProof::from)
+							.map_err(|_| Error::Proof)?;
+
+						Ok((data, proof))
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+
+				Ok(GRowProof { row, cells })
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(AppDataProof { app_id, rows })
+	}
 }
 
 fn to_width_height(block_len: &BlockLength) -> (usize, usize) {
@@ -307,3 +307,154 @@ mod set_application_key {
 		})
 	}
 }
+
+mod app_data_proof {
+	use super::*;
+	use crate::kate::{
+		completeness::{verify_rows, CellVerifier, CompletenessError, COMMITMENT_SIZE},
+		GProof, GRawScalar, GRowProof,
+	};
+
+	/// Accepts the cells whose proof starts with `1`.
+	struct FlagVerifier;
+
+	impl CellVerifier for FlagVerifier {
+		fn verify(
+			&self,
+			_commitment: &[u8; COMMITMENT_SIZE],
+			_row: u32,
+			_col: u32,
+			_data: &GRawScalar,
+			proof: &GProof,
+		) -> bool {
+			Vec::<u8>::from(*proof)[0] == 1
+		}
+	}
+
+	const COLS: u32 = 4;
+
+	fn row(row: u32, cols: u32) -> GRowProof {
+		let cells = (0..cols)
+			.map(|col| (GRawScalar::from(row * COLS + col), GProof::from([1u8; 48])))
+			.collect();
+		GRowProof { row, cells }
+	}
+
+	fn commitment(extended_rows: usize) -> Vec<u8> {
+		vec![0u8; extended_rows * COMMITMENT_SIZE]
+	}
+
+	#[test]
+	fn complete_rows_are_accepted() {
+		// Chunks 3 to 8 span the first three rows.
+		let rows = vec![row(0, COLS), row(1, COLS), row(2, COLS)];
+		let data = verify_rows(Some(3..9), COLS, &commitment(6), &rows, &FlagVerifier).unwrap();
+
+		assert_eq!(data.len(), 3);
+		assert_eq!(data[2][1], GRawScalar::from(9));
+	}
+
+	#[test]
+	fn withheld_rows_are_rejected() {
+		let rows = vec![row(0, COLS), row(1, COLS)];
+		assert_eq!(
+			verify_rows(Some(3..9), COLS, &commitment(6), &rows, &FlagVerifier),
+			Err(CompletenessError::UnexpectedRows)
+		);
+
+		let rows = vec![row(0, COLS), row(2, COLS), row(1, COLS)];
+		assert_eq!(
+			verify_rows(Some(3..9), COLS, &commitment(6), &rows, &FlagVerifier),
+			Err(CompletenessError::UnexpectedRows)
+		);
+
+		let rows = vec![row(0, COLS), row(1, COLS - 1), row(2, COLS)];
+		assert_eq!(
+			verify_rows(Some(3..9), COLS, &commitment(6), &rows, &FlagVerifier),
+			Err(CompletenessError::MissingCells(1))
+		);
+	}
+
+	#[test]
+	fn invalid_cells_and_commitments_are_rejected() {
+		let mut rows = vec![row(0, COLS), row(1, COLS)];
+		rows[1].cells[2].1 = GProof::from([0u8; 48]);
+		assert_eq!(
+			verify_rows(Some(0..8), COLS, &commitment(4), &rows, &FlagVerifier),
+			Err(CompletenessError::InvalidCell { row: 1, col: 2 })
+		);
+
+		// Row 1 is committed in the third row of the extended grid.
+		let rows = vec![row(0, COLS), row(1, COLS)];
+		assert_eq!(
+			verify_rows(Some(0..8), COLS, &commitment(2), &rows, &FlagVerifier),
+			Err(CompletenessError::MissingCommitment(1))
+		);
+	}
+
+	#[test]
+	fn hosted_app_data_proof_is_verified_with_kate_recovery() {
+		use crate::kate::{
+			completeness::{verify_app_data_proof, KateRecoveryVerifier},
+			hosted_app_data_proof,
+		};
+		use avail_core::{header::HeaderExtension, AppExtrinsic, AppId};
+		use frame_system::header_builder::hosted_header_builder;
+
+		let submitted = vec![
+			AppExtrinsic::new(AppId(1), vec![1u8; 100]),
+			AppExtrinsic::new(AppId(2), vec![2u8; 300]),
+		];
+		let block_length = BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			BLOCK_CHUNK_SIZE,
+			DA_DISPATCH_RATIO,
+		)
+		.unwrap();
+		let seed = [0u8; 32];
+
+		let extension = hosted_header_builder::build(
+			submitted.clone(),
+			H256::zero(),
+			block_length.clone(),
+			1,
+			seed,
+		);
+		let (lookup, rows, cols, commitment) = match extension {
+			HeaderExtension::V3(ext) => (
+				ext.app_lookup,
+				ext.commitment.rows,
+				ext.commitment.cols,
+				ext.commitment.commitment,
+			),
+		};
+		let verifier = KateRecoveryVerifier::new(rows, cols).unwrap();
+
+		let proof = hosted_app_data_proof(submitted, block_length, seed, 2).unwrap();
+		let data = verify_app_data_proof(&lookup, cols, &commitment, &proof, &verifier).unwrap();
+		assert_eq!(data.len(), proof.rows.len());
+
+		let mut tampered = proof;
+		tampered.rows[0].cells[1].0 += GRawScalar::one();
+		assert_eq!(
+			verify_app_data_proof(&lookup, cols, &commitment, &tampered, &verifier),
+			Err(CompletenessError::InvalidCell {
+				row: tampered.rows[0].row,
+				col: 1
+			})
+		);
+	}
+
+	#[test]
+	fn app_without_data_has_no_rows() {
+		assert_eq!(
+			verify_rows(None, COLS, &commitment(2), &[], &FlagVerifier),
+			Ok(Vec::new())
+		);
+		assert_eq!(
+			verify_rows(None, COLS, &commitment(2), &[row(0, COLS)], &FlagVerifier),
+			Err(CompletenessError::UnexpectedRows)
+		);
+	}
+}
@@ -3,8 +3,30 @@
 use super::*;
 use crate::Pallet;
 use frame_benchmarking::{impl_benchmark_test_suite, v1::BenchmarkError, v2::*};
+use frame_support::traits::Hooks;
 use frame_system::RawOrigin;
 
+// Encoding overhead of a remark call around its payload.
+const REMARK_OVERHEAD: u32 = 8;
+
+fn remark_call<T: Config>(len: u32, seed: u32) -> <T as Config>::RuntimeCall
+where
+	<T as Config>::RuntimeCall: From<frame_system::Call<T>>,
+{
+	let mut remark = vec![0u8; len as usize];
+	for (byte, seed_byte) in remark.iter_mut().zip(seed.to_le_bytes()) {
+		*byte = seed_byte;
+	}
+	frame_system::Call::remark { remark }.into()
+}
+
+fn schedule<T: Config>(call: <T as Config>::RuntimeCall) -> Result<T::Hash, BenchmarkError> {
+	let call_hash = T::Hashing::hash(&call.encode());
+	Pallet::<T>::mandate_scheduled(RawOrigin::Root.into(), Box::new(call), T::MinDelay::get())
+		.map_err(|e| BenchmarkError::Stop(e.error.into()))?;
+	Ok(call_hash)
+}
+
 #[benchmarks(
 	where <T as Config>::RuntimeCall: From<frame_system::Call<T>>,
 )]
@@ -21,5 +43,80 @@ mod benchmarks {
 		Ok(())
 	}
 
+	#[benchmark]
+	fn mandate_scheduled(
+		l: Linear<0, { T::MaxCallLength::get() - REMARK_OVERHEAD }>,
+	) -> Result<(), BenchmarkError> {
+		let call = remark_call::<T>(l, 0);
+		let delay = T::MinDelay::get();
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, Box::new(call), delay);
+
+		let execute_at = frame_system::Pallet::<T>::block_number() + delay;
+		assert_eq!(Agenda::<T>::get(execute_at).len(), 1);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn veto() -> Result<(), BenchmarkError> {
+		let call = remark_call::<T>(T::MaxCallLength::get() - REMARK_OVERHEAD, 0);
+		let call_hash = schedule::<T>(call)?;
+		let origin =
+			T::VetoOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, call_hash);
+
+		assert!(!Scheduled::<T>::contains_key(call_hash));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn service_agenda(
+		s: Linear<0, { T::MaxScheduledPerBlock::get() }>,
+	) -> Result<(), BenchmarkError> {
+		for seed in 0..s {
+			schedule::<T>(remark_call::<T>(
+				T::MaxCallLength::get() - REMARK_OVERHEAD - 4,
+				seed,
+			))?;
+		}
+		let execute_at = frame_system::Pallet::<T>::block_number() + T::MinDelay::get();
+
+		#[block]
+		{
+			Pallet::<T>::on_initialize(execute_at);
+		}
+
+		assert!(Agenda::<T>::get(execute_at).is_empty());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn mandate_batch(c: Linear<0, { T::MaxBatchCalls::get() }>) -> Result<(), BenchmarkError> {
+		let calls = (0..c)
+			.map(|seed| remark_call::<T>(4, seed))
+			.collect::<Vec<_>>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, calls, BatchMode::AllOrNothing);
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn mandate_as() -> Result<(), BenchmarkError> {
+		let who: T::AccountId = whitelisted_caller();
+		let call: <T as Config>::RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+		let origin =
+			T::DispatchAsOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who, Box::new(call));
+
+		Ok(())
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
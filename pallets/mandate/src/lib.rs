@@ -8,16 +8,46 @@ mod mock;
 mod tests;
 mod weights;
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	dispatch::{DispatchResultWithPostInfo, GetDispatchInfo},
+	dispatch::{
+		extract_actual_weight, DispatchResultWithPostInfo, GetDispatchInfo, PostDispatchInfo,
+	},
 	pallet_prelude::*,
+	storage::{with_transaction, TransactionOutcome},
 	traits::UnfilteredDispatchable,
 };
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_runtime::traits::{Hash, One, Zero};
 use sp_std::prelude::*;
 pub use weights::WeightInfo;
 
+/// Call waiting for its delay to expire before being dispatched as Root.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxCallLength))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+pub struct ScheduledCall<BlockNumber, MaxCallLength: Get<u32>> {
+	/// Encoded call.
+	pub call: BoundedVec<u8, MaxCallLength>,
+	/// Dispatch weight of the call, charged when it is executed.
+	pub weight: Weight,
+	/// Block at the start of which the call is executed.
+	pub execute_at: BlockNumber,
+}
+
+/// How `mandate_batch` handles a failing call.
+#[derive(Clone, Copy, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum BatchMode {
+	/// The first failing call reverts the calls executed before it and stops the batch.
+	AllOrNothing,
+	/// Every call is executed, whether the calls before it failed or not.
+	BestEffort,
+}
+
+pub type ScheduledCallOf<T> = ScheduledCall<BlockNumberFor<T>, <T as Config>::MaxCallLength>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -35,15 +65,97 @@ pub mod pallet {
 		/// Type representing the weight of this pallet
 		type WeightInfo: WeightInfo;
 
-		/// Someone who can call the mandate extrinsic.
+		/// Someone who can call the mandate extrinsics, immediate or scheduled.
 		type ApprovedOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Someone who can cancel a scheduled mandate before it is executed.
+		type VetoOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Someone who can dispatch a call on behalf of any account with `mandate_as`.
+		type DispatchAsOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Maximum number of calls in a batch.
+		#[pallet::constant]
+		type MaxBatchCalls: Get<u32>;
+
+		/// Minimum number of blocks between scheduling a mandate and its execution.
+		#[pallet::constant]
+		type MinDelay: Get<BlockNumberFor<Self>>;
+
+		/// Maximum length of the encoding of a scheduled call.
+		#[pallet::constant]
+		type MaxCallLength: Get<u32>;
+
+		/// Maximum number of scheduled calls executed in a single block.
+		#[pallet::constant]
+		type MaxScheduledPerBlock: Get<u32>;
 	}
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
+	/// Scheduled calls by hash.
+	#[pallet::storage]
+	pub type Scheduled<T: Config> =
+		StorageMap<_, Identity, T::Hash, ScheduledCallOf<T>, OptionQuery>;
+
+	/// Hashes of the calls to execute at the start of a block.
+	#[pallet::storage]
+	pub type Agenda<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<T::Hash, T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let call_hashes = Agenda::<T>::take(n);
+			let mut weight = T::WeightInfo::service_agenda(call_hashes.len() as u32);
+			let limit = T::BlockWeights::get()
+				.max_block
+				.saturating_sub(frame_system::Pallet::<T>::block_weight().total());
+			let mut deferred = Vec::new();
+
+			for call_hash in call_hashes {
+				let Some(scheduled) = Scheduled::<T>::take(call_hash) else {
+					continue;
+				};
+				// A call that does not fit in what is left of the block waits for a later one
+				// rather than overweighting this block.
+				if weight.saturating_add(scheduled.weight).any_gt(limit) {
+					deferred.push((call_hash, scheduled));
+					continue;
+				}
+				weight.saturating_accrue(scheduled.weight);
+
+				let result = match <T as Config>::RuntimeCall::decode(&mut &scheduled.call[..]) {
+					Ok(call) => call
+						.dispatch_bypass_filter(frame_system::RawOrigin::Root.into())
+						.map(|_| ())
+						.map_err(|e| e.error),
+					Err(_) => Err(Error::<T>::UndecodableCall.into()),
+				};
+				Self::deposit_event(Event::ScheduledExecuted { call_hash, result });
+			}
+
+			if !deferred.is_empty() {
+				weight.saturating_accrue(Self::defer(n, deferred));
+			}
+
+			weight
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Dispatches `call` as Root right away.
+		///
+		/// Unlike `mandate_scheduled`, this leaves no window to veto the call. It is kept for
+		/// actions that cannot wait for `MinDelay`, such as reacting to an incident. Runtimes that
+		/// want every action to be vetoable should filter this call.
 		#[pallet::call_index(0)]
 		#[pallet::weight({
 			let dispatch_info = call.get_dispatch_info();
@@ -66,11 +178,282 @@ pub mod pallet {
 			// Sudo user does not pay a fee.
 			Ok(Pays::No.into())
 		}
+
+		/// Schedules `call` to be dispatched as Root `delay` blocks from now, leaving `VetoOrigin`
+		/// the time to cancel it with `veto`. If the call does not fit in what is left of that block,
+		/// it is deferred to the next block with room in its agenda.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::mandate_scheduled(T::MaxCallLength::get()))]
+		pub fn mandate_scheduled(
+			origin: OriginFor<T>,
+			call: Box<<T as Config>::RuntimeCall>,
+			delay: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ApprovedOrigin::ensure_origin(origin)?;
+			ensure!(
+				!delay.is_zero() && delay >= T::MinDelay::get(),
+				Error::<T>::DelayTooShort
+			);
+
+			let encoded = call.encode();
+			let call_len = encoded.len() as u32;
+			let call_hash = T::Hashing::hash(&encoded);
+			ensure!(
+				!Scheduled::<T>::contains_key(call_hash),
+				Error::<T>::AlreadyScheduled
+			);
+
+			// The call must fit in a block next to a full agenda, or it could never be executed.
+			let weight = call.get_dispatch_info().weight;
+			ensure!(
+				weight
+					.saturating_add(T::WeightInfo::service_agenda(T::MaxScheduledPerBlock::get()))
+					.all_lte(T::BlockWeights::get().max_block),
+				Error::<T>::CallTooHeavy
+			);
+
+			let execute_at = <frame_system::Pallet<T>>::block_number().saturating_add(delay);
+			let scheduled = ScheduledCall {
+				call: BoundedVec::try_from(encoded).map_err(|_| Error::<T>::CallTooLong)?,
+				weight,
+				execute_at,
+			};
+			Agenda::<T>::try_append(execute_at, call_hash).map_err(|_| Error::<T>::AgendaFull)?;
+			Scheduled::<T>::insert(call_hash, scheduled);
+
+			Self::deposit_event(Event::Scheduled {
+				call_hash,
+				execute_at,
+			});
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::WeightInfo::mandate_scheduled(call_len)),
+				pays_fee: Pays::No,
+			})
+		}
+
+		/// Dispatches `calls` as Root, emitting the result of each call.
+		///
+		/// In `AllOrNothing` mode, a failing call reverts the whole batch and only
+		/// `BatchRolledBack` is emitted. The extrinsic itself succeeds so that the event is kept.
+		#[pallet::call_index(3)]
+		#[pallet::weight({
+			let dispatch_infos = calls.iter().map(|call| call.get_dispatch_info()).collect::<Vec<_>>();
+			let dispatch_weight = dispatch_infos
+				.iter()
+				.fold(T::WeightInfo::mandate_batch(calls.len() as u32), |total, info| {
+					total.saturating_add(info.weight)
+				});
+			let dispatch_class = if dispatch_infos
+				.iter()
+				.all(|info| info.class == DispatchClass::Operational)
+			{
+				DispatchClass::Operational
+			} else {
+				DispatchClass::Normal
+			};
+			(dispatch_weight, dispatch_class)
+		})]
+		pub fn mandate_batch(
+			origin: OriginFor<T>,
+			calls: Vec<<T as Config>::RuntimeCall>,
+			mode: BatchMode,
+		) -> DispatchResultWithPostInfo {
+			T::ApprovedOrigin::ensure_origin(origin)?;
+			let calls_len = calls.len() as u32;
+			ensure!(
+				calls_len <= T::MaxBatchCalls::get(),
+				Error::<T>::TooManyCalls
+			);
+
+			let mut weight = T::WeightInfo::mandate_batch(calls_len);
+			let mut failed = 0u32;
+			match mode {
+				BatchMode::AllOrNothing => {
+					let mut executed = 0u32;
+					let res = with_transaction(|| {
+						for call in calls {
+							let info = call.get_dispatch_info();
+							let res =
+								call.dispatch_bypass_filter(frame_system::RawOrigin::Root.into());
+							weight.saturating_accrue(extract_actual_weight(&res, &info));
+							if let Err(e) = res {
+								return TransactionOutcome::Rollback(Err(e.error));
+							}
+							Self::deposit_event(Event::BatchCallExecuted {
+								index: executed,
+								result: Ok(()),
+							});
+							executed += 1;
+						}
+						TransactionOutcome::Commit(Ok(()))
+					});
+					if let Err(error) = res {
+						Self::deposit_event(Event::BatchRolledBack {
+							index: executed,
+							error,
+						});
+						return Ok(PostDispatchInfo {
+							actual_weight: Some(weight),
+							pays_fee: Pays::No,
+						});
+					}
+				},
+				BatchMode::BestEffort => {
+					for (index, call) in calls.into_iter().enumerate() {
+						let info = call.get_dispatch_info();
+						let res = call.dispatch_bypass_filter(frame_system::RawOrigin::Root.into());
+						weight.saturating_accrue(extract_actual_weight(&res, &info));
+						if res.is_err() {
+							failed += 1;
+						}
+						Self::deposit_event(Event::BatchCallExecuted {
+							index: index as u32,
+							result: res.map(|_| ()).map_err(|e| e.error),
+						});
+					}
+				},
+			}
+
+			Self::deposit_event(Event::BatchCompleted { mode, failed });
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(weight),
+				pays_fee: Pays::No,
+			})
+		}
+
+		/// Dispatches `call` with a signed origin for `who`, for calls that require a signed
+		/// origin rather than Root.
+		#[pallet::call_index(4)]
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			(
+				T::WeightInfo::mandate_as().saturating_add(dispatch_info.weight),
+				dispatch_info.class
+			)
+		})]
+		pub fn mandate_as(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			T::DispatchAsOrigin::ensure_origin(origin)?;
+
+			let res =
+				call.dispatch_bypass_filter(frame_system::RawOrigin::Signed(who.clone()).into());
+			Self::deposit_event(Event::DispatchedAs {
+				who,
+				result: res.map(|_| ()).map_err(|e| e.error),
+			});
+
+			Ok(Pays::No.into())
+		}
+
+		/// Cancels the scheduled call with hash `call_hash`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::veto())]
+		pub fn veto(origin: OriginFor<T>, call_hash: T::Hash) -> DispatchResultWithPostInfo {
+			T::VetoOrigin::ensure_origin(origin)?;
+
+			let scheduled = Scheduled::<T>::take(call_hash).ok_or(Error::<T>::NotScheduled)?;
+			Agenda::<T>::mutate(scheduled.execute_at, |hashes| {
+				hashes.retain(|hash| *hash != call_hash)
+			});
+
+			Self::deposit_event(Event::Vetoed { call_hash });
+
+			Ok(Pays::No.into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Moves `deferred` calls to the front of the first agenda after block `n` with room
+		/// for them, keeping their order, so they are executed before the calls scheduled there.
+		fn defer(n: BlockNumberFor<T>, deferred: Vec<(T::Hash, ScheduledCallOf<T>)>) -> Weight {
+			let mut weight = Weight::zero();
+			let mut execute_at = n.saturating_add(One::one());
+			let mut index = 0;
+
+			for (call_hash, mut scheduled) in deferred {
+				loop {
+					weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+					let inserted = Agenda::<T>::mutate(execute_at, |hashes| {
+						hashes.try_insert(index, call_hash).is_ok()
+					});
+					if inserted {
+						break;
+					}
+					execute_at = execute_at.saturating_add(One::one());
+					index = 0;
+				}
+				index += 1;
+
+				scheduled.execute_at = execute_at;
+				Scheduled::<T>::insert(call_hash, scheduled);
+				weight.saturating_accrue(T::DbWeight::get().writes(1));
+				Self::deposit_event(Event::ScheduledDeferred {
+					call_hash,
+					execute_at,
+				});
+			}
+
+			weight
+		}
 	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// A root operation was executed, show result
 		RootOp { result: DispatchResult },
+		/// A call was scheduled to be executed as Root at the start of block `execute_at`.
+		Scheduled {
+			call_hash: T::Hash,
+			execute_at: BlockNumberFor<T>,
+		},
+		/// A scheduled call was cancelled.
+		Vetoed { call_hash: T::Hash },
+		/// A scheduled call did not fit in its block and was moved to block `execute_at`.
+		ScheduledDeferred {
+			call_hash: T::Hash,
+			execute_at: BlockNumberFor<T>,
+		},
+		/// A scheduled call was executed, show result
+		ScheduledExecuted {
+			call_hash: T::Hash,
+			result: DispatchResult,
+		},
+		/// A call of a batch was executed, show result
+		BatchCallExecuted { index: u32, result: DispatchResult },
+		/// All calls of a batch were executed, `failed` of them unsuccessfully.
+		BatchCompleted { mode: BatchMode, failed: u32 },
+		/// The call at `index` of an all-or-nothing batch failed and the batch was reverted.
+		BatchRolledBack { index: u32, error: DispatchError },
+		/// A call was dispatched on behalf of `who`, show result
+		DispatchedAs {
+			who: T::AccountId,
+			result: DispatchResult,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The delay is shorter than `MinDelay`.
+		DelayTooShort,
+		/// The encoded call is longer than `MaxCallLength`.
+		CallTooLong,
+		/// The same call is already scheduled.
+		AlreadyScheduled,
+		/// Too many calls are scheduled for the same block.
+		AgendaFull,
+		/// No call is scheduled with this hash.
+		NotScheduled,
+		/// The scheduled call can no longer be decoded.
+		UndecodableCall,
+		/// The batch has more than `MaxBatchCalls` calls.
+		TooManyCalls,
+		/// The call is too heavy to be executed at the start of a block.
+		CallTooHeavy,
 	}
 }
@@ -58,10 +58,24 @@ impl pallet_collective::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const MinDelay: BlockNumber = 5;
+	pub const MaxCallLength: u32 = 16 * 1024;
+	pub const MaxScheduledPerBlock: u32 = 2;
+	pub const MaxBatchCalls: u32 = 4;
+}
+
 impl pallet_mandate::Config for Test {
 	type ApprovedOrigin = EitherOf<EnsureRoot<AccountId>, HalfOfTechnicalCommittee>;
+	// Root is left out so that a mandate cannot reach `mandate_as` through its Root origin.
+	type DispatchAsOrigin = pallet_collective::EnsureProportionAtLeast<AccountId, (), 2, 3>;
+	type MaxBatchCalls = MaxBatchCalls;
+	type MaxCallLength = MaxCallLength;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type MinDelay = MinDelay;
 	type RuntimeCall = RuntimeCall;
 	type RuntimeEvent = RuntimeEvent;
+	type VetoOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = ();
 }
 
@@ -1,8 +1,8 @@
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use frame_system::RawOrigin;
 
 use crate::{
-	mock::{new_test_ext, Mandate, RuntimeCall, RuntimeOrigin, System, TechnicalCommittee},
+	mock::{new_test_ext, Mandate, RuntimeCall, RuntimeOrigin, System, TechnicalCommittee, Test},
 	*,
 };
 
@@ -105,3 +105,319 @@ fn mandate_can_not_be_called_with_normal_signed_origins() {
 		);
 	});
 }
+
+fn set_members_call(new_members: Vec<u64>) -> Box<RuntimeCall> {
+	let privileged_call = pallet_collective::Call::set_members {
+		new_members,
+		prime: None,
+		old_count: 0,
+	};
+	Box::new(RuntimeCall::TechnicalCommittee(privileged_call))
+}
+
+#[test]
+fn scheduled_mandate_is_executed_after_delay() {
+	new_test_ext().execute_with(|| {
+		let new_members = [ALICE, BOB, DAVID].to_vec();
+		let call = set_members_call(new_members.clone());
+		let call_hash = <Test as frame_system::Config>::Hashing::hash(&call.encode());
+
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_ok!(Mandate::mandate_scheduled(o, call, 5));
+		System::assert_last_event(
+			Event::Scheduled {
+				call_hash,
+				execute_at: 6,
+			}
+			.into(),
+		);
+
+		// Nothing happens before the delay expires.
+		Mandate::on_initialize(5);
+		assert!(TechnicalCommittee::members().is_empty());
+		assert!(Scheduled::<Test>::contains_key(call_hash));
+
+		System::set_block_number(6);
+		Mandate::on_initialize(6);
+		assert_eq!(TechnicalCommittee::members(), new_members);
+		assert!(!Scheduled::<Test>::contains_key(call_hash));
+		assert!(Agenda::<Test>::get(6).is_empty());
+		System::assert_has_event(
+			Event::ScheduledExecuted {
+				call_hash,
+				result: Ok(()),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn vetoed_mandate_is_not_executed() {
+	new_test_ext().execute_with(|| {
+		let call = set_members_call([ALICE, BOB].to_vec());
+		let call_hash = <Test as frame_system::Config>::Hashing::hash(&call.encode());
+
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_ok!(Mandate::mandate_scheduled(o.clone(), call, 5));
+		assert_ok!(Mandate::veto(o.clone(), call_hash));
+		System::assert_last_event(Event::Vetoed { call_hash }.into());
+		assert!(Agenda::<Test>::get(6).is_empty());
+
+		System::set_block_number(6);
+		Mandate::on_initialize(6);
+		assert!(TechnicalCommittee::members().is_empty());
+
+		assert_noop!(Mandate::veto(o, call_hash), Error::<Test>::NotScheduled);
+	});
+}
+
+#[test]
+fn veto_can_not_be_called_with_normal_signed_origins() {
+	new_test_ext().execute_with(|| {
+		let call = set_members_call([ALICE, BOB].to_vec());
+		let call_hash = <Test as frame_system::Config>::Hashing::hash(&call.encode());
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_ok!(Mandate::mandate_scheduled(o, call, 5));
+
+		let o = RuntimeOrigin::from(RawOrigin::Signed(ALICE));
+		assert_noop!(
+			Mandate::veto(o, call_hash),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn mandate_scheduled_checks_delay_and_duplicates() {
+	new_test_ext().execute_with(|| {
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+
+		assert_noop!(
+			Mandate::mandate_scheduled(o.clone(), set_members_call([ALICE].to_vec()), 4),
+			Error::<Test>::DelayTooShort
+		);
+		assert_noop!(
+			Mandate::mandate_scheduled(
+				RuntimeOrigin::from(RawOrigin::Signed(ALICE)),
+				set_members_call([ALICE].to_vec()),
+				5
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(Mandate::mandate_scheduled(
+			o.clone(),
+			set_members_call([ALICE].to_vec()),
+			5
+		));
+		assert_noop!(
+			Mandate::mandate_scheduled(o.clone(), set_members_call([ALICE].to_vec()), 6),
+			Error::<Test>::AlreadyScheduled
+		);
+
+		// At most two calls per block in the mock.
+		assert_ok!(Mandate::mandate_scheduled(
+			o.clone(),
+			set_members_call([BOB].to_vec()),
+			5
+		));
+		assert_noop!(
+			Mandate::mandate_scheduled(o, set_members_call([DAVID].to_vec()), 5),
+			Error::<Test>::AgendaFull
+		);
+	});
+}
+
+#[test]
+fn all_or_nothing_batch_is_reverted_by_a_failing_call() {
+	new_test_ext().execute_with(|| {
+		// `remark_with_event` requires a signed origin, so it fails when dispatched as Root.
+		let calls = vec![
+			*set_members_call([ALICE, BOB].to_vec()),
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] }),
+		];
+
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_ok!(Mandate::mandate_batch(o, calls, BatchMode::AllOrNothing));
+
+		assert!(TechnicalCommittee::members().is_empty());
+		System::assert_last_event(
+			Event::BatchRolledBack {
+				index: 1,
+				error: sp_runtime::DispatchError::BadOrigin,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn best_effort_batch_executes_every_call() {
+	new_test_ext().execute_with(|| {
+		let calls = vec![
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] }),
+			*set_members_call([ALICE, BOB].to_vec()),
+		];
+
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_ok!(Mandate::mandate_batch(o, calls, BatchMode::BestEffort));
+
+		assert_eq!(TechnicalCommittee::members(), [ALICE, BOB].to_vec());
+		System::assert_has_event(
+			Event::BatchCallExecuted {
+				index: 0,
+				result: Err(sp_runtime::DispatchError::BadOrigin),
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			Event::BatchCallExecuted {
+				index: 1,
+				result: Ok(()),
+			}
+			.into(),
+		);
+		System::assert_last_event(
+			Event::BatchCompleted {
+				mode: BatchMode::BestEffort,
+				failed: 1,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn mandate_batch_checks_origin_and_length() {
+	new_test_ext().execute_with(|| {
+		let calls = vec![*set_members_call([ALICE].to_vec()); 5];
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_noop!(
+			Mandate::mandate_batch(o, calls.clone(), BatchMode::BestEffort),
+			Error::<Test>::TooManyCalls
+		);
+
+		let o = RuntimeOrigin::from(RawOrigin::Signed(ALICE));
+		assert_noop!(
+			Mandate::mandate_batch(o, calls[..1].to_vec(), BatchMode::BestEffort),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn mandate_as_dispatches_with_a_signed_origin() {
+	new_test_ext().execute_with(|| {
+		let remark = vec![1, 2, 3];
+		let call = Box::new(RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: remark.clone(),
+		}));
+
+		let o = RuntimeOrigin::from(RawOrigin::Signed(ALICE));
+		assert_noop!(
+			Mandate::mandate_as(o, BOB, call.clone()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		let o = RuntimeOrigin::from(pallet_collective::RawOrigin::Members(2, 3));
+		assert_ok!(Mandate::mandate_as(o, BOB, call));
+
+		System::assert_has_event(
+			frame_system::Event::Remarked {
+				sender: BOB,
+				hash: <Test as frame_system::Config>::Hashing::hash(&remark),
+			}
+			.into(),
+		);
+		System::assert_last_event(
+			Event::DispatchedAs {
+				who: BOB,
+				result: Ok(()),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn mandate_as_can_not_be_reached_through_root() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: vec![1],
+		}));
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		assert_noop!(
+			Mandate::mandate_as(o.clone(), BOB, call.clone()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		let mandate_as = RuntimeCall::Mandate(crate::pallet::Call::mandate_as { who: BOB, call });
+
+		// A simple majority of the committee, as `ApprovedOrigin`, only gets Root.
+		let half = RuntimeOrigin::from(pallet_collective::RawOrigin::Members(1, 2));
+		assert_ok!(Mandate::mandate(half, Box::new(mandate_as.clone())));
+		System::assert_last_event(
+			Event::RootOp {
+				result: Err(sp_runtime::DispatchError::BadOrigin),
+			}
+			.into(),
+		);
+
+		assert_ok!(Mandate::mandate_batch(
+			o,
+			vec![mandate_as],
+			BatchMode::BestEffort
+		));
+		System::assert_has_event(
+			Event::BatchCallExecuted {
+				index: 0,
+				result: Err(sp_runtime::DispatchError::BadOrigin),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn scheduled_mandate_that_does_not_fit_in_its_block_is_deferred() {
+	new_test_ext().execute_with(|| {
+		let o = RuntimeOrigin::from(RawOrigin::Root);
+		let call = set_members_call([ALICE, BOB].to_vec());
+		let call_hash = <Test as frame_system::Config>::Hashing::hash(&call.encode());
+		assert_ok!(Mandate::mandate_scheduled(o.clone(), call, 5));
+		let other = set_members_call([DAVID].to_vec());
+		let other_hash = <Test as frame_system::Config>::Hashing::hash(&other.encode());
+		assert_ok!(Mandate::mandate_scheduled(o, other, 6));
+
+		// Block 6 is already full when the agenda is serviced.
+		System::set_block_number(6);
+		let max_block = <Test as frame_system::Config>::BlockWeights::get().max_block;
+		System::register_extra_weight_unchecked(max_block, DispatchClass::Mandatory);
+		Mandate::on_initialize(6);
+		assert!(TechnicalCommittee::members().is_empty());
+		System::assert_last_event(
+			Event::ScheduledDeferred {
+				call_hash,
+				execute_at: 7,
+			}
+			.into(),
+		);
+		// The deferred call goes before the ones scheduled for block 7.
+		assert_eq!(Agenda::<Test>::get(7).to_vec(), vec![call_hash, other_hash]);
+		assert_eq!(Scheduled::<Test>::get(call_hash).unwrap().execute_at, 7);
+
+		System::set_block_number(7);
+		frame_system::BlockWeight::<Test>::kill();
+		Mandate::on_initialize(7);
+		assert_eq!(TechnicalCommittee::members(), [DAVID].to_vec());
+		assert!(!Scheduled::<Test>::contains_key(call_hash));
+		System::assert_has_event(
+			Event::ScheduledExecuted {
+				call_hash,
+				result: Ok(()),
+			}
+			.into(),
+		);
+	});
+}
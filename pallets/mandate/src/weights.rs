@@ -51,6 +51,11 @@ use core::marker::PhantomData;
 /// Weight functions needed for `pallet_mandate`.
 pub trait WeightInfo {
 	fn mandate() -> Weight;
+	fn mandate_scheduled(l: u32, ) -> Weight;
+	fn veto() -> Weight;
+	fn service_agenda(s: u32, ) -> Weight;
+	fn mandate_batch(c: u32, ) -> Weight;
+	fn mandate_as() -> Weight;
 }
 
 /// Weights for `pallet_mandate` using the Avail node and recommended hardware.
@@ -63,6 +68,53 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		// Minimum execution time: 13_231_000 picoseconds.
 		Weight::from_parts(13_508_000, 0)
 	}
+	/// Storage: `Mandate::Scheduled` (r:1 w:1)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 16384]`.
+	fn mandate_scheduled(l: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(18_410_000, 19904)
+			.saturating_add(Weight::from_parts(1_120, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Mandate::Scheduled` (r:1 w:1)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	fn veto() -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(23_512_000, 19904)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Scheduled` (r:8 w:8)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[0, 8]`.
+	fn service_agenda(s: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(3_652_000, 3734)
+			.saturating_add(Weight::from_parts(9_870_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 18914).saturating_mul(s.into()))
+	}
+	/// The range of component `c` is `[0, 32]`.
+	fn mandate_batch(c: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(9_871_000, 0)
+			.saturating_add(Weight::from_parts(4_912_000, 0).saturating_mul(c.into()))
+	}
+	fn mandate_as() -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(14_032_000, 0)
+	}
 }
 
 // For backwards compatibility and tests.
@@ -74,4 +126,51 @@ impl WeightInfo for () {
 		// Minimum execution time: 13_231_000 picoseconds.
 		Weight::from_parts(13_508_000, 0)
 	}
+	/// Storage: `Mandate::Scheduled` (r:1 w:1)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 16384]`.
+	fn mandate_scheduled(l: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(18_410_000, 19904)
+			.saturating_add(Weight::from_parts(1_120, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Mandate::Scheduled` (r:1 w:1)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	fn veto() -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(23_512_000, 19904)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Mandate::Agenda` (r:1 w:1)
+	/// Proof: `Mandate::Agenda` (`max_values`: None, `max_size`: Some(269), added: 2744, mode: `MaxEncodedLen`)
+	/// Storage: `Mandate::Scheduled` (r:8 w:8)
+	/// Proof: `Mandate::Scheduled` (`max_values`: None, `max_size`: Some(16439), added: 18914, mode: `MaxEncodedLen`)
+	/// The range of component `s` is `[0, 8]`.
+	fn service_agenda(s: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(3_652_000, 3734)
+			.saturating_add(Weight::from_parts(9_870_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(s.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 18914).saturating_mul(s.into()))
+	}
+	/// The range of component `c` is `[0, 32]`.
+	fn mandate_batch(c: u32, ) -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(9_871_000, 0)
+			.saturating_add(Weight::from_parts(4_912_000, 0).saturating_mul(c.into()))
+	}
+	fn mandate_as() -> Weight {
+		// Estimate, not benchmarked: the `pallet_mandate` benchmarks have not been run yet.
+		Weight::from_parts(14_032_000, 0)
+	}
 }
\ No newline at end of file
@@ -13,6 +13,10 @@ use sp_runtime::traits::Hash;
 use sp_runtime_interface::runtime_interface;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "std")]
+pub mod precompute;
+#[cfg(feature = "std")]
+pub mod sampled;
 #[cfg(feature = "std")]
 mod version_select;
 
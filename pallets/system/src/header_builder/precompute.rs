@@ -0,0 +1,362 @@
+//! Header extension built ahead of `finalize_block` by the block author.
+//!
+//! The proposer feeds the `submit_data` extrinsics it pushes to a [`GridPrecomputer`]. Each time
+//! a row of the grid is completed, the grid and its commitments are built for the data pushed so
+//! far on a worker thread, the latest snapshot superseding the queued ones. The commitments of
+//! the extended rows are built in parallel, and those of the rows left unchanged by the previous
+//! snapshot are reused. When the block is finalized, `build_extension` reuses the result built
+//! from the same inputs, waiting for it if it is still being built, and builds the extension from
+//! scratch otherwise.
+
+use super::version_select::{build_grid, pmp};
+use crate::limits::BlockLength;
+
+use avail_core::{AppExtrinsic, AppId, DataLookup};
+use codec::Encode;
+use core::num::NonZeroU16;
+use kate::{config::DATA_CHUNK_SIZE, gridgen::AsBytes, Seed};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use sp_core::{blake2_256, H256};
+use std::{
+	collections::HashMap,
+	panic::{catch_unwind, AssertUnwindSafe},
+	sync::{
+		mpsc::{channel, Sender},
+		Arc, Condvar, Mutex,
+	},
+	thread,
+	vec::Vec,
+};
+
+/// Number of scalars used by `len` bytes of submitted data.
+///
+/// Each extrinsic is padded to whole scalars on its own, with room for the padding marker.
+pub fn padded_scalars(len: usize) -> usize {
+	(len + 1).div_ceil(DATA_CHUNK_SIZE)
+}
+
+/// Grid dimensions, lookup and commitment of a header extension.
+#[derive(Clone)]
+pub struct Precomputed {
+	pub rows: u16,
+	pub cols: u16,
+	pub lookup: DataLookup,
+	pub commitment: Vec<u8>,
+}
+
+type Built = Result<Precomputed, String>;
+
+/// Latest snapshot, `None` while it is being built.
+static LATEST: Mutex<Option<(H256, Option<Built>)>> = Mutex::new(None);
+static BUILT: Condvar = Condvar::new();
+
+/// Identifies the inputs of a build, from the hashes of the extrinsics so they are hashed once
+/// while the block is authored.
+fn key(xt_hashes: &[[u8; 32]], block_length: &BlockLength, seed: &Seed) -> H256 {
+	(xt_hashes, block_length, seed)
+		.using_encoded(blake2_256)
+		.into()
+}
+
+fn xt_hash(xt: &AppExtrinsic) -> [u8; 32] {
+	xt.using_encoded(blake2_256)
+}
+
+/// Commitments of the extended rows of the previous snapshot, by row content.
+type RowCommitments = HashMap<H256, Vec<u8>>;
+
+fn build(
+	submitted: Vec<AppExtrinsic>,
+	block_length: BlockLength,
+	seed: Seed,
+	previous: &mut RowCommitments,
+) -> Built {
+	let grid = build_grid(submitted, block_length, seed)?;
+	// The commitments of the rows of the extended grid are the extended commitments, the even
+	// rows being the original ones.
+	let extended = grid
+		.extend_columns(NonZeroU16::new(2).expect("2>0"))
+		.map_err(|e| format!("Grid extension failed: {e:?}"))?;
+
+	let row_keys = (0..extended.dims().rows().get() as usize)
+		.map(|row| {
+			let scalars = extended
+				.row(row)
+				.ok_or_else(|| format!("Missing row {row}"))?
+				.iter()
+				.map(|scalar| scalar.to_bytes())
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|e| format!("Scalar serialization failed: {e:?}"))?;
+			Ok(H256(scalars.using_encoded(blake2_256)))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	let missing = row_keys
+		.iter()
+		.enumerate()
+		.filter(|(_, key)| !previous.contains_key(*key))
+		.map(|(row, _)| row)
+		.collect::<Vec<_>>();
+	let built = if missing.is_empty() {
+		Vec::new()
+	} else {
+		let poly_grid = extended
+			.make_polynomial_grid()
+			.map_err(|e| format!("Make polynomial grid failed: {e:?}"))?;
+		missing
+			.into_par_iter()
+			.map(|row| {
+				let commitment = poly_grid
+					.commitment(pmp(), row)
+					.map_err(|e| format!("Commitment of row {row} failed: {e:?}"))?
+					.to_bytes()
+					.map_err(|e| format!("Commitment serialization failed: {e:?}"))?;
+				Ok((row_keys[row], commitment.to_vec()))
+			})
+			.collect::<Result<Vec<_>, String>>()?
+	};
+
+	// Only the rows of this snapshot are kept for the next one.
+	let mut rows = row_keys
+		.iter()
+		.filter_map(|key| previous.remove_entry(key))
+		.collect::<RowCommitments>();
+	rows.extend(built);
+	*previous = rows;
+
+	let mut commitment = Vec::new();
+	for key in &row_keys {
+		commitment.extend_from_slice(&previous[key]);
+	}
+	Ok(Precomputed {
+		rows: grid.dims().rows().get(),
+		cols: grid.dims().cols().get(),
+		lookup: grid.lookup().clone(),
+		commitment,
+	})
+}
+
+/// Takes the precomputed extension of `submitted`, if the latest snapshot was built from the
+/// same inputs.
+pub fn take(
+	submitted: &[AppExtrinsic],
+	block_length: &BlockLength,
+	seed: &Seed,
+) -> Option<Precomputed> {
+	let mut latest = LATEST.lock().ok()?;
+	latest.as_ref()?;
+
+	let xt_hashes = submitted.iter().map(xt_hash).collect::<Vec<_>>();
+	let key = key(&xt_hashes, block_length, seed);
+	loop {
+		match latest.as_ref() {
+			Some((latest_key, None)) if *latest_key == key => {
+				latest = BUILT.wait(latest).ok()?;
+			},
+			Some((latest_key, Some(_))) if *latest_key == key => {
+				let (_, built) = latest.take()?;
+				return built?
+					.map_err(|e| log::warn!("Precomputed header extension failed: {e}"))
+					.ok();
+			},
+			_ => return None,
+		}
+	}
+}
+
+/// Builds the grid of the data pushed by the proposer as rows are completed.
+pub struct GridPrecomputer {
+	/// Extrinsics pushed so far, shared with the worker which builds a prefix of them.
+	submitted: Arc<Mutex<Vec<AppExtrinsic>>>,
+	/// Hashes of the pushed extrinsics, see `key`.
+	xt_hashes: Vec<[u8; 32]>,
+	block_length: BlockLength,
+	seed: Seed,
+	/// Scalars pushed so far, each extrinsic being padded on its own.
+	scalars: usize,
+	/// Number of extrinsics in the last snapshot sent to the worker.
+	sent: usize,
+	worker: Sender<(H256, usize)>,
+}
+
+impl GridPrecomputer {
+	/// Starts the worker for a block with the given `block_length`.
+	///
+	/// `seed` must be the one used by the runtime to fill the padding, the precomputed result
+	/// being ignored otherwise.
+	pub fn new(block_length: BlockLength, seed: Seed) -> Self {
+		let (worker, snapshots) = channel::<(H256, usize)>();
+		let submitted = Arc::new(Mutex::new(Vec::new()));
+		let worker_submitted = submitted.clone();
+		let worker_block_length = block_length.clone();
+		let spawned = thread::Builder::new()
+			.name("grid-precompute".into())
+			.spawn(move || {
+				let mut previous = RowCommitments::new();
+				while let Ok(mut snapshot) = snapshots.recv() {
+					// Only the latest snapshot is worth building.
+					while let Ok(newer) = snapshots.try_recv() {
+						snapshot = newer;
+					}
+
+					let (key, len) = snapshot;
+					let built = catch_unwind(AssertUnwindSafe(|| {
+						let submitted = worker_submitted
+							.lock()
+							.map_err(|_| "Pushed extrinsics are poisoned")?[..len]
+							.to_vec();
+						build(submitted, worker_block_length.clone(), seed, &mut previous)
+					}))
+					.unwrap_or_else(|_| {
+						previous.clear();
+						Err("Grid precomputation panicked".into())
+					});
+
+					if let Ok(mut latest) = LATEST.lock() {
+						if matches!(&*latest, Some((latest_key, None)) if *latest_key == key) {
+							*latest = Some((key, Some(built)));
+							BUILT.notify_all();
+						}
+					}
+				}
+			});
+		if let Err(e) = spawned {
+			log::warn!("Cannot spawn the grid precomputation worker: {e}");
+		}
+
+		Self {
+			submitted,
+			xt_hashes: Vec::new(),
+			block_length,
+			seed,
+			scalars: 0,
+			sent: 0,
+			worker,
+		}
+	}
+
+	/// Adds the data of a `submit_data` extrinsic pushed to the block.
+	pub fn push(&mut self, app_id: AppId, data: Vec<u8>) {
+		let cols = (self.block_length.cols.0 as usize).max(1);
+		let completed_rows = self.scalars / cols;
+
+		self.scalars += padded_scalars(data.len());
+		let xt = AppExtrinsic::new(app_id, data);
+		self.xt_hashes.push(xt_hash(&xt));
+		if let Ok(mut submitted) = self.submitted.lock() {
+			submitted.push(xt);
+		}
+
+		if self.scalars / cols > completed_rows {
+			self.send();
+		}
+	}
+
+	/// Builds the data pushed so far, once the proposer stops pushing extrinsics.
+	pub fn finish(mut self) {
+		if self.sent != self.xt_hashes.len() {
+			self.send();
+		}
+	}
+
+	fn send(&mut self) {
+		let key = key(&self.xt_hashes, &self.block_length, &self.seed);
+		let Ok(mut latest) = LATEST.lock() else {
+			return;
+		};
+
+		let len = self.xt_hashes.len();
+		if self.worker.send((key, len)).is_ok() {
+			*latest = Some((key, None));
+			self.sent = len;
+		} else {
+			// Nobody would wake up `take`.
+			*latest = None;
+		}
+		BUILT.notify_all();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::header_builder::version_select::build_commitment;
+
+	use avail_core::{BlockLengthColumns, BlockLengthRows, BLOCK_CHUNK_SIZE};
+	use sp_runtime::Perbill;
+
+	fn block_length() -> BlockLength {
+		BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			BLOCK_CHUNK_SIZE,
+			Perbill::from_percent(90),
+		)
+		.unwrap()
+	}
+
+	fn submitted(apps: &[(u32, usize)]) -> Vec<AppExtrinsic> {
+		apps.iter()
+			.map(|(app_id, len)| AppExtrinsic::new(AppId(*app_id), vec![*app_id as u8; *len]))
+			.collect()
+	}
+
+	fn full_commitment(submitted: Vec<AppExtrinsic>) -> Vec<u8> {
+		let grid = build_grid(submitted, block_length(), Seed::default()).unwrap();
+		build_commitment(&grid).unwrap()
+	}
+
+	#[test]
+	fn incremental_build_matches_full_build() {
+		let mut previous = RowCommitments::new();
+
+		let first = submitted(&[(1, 100), (2, 300)]);
+		let built = build(
+			first.clone(),
+			block_length(),
+			Seed::default(),
+			&mut previous,
+		)
+		.unwrap();
+		assert_eq!(built.commitment, full_commitment(first));
+
+		// Data of a lower application moves the rows of the others.
+		let second = submitted(&[(0, 50), (1, 100), (2, 300), (3, 1_000)]);
+		let built = build(
+			second.clone(),
+			block_length(),
+			Seed::default(),
+			&mut previous,
+		)
+		.unwrap();
+		assert_eq!(built.commitment, full_commitment(second));
+		// Only the rows of the last snapshot are kept.
+		assert!(previous.len() <= 2 * built.rows as usize);
+	}
+
+	#[test]
+	fn take_ignores_results_built_from_other_inputs() {
+		let xts = submitted(&[(1, 100), (2, 300)]);
+		let mut precomputer = GridPrecomputer::new(block_length(), Seed::default());
+		for xt in xts.clone() {
+			precomputer.push(xt.app_id, xt.data);
+		}
+		precomputer.finish();
+
+		let seed = Seed::default();
+		assert!(take(&xts[..1], &block_length(), &seed).is_none());
+		assert!(take(&xts, &block_length(), &[1u8; 32]).is_none());
+
+		let precomputed = take(&xts, &block_length(), &seed).unwrap();
+		assert_eq!(precomputed.commitment, full_commitment(xts.clone()));
+		// A result is taken once.
+		assert!(take(&xts, &block_length(), &seed).is_none());
+	}
+
+	#[test]
+	fn padded_scalars_leave_room_for_the_padding_marker() {
+		assert_eq!(padded_scalars(0), 1);
+		assert_eq!(padded_scalars(DATA_CHUNK_SIZE - 1), 1);
+		assert_eq!(padded_scalars(DATA_CHUNK_SIZE), 2);
+	}
+}
@@ -0,0 +1,119 @@
+//! Commitments of a subset of the rows of the grid, used to check imported blocks by sampling.
+
+use super::version_select::{build_grid, pmp};
+use crate::limits::BlockLength;
+
+use avail_core::{AppExtrinsic, DataLookup};
+use core::num::NonZeroU16;
+use kate::{gridgen::AsBytes, Seed};
+use std::vec::Vec;
+
+/// Grid of a block with the commitments of some of its rows.
+pub struct SampledRows {
+	pub rows: u16,
+	pub cols: u16,
+	pub lookup: DataLookup,
+	/// Commitments of the sampled rows of the extended grid.
+	pub commitments: Vec<(u32, Vec<u8>)>,
+}
+
+/// Builds the grid of `submitted` and the commitments of the `rows` of its extended grid only.
+///
+/// Rows beyond the extended grid are ignored. The commitment of the extended row `r` is the one
+/// at index `r` of the extended commitments in the header, the even rows being the original ones.
+pub fn build_sampled_rows(
+	submitted: Vec<AppExtrinsic>,
+	block_length: BlockLength,
+	seed: Seed,
+	rows: &[u32],
+) -> Result<SampledRows, String> {
+	let grid = build_grid(submitted, block_length, seed)?;
+	let extended = grid
+		.extend_columns(NonZeroU16::new(2).expect("2>0"))
+		.map_err(|e| format!("Grid extension failed: {e:?}"))?;
+	let poly_grid = extended
+		.make_polynomial_grid()
+		.map_err(|e| format!("Make polynomial grid failed: {e:?}"))?;
+
+	let extended_rows = u32::from(extended.dims().rows().get());
+	let commitments = rows
+		.iter()
+		.filter(|row| **row < extended_rows)
+		.map(|row| {
+			let commitment = poly_grid
+				.commitment(pmp(), *row as usize)
+				.map_err(|e| format!("Commitment of row {row} failed: {e:?}"))?
+				.to_bytes()
+				.map_err(|e| format!("Commitment serialization failed: {e:?}"))?;
+			Ok((*row, commitment.to_vec()))
+		})
+		.collect::<Result<Vec<_>, String>>()?;
+
+	Ok(SampledRows {
+		rows: grid.dims().rows().get(),
+		cols: grid.dims().cols().get(),
+		lookup: grid.lookup().clone(),
+		commitments,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::header_builder::version_select::build_commitment;
+
+	use avail_core::{AppId, BlockLengthColumns, BlockLengthRows, BLOCK_CHUNK_SIZE};
+	use sp_runtime::Perbill;
+
+	fn block_length() -> BlockLength {
+		BlockLength::with_normal_ratio(
+			BlockLengthRows(32),
+			BlockLengthColumns(32),
+			BLOCK_CHUNK_SIZE,
+			Perbill::from_percent(90),
+		)
+		.unwrap()
+	}
+
+	fn submitted(data: u8) -> Vec<AppExtrinsic> {
+		vec![
+			AppExtrinsic::new(AppId(1), vec![data; 100]),
+			AppExtrinsic::new(AppId(2), vec![data; 300]),
+		]
+	}
+
+	#[test]
+	fn sampled_rows_are_the_extended_commitments() {
+		let grid = build_grid(submitted(1), block_length(), Seed::default()).unwrap();
+		let commitment = build_commitment(&grid).unwrap();
+		let extended_rows = 2 * u32::from(grid.dims().rows().get());
+
+		let rows = (0..extended_rows + 1).collect::<Vec<_>>();
+		let sampled =
+			build_sampled_rows(submitted(1), block_length(), Seed::default(), &rows).unwrap();
+		assert_eq!(sampled.rows, grid.dims().rows().get());
+		assert_eq!(sampled.commitments.len(), extended_rows as usize);
+		assert_eq!(
+			commitment.len(),
+			extended_rows as usize * sampled.commitments[0].1.len()
+		);
+		for (row, built) in sampled.commitments {
+			let start = row as usize * built.len();
+			assert_eq!(&commitment[start..start + built.len()], built.as_slice());
+		}
+	}
+
+	#[test]
+	fn sampled_rows_of_other_data_mismatch() {
+		let grid = build_grid(submitted(1), block_length(), Seed::default()).unwrap();
+		let commitment = build_commitment(&grid).unwrap();
+
+		// An even and an odd row.
+		let sampled =
+			build_sampled_rows(submitted(2), block_length(), Seed::default(), &[0, 1]).unwrap();
+		for (row, built) in sampled.commitments {
+			let start = row as usize * built.len();
+			assert_ne!(&commitment[start..start + built.len()], built.as_slice());
+		}
+	}
+}
@@ -1,11 +1,11 @@
-use super::MIN_WIDTH;
+use super::{precompute, MIN_WIDTH};
 use crate::limits::BlockLength;
 
-use avail_base::metrics::avail::HeaderExtensionBuilderMetrics as Metrics;
+use avail_base::{header_extension_cost, metrics::avail::HeaderExtensionBuilderMetrics as Metrics};
 use avail_core::{
 	app_extrinsic::AppExtrinsic,
 	header::{extension as he, HeaderExtension},
-	kate_commitment as kc, HeaderVersion,
+	kate_commitment as kc, DataLookup, HeaderVersion,
 };
 use kate::{
 	couscous::multiproof_params,
@@ -20,9 +20,13 @@ use std::{sync::OnceLock, time::Instant, vec::Vec};
 
 static PMP: OnceLock<M1NoPrecomp> = OnceLock::new();
 
+/// Public parameters of the commitments, couscous has them for degree up to 1024.
+pub(super) fn pmp() -> &'static M1NoPrecomp {
+	PMP.get_or_init(multiproof_params)
+}
+
 #[cfg(feature = "std")]
 pub fn get_empty_header(data_root: H256, version: HeaderVersion) -> HeaderExtension {
-	use avail_core::DataLookup;
 	let empty_commitment: Vec<u8> = vec![];
 	let empty_app_lookup = DataLookup::new_empty();
 
@@ -58,8 +62,7 @@ pub fn build_grid(
 
 #[cfg(feature = "std")]
 pub fn build_commitment(grid: &EvaluationGrid) -> Result<Vec<u8>, String> {
-	// couscous has pp for degree upto 1024
-	let pmp = PMP.get_or_init(multiproof_params);
+	let pmp = pmp();
 
 	let poly_grid = grid
 		.make_polynomial_grid()
@@ -94,6 +97,19 @@ pub fn build_extension(
 	}
 	let build_extension_start = Instant::now();
 
+	// The block author may have built it already
+	if let Some(precomputed) = precompute::take(&submitted, &block_length, &seed) {
+		Metrics::observe_total_execution_time(build_extension_start.elapsed());
+		return to_extension(
+			precomputed.rows,
+			precomputed.cols,
+			data_root,
+			precomputed.commitment,
+			precomputed.lookup,
+			version,
+		);
+	}
+
 	// Build the grid
 	let timer = Instant::now();
 	let maybe_grid = build_grid(submitted, block_length, seed);
@@ -140,7 +156,25 @@ pub fn build_extension(
 
 	let app_lookup = grid.lookup().clone();
 
-	let extension = match version {
+	let extension = to_extension(rows, cols, data_root, commitment, app_lookup, version);
+
+	// Total Execution Time Metrics
+	let build_extension_time = build_extension_start.elapsed();
+	Metrics::observe_total_execution_time(build_extension_time);
+	header_extension_cost::observe(rows.into(), cols.into(), build_extension_time);
+
+	extension
+}
+
+fn to_extension(
+	rows: u16,
+	cols: u16,
+	data_root: H256,
+	commitment: Vec<u8>,
+	app_lookup: DataLookup,
+	version: HeaderVersion,
+) -> HeaderExtension {
+	match version {
 		HeaderVersion::V3 => {
 			let commitment = kc::v3::KateCommitment::new(rows, cols, data_root, commitment);
 			he::v3::HeaderExtension {
@@ -149,10 +183,5 @@ pub fn build_extension(
 			}
 			.into()
 		},
-	};
-
-	// Total Execution Time Metrics
-	Metrics::observe_total_execution_time(build_extension_start.elapsed());
-
-	extension
+	}
 }
@@ -28,6 +28,7 @@ pub const GENESIS_VALIDATOR_ROOT: H256 = H256(hex!(
 pub const GENESIS_TIME: u64 = 1655733600;
 pub const SECONDS_PER_SLOT: u64 = 12;
 pub const SOURCE_CHAIN_ID: u64 = 11155111;
+pub const CHALLENGE_DELAY: u64 = 30 * 60;
 pub const STEP_FUNCTION_ID: H256 = H256(hex!(
 	"55b63fe87aef4a2d5e6a141455c12964f2b5611a45a30104fc78cbda308c0ee3"
 ));
@@ -1,9 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit = "512"]
 
-use crate::{storage_utils::MessageStatusEnum, verifier::Verifier};
+use crate::verifier::{ProofSystem, VerificationError};
 use avail_base::{MemoryTemporaryStorage, ProvidePostInherent};
-use avail_core::data_proof::{tx_uid, AddressedMessage, Message, MessageType};
+use avail_core::data_proof::{
+	tx_uid, AddressedMessage, Message, MessageType, BOUNDED_DATA_MAX_LENGTH,
+};
 
 use codec::Compact;
 use frame_support::{
@@ -13,14 +15,16 @@ use frame_support::{
 };
 use sp_core::H256;
 use sp_io::{hashing::blake2_256, transaction_index};
-use sp_runtime::SaturatedConversion;
+use sp_runtime::{traits::Zero, SaturatedConversion};
 use sp_std::{vec, vec::Vec};
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 pub mod constants;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
+pub mod ssz;
 mod state;
 mod storage_utils;
 #[cfg(test)]
@@ -29,25 +33,134 @@ mod verifier;
 mod weights;
 
 pub use pallet::*;
+pub use state::Configuration;
+pub use storage_utils::MessageStatusEnum;
 
 pub type FunctionInput = BoundedVec<u8, ConstU32<256>>;
 pub type FunctionOutput = BoundedVec<u8, ConstU32<512>>;
 pub type FunctionProof = BoundedVec<u8, ConstU32<1048>>;
 pub type ValidProof = BoundedVec<BoundedVec<u8, ConstU32<2048>>, ConstU32<32>>;
+pub type MessageBatch = BoundedVec<AddressedMessage, ConstU32<MAX_BATCH_SIZE>>;
+pub type StorageProofBatch = BoundedVec<ValidProof, ConstU32<MAX_BATCH_SIZE>>;
+/// Receipts trie proof, whose leaf can hold a receipt emitting a message of the maximum size.
+pub type ReceiptTrieProof = BoundedVec<
+	BoundedVec<u8, ConstU32<MAX_RECEIPT_NODE_LENGTH>>,
+	ConstU32<MAX_RECEIPT_PROOF_NODES>,
+>;
+pub type SszBranch = BoundedVec<H256, ConstU32<{ ssz::MAX_BRANCH_DEPTH }>>;
 
 // Avail asset is supported for now
 pub const SUPPORTED_ASSET_ID: H256 = H256::zero();
 pub const FAILED_SEND_MSG_ID: &[u8] = b"vector:failed_send_msg_txs";
 pub const LOG_TARGET: &str = "runtime::vector";
+/// Topic of the `MessageSent(bytes)` event through which a broadcaster emits the ABI-encoded
+/// `AddressedMessage`, keccak("MessageSent(bytes)").
+pub const MESSAGE_SENT_TOPIC: H256 = H256(hex_literal::hex!(
+	"8c5261668696ce22758910d05bab8f186d6eb247ceac2af2e82c7dc17669b036"
+));
+/// Maximum length of a receipts trie node. Branch nodes take at most 532 bytes, leaves hold the
+/// receipt with the message log: its bloom filter, the ABI encoding overhead and small other logs.
+pub const MAX_RECEIPT_NODE_LENGTH: u32 = BOUNDED_DATA_MAX_LENGTH + 2048;
+/// Maximum number of nodes of a receipts trie proof. The keys are RLP encoded transaction indices,
+/// at most 3 bytes for the transactions of a block, so a proof has a branch node per nibble at most
+/// and the leaf.
+pub const MAX_RECEIPT_PROOF_NODES: u32 = 7;
+/// Maximum number of messages that can be executed with a single `execute_batch`.
+pub const MAX_BATCH_SIZE: u32 = 32;
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type RelayerFeeOf<T> = RelayerFee<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	frame_system::pallet_prelude::BlockNumberFor<T>,
+>;
+
+pub type PendingUpdateOf<T> = PendingUpdate<frame_system::pallet_prelude::BlockNumberFor<T>>;
+
+/// Maximum number of governance updates that can activate in the same block.
+pub const MAX_UPDATES_PER_BLOCK: u32 = 16;
+
+/// Change of a bridge security parameter that only takes effect after the governance delay.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum GovernanceUpdate {
+	/// Verification key of a function id in the encoding of `proof_system`, which becomes the
+	/// proof system of the function.
+	VerificationKey {
+		function_id: H256,
+		proof_system: ProofSystem,
+		value: Option<BoundedVec<u8, ConstU32<10_000>>>,
+	},
+	FunctionIds(Option<(H256, H256)>),
+	Broadcaster {
+		domain: u32,
+		broadcaster: H256,
+	},
+	Configuration(Configuration),
+	ChallengeDelay(u64),
+	InboundOrdering {
+		domain: u32,
+		next_nonce: Option<u64>,
+	},
+}
+
+/// Governance update waiting for its activation block.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct PendingUpdate<BlockNumber> {
+	pub update: GovernanceUpdate,
+	pub activates_at: BlockNumber,
+}
+
+/// Proofs that a beacon block header is an ancestor of a stored header, and of its slot and
+/// execution state root.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo)]
+pub struct HistoricalSlotProof {
+	/// Root of the historical beacon block header.
+	pub header_root: H256,
+	/// Branch of `header_root` in the `block_roots` of the anchor header state.
+	pub block_root_branch: SszBranch,
+	/// Branch of the slot in the historical header.
+	pub slot_branch: SszBranch,
+	/// Execution state root of the historical header.
+	pub execution_state_root: H256,
+	/// Branch of `execution_state_root` in the historical header.
+	pub execution_state_root_branch: SszBranch,
+}
+
+/// Proof that a message was emitted in a transaction receipt of an execution block.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo)]
+pub struct ReceiptProof {
+	/// Receipts root of the execution payload of the slot.
+	pub receipts_root: H256,
+	/// Branch of `receipts_root` in the beacon block header of the slot.
+	pub receipts_root_branch: SszBranch,
+	/// Index of the transaction in the execution block.
+	pub tx_index: u64,
+	/// Receipts trie nodes proving the receipt of the transaction.
+	pub receipt_proof: ReceiptTrieProof,
+	/// Index of the message log in the receipt.
+	pub log_index: u32,
+}
+
+/// Fee escrowed for relaying an outbound message to its destination domain.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RelayerFee<AccountId, Balance, BlockNumber> {
+	/// Account that paid the fee, it gets the fee back if the message is not delivered in time.
+	pub payer: AccountId,
+	pub amount: Balance,
+	/// Root of the addressed message, its delivery has to be proven to claim the fee.
+	pub message_root: H256,
+	pub destination_domain: u32,
+	/// Block from which on the fee can be refunded to the payer.
+	pub refundable_at: BlockNumber,
+}
 
 #[frame_support::pallet]
 pub mod pallet {
 	use ethabi::Token;
 	use ethabi::Token::Uint;
 	use frame_support::dispatch::GetDispatchInfo;
+	use frame_support::storage::with_storage_layer;
 	use frame_support::traits::{LockableCurrency, UnfilteredDispatchable};
 	use frame_support::{pallet_prelude::ValueQuery, DefaultNoBound};
 	use frame_system::pallet_prelude::*;
@@ -62,11 +175,14 @@ pub mod pallet {
 	use crate::state::{
 		parse_rotate_output, parse_step_output, VerifiedRotate, VerifiedStep, VerifiedStepOutput,
 	};
-	use crate::storage_utils::{get_storage_root, get_storage_value};
+	use crate::storage_utils::{get_receipt, get_storage_root, get_storage_value};
 	use crate::verifier::encode_packed;
 
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
 	#[pallet::error]
 	pub enum Error<T> {
 		VerificationError,
@@ -106,6 +222,44 @@ pub mod pallet {
 		BadContext,
 		/// Invalid FailedIndices
 		InvalidFailedIndices,
+		/// Message batch is empty
+		EmptyBatch,
+		/// Number of storage proofs does not match the number of messages in the batch
+		BatchLengthMismatch,
+		/// Messages in the batch do not share the same origin domain
+		BatchOriginDomainMismatch,
+		/// Relayer fee must be greater than zero
+		InvalidRelayerFee,
+		/// No relayer fee is escrowed for the message
+		RelayerFeeNotFound,
+		/// Relayer fee cannot be refunded before its timeout
+		RelayerFeeNotRefundable,
+		/// Message is not marked as delivered on the destination chain
+		MessageNotDelivered,
+		/// No pending governance update with the given id
+		UpdateNotFound,
+		/// Too many governance updates activate in the same block
+		TooManyPendingUpdates,
+		/// Challenge delay of the slot has not elapsed yet
+		ChallengePeriodNotElapsed,
+		/// Challenge window of the slot is already closed
+		ChallengeWindowClosed,
+		/// Slot roots are not set
+		SlotRootsNotSet,
+		/// SSZ proof does not match the header root
+		InvalidSszProof,
+		/// Slot is not covered by the block roots of the anchor slot
+		SlotOutOfHistoricalRange,
+		/// Receipt cannot be read from the receipts trie proof
+		CannotGetReceipt,
+		/// Transaction of the receipt was reverted
+		ReceiptNotSuccessful,
+		/// Log is missing or was not emitted by the broadcaster as a message
+		InvalidMessageLog,
+		/// Message id is not the next one expected from its origin domain
+		MessageOutOfOrder,
+		/// Delivery record does not hold a valid relayer account
+		CannotDecodeRelayerAccountId,
 	}
 
 	#[pallet::event]
@@ -128,6 +282,12 @@ pub mod pallet {
 			message_id: u64,
 			message_root: H256,
 		},
+		/// Emit when a message of a batch could not be executed.
+		MessageExecutionFailed {
+			message_id: u64,
+			message_root: H256,
+			error: DispatchError,
+		},
 		/// Emit if source chain gets frozen.
 		SourceChainFrozen { source_chain_id: u32, frozen: bool },
 		/// Emit when message is submitted.
@@ -137,6 +297,7 @@ pub mod pallet {
 			message_type: MessageType,
 			destination_domain: u32,
 			message_id: u64,
+			nonce: u64,
 		},
 		/// Emit whitelisted domains that are updated.
 		WhitelistedDomainsUpdated,
@@ -147,13 +308,60 @@ pub mod pallet {
 		},
 		/// Emit function Ids that are updated.
 		FunctionIdsUpdated { value: Option<(H256, H256)> },
-		/// Emit updated step verification key.
-		StepVerificationKeyUpdated {
+		/// Emit when the verification key and proof system of a function id are updated.
+		VerificationKeyUpdated {
+			function_id: H256,
+			proof_system: ProofSystem,
 			value: Option<BoundedVec<u8, ConstU32<10_000>>>,
 		},
-		/// Emit updated rotate verification key.
-		RotateVerificationKeyUpdated {
-			value: Option<BoundedVec<u8, ConstU32<10_000>>>,
+		/// Emit when a relayer fee is escrowed for an outbound message.
+		RelayerFeeEscrowed {
+			message_id: u64,
+			payer: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// Emit when a relayer claims the fee of a delivered message.
+		RelayerFeeClaimed {
+			message_id: u64,
+			relayer: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// Emit when the fee of an undelivered message is refunded to its payer.
+		RelayerFeeRefunded {
+			message_id: u64,
+			payer: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// Emit when a governance update is staged, it takes effect at `activates_at`.
+		UpdateScheduled {
+			id: u32,
+			update: GovernanceUpdate,
+			activates_at: BlockNumberFor<T>,
+		},
+		/// Emit when a pending governance update is cancelled.
+		UpdateCancelled { id: u32 },
+		/// Emit when the challenge delay is updated.
+		ChallengeDelayUpdated { value: u64 },
+		/// Emit when in-order delivery of the messages of `domain` is enabled or disabled.
+		InboundOrderingUpdated {
+			domain: u32,
+			next_nonce: Option<u64>,
+		},
+		/// Emit when the roots of a slot are invalidated during its challenge window.
+		SlotInvalidated { slot: u64 },
+		/// Emit when the roots of a slot are proven against the header of a later slot.
+		HistoricalSlotProven {
+			slot: u64,
+			anchor_slot: u64,
+			header_root: H256,
+			execution_state_root: H256,
+		},
+		/// Emit when the proof of a function that is neither the step nor the rotate function is
+		/// verified.
+		FunctionCallVerified {
+			function_id: H256,
+			input_hash: H256,
+			output_hash: H256,
 		},
 	}
 
@@ -171,6 +379,11 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type Timestamps<T> = StorageMap<_, Identity, u64, u64, ValueQuery>;
 
+	/// Number of seconds after a slot's timestamp before messages can be proven against it.
+	#[pallet::storage]
+	#[pallet::getter(fn challenge_delay)]
+	pub type ChallengeDelay<T> = StorageValue<_, u64, ValueQuery>;
+
 	/// Maps from a slot to the current finalized ethereum execution state root.
 	#[pallet::storage]
 	pub type ExecutionStateRoots<T> = StorageMap<_, Identity, u64, H256, ValueQuery>;
@@ -188,6 +401,47 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type MessageStatus<T> = StorageMap<_, Identity, H256, MessageStatusEnum, ValueQuery>;
 
+	/// Maps (origin domain, message id) to the root of the executed message.
+	#[pallet::storage]
+	pub type ExecutedMessageRoots<T> =
+		StorageDoubleMap<_, Identity, u32, Identity, u64, H256, OptionQuery>;
+
+	/// Maps (origin domain, destination domain) to the nonce of the next outbound message.
+	/// The nonce is not part of the `AddressedMessage` committed in the bridge root: the leaves are
+	/// built by the `TxDataFilter` from the call, block and transaction index alone, also natively
+	/// by the node, so relayers read it from `MessageSubmitted` or the runtime API.
+	#[pallet::storage]
+	pub type OutboundNonces<T> = StorageDoubleMap<_, Identity, u32, Identity, u32, u64, ValueQuery>;
+
+	/// Maps origin domains with in-order delivery to the id of the next message to execute.
+	#[pallet::storage]
+	pub type InboundNonces<T> = StorageMap<_, Identity, u32, u64, OptionQuery>;
+
+	/// Governance updates that are waiting for their activation delay to pass.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_updates)]
+	pub type PendingUpdates<T: Config> =
+		StorageMap<_, Identity, u32, PendingUpdateOf<T>, OptionQuery>;
+
+	/// Ids of the pending governance updates that activate in a block.
+	#[pallet::storage]
+	pub type UpdateAgenda<T: Config> = StorageMap<
+		_,
+		Identity,
+		BlockNumberFor<T>,
+		BoundedVec<u32, ConstU32<MAX_UPDATES_PER_BLOCK>>,
+		ValueQuery,
+	>;
+
+	/// Id of the next staged governance update.
+	#[pallet::storage]
+	pub type NextUpdateId<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Relayer fees escrowed for outbound messages, keyed by message id.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_fees)]
+	pub type RelayerFees<T: Config> = StorageMap<_, Identity, u64, RelayerFeeOf<T>, OptionQuery>;
+
 	/// Mapping between source chainId and the address of the broadcaster on that chain.
 	#[pallet::storage]
 	pub type Broadcasters<T> = StorageMap<_, Identity, u32, H256, ValueQuery>;
@@ -209,17 +463,18 @@ pub mod pallet {
 	#[pallet::getter(fn function_ids)]
 	pub type FunctionIds<T: Config> = StorageValue<_, Option<(H256, H256)>, ValueQuery>;
 
-	/// Step verification key storage.
+	/// Maps a function id to its verification key, in the encoding of the proof system of the
+	/// function.
 	#[pallet::storage]
-	#[pallet::getter(fn step_verification_key)]
-	pub type StepVerificationKey<T: Config> =
-		StorageValue<_, Option<BoundedVec<u8, ConstU32<10_000>>>, ValueQuery>;
+	#[pallet::getter(fn verification_key)]
+	pub type VerificationKeys<T> =
+		StorageMap<_, Identity, H256, BoundedVec<u8, ConstU32<10_000>>, OptionQuery>;
 
-	/// Rotate verification key storage.
+	/// Function ids registered for `fulfill_call`, with the proof system that verifies their
+	/// proofs. A function id is registered along with its verification key.
 	#[pallet::storage]
-	#[pallet::getter(fn rotate_verification_key)]
-	pub type RotateVerificationKey<T: Config> =
-		StorageValue<_, Option<BoundedVec<u8, ConstU32<10_000>>>, ValueQuery>;
+	#[pallet::getter(fn function_proof_system)]
+	pub type FunctionProofSystems<T> = StorageMap<_, Identity, H256, ProofSystem, OptionQuery>;
 
 	/// Genesis validator root, used to check initialization.
 	#[pallet::storage]
@@ -264,6 +519,7 @@ pub mod pallet {
 		impl DefaultConfig for TestDefaultConfig {
 			type WeightInfo = ();
 			type MessageMappingStorageIndex = ConstU64<1>;
+			type DeliveryMappingStorageIndex = ConstU64<0>;
 			type AvailDomain = ConstU32<1>;
 			#[inject_runtime_type]
 			type RuntimeEvent = ();
@@ -295,6 +551,21 @@ pub mod pallet {
 		/// This is mandatory when calling execute messages via storage proofs.
 		#[pallet::constant]
 		type MessageMappingStorageIndex: Get<u64>;
+		/// The index of the mapping in the destination bridge contract from delivered message roots to
+		/// the account of their relayer. This is mandatory when claiming relayer fees via storage proofs.
+		#[pallet::constant]
+		type DeliveryMappingStorageIndex: Get<u64>;
+		/// Number of blocks between staging a governance update and its activation.
+		#[pallet::constant]
+		#[pallet::no_default]
+		type GovernanceDelay: Get<BlockNumberFor<Self>>;
+		/// Origin that can cancel pending governance updates.
+		#[pallet::no_default]
+		type CancelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Number of blocks after which an unclaimed relayer fee can be refunded to its payer.
+		#[pallet::constant]
+		#[pallet::no_default]
+		type RelayerFeeTimeout: Get<BlockNumberFor<Self>>;
 		/// Bridge's pallet id, used for deriving its sovereign account ID.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
@@ -320,6 +591,7 @@ pub mod pallet {
 		pub genesis_time: u64,
 		pub seconds_per_slot: u64,
 		pub source_chain_id: u64,
+		pub challenge_delay: u64,
 		pub _phantom: PhantomData<T>,
 	}
 
@@ -345,14 +617,18 @@ pub mod pallet {
 
 			FunctionIds::<T>::set(Some(self.function_ids));
 
-			let step_verification_key = BoundedVec::try_from(self.step_verification_key.clone())
-				.expect("Step verification key should be valid at genesis.");
-			StepVerificationKey::<T>::set(Some(step_verification_key));
+			let proof_system = ProofSystem::default();
+			let step_verification_key =
+				Pallet::<T>::prepare_verification_key(proof_system, &self.step_verification_key)
+					.expect("Step verification key should be valid at genesis.");
+			FunctionProofSystems::<T>::insert(self.function_ids.0, proof_system);
+			VerificationKeys::<T>::insert(self.function_ids.0, step_verification_key);
 
 			let rotate_verification_key =
-				BoundedVec::try_from(self.rotate_verification_key.clone())
+				Pallet::<T>::prepare_verification_key(proof_system, &self.rotate_verification_key)
 					.expect("Rotate verification key should be valid at genesis.");
-			RotateVerificationKey::<T>::set(Some(rotate_verification_key));
+			FunctionProofSystems::<T>::insert(self.function_ids.1, proof_system);
+			VerificationKeys::<T>::insert(self.function_ids.1, rotate_verification_key);
 
 			SyncCommitteePoseidons::<T>::insert(self.period, self.sync_committee_poseidon);
 
@@ -363,22 +639,25 @@ pub mod pallet {
 			SecondsPerSlot::<T>::set(self.seconds_per_slot);
 
 			SourceChainId::<T>::set(self.source_chain_id);
+
+			ChallengeDelay::<T>::set(self.challenge_delay);
 		}
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
 			if let Some(failed_txs) =
 				MemoryTemporaryStorage::take::<Vec<Compact<u32>>>(FAILED_SEND_MSG_ID)
 			{
 				log::trace!(target: LOG_TARGET, "Failed Txs cleaned: {failed_txs:?}");
 			}
 
-			Weight::zero()
+			Self::enact_updates(n)
 		}
 	}
 
@@ -388,6 +667,9 @@ pub mod pallet {
 		[u8; 32]: From<T::AccountId>,
 	{
 		/// The entrypoint for fulfilling a call.
+		/// The proof is verified with the proof system registered for the function id. Step and
+		/// rotate outputs then update the light client, the calls of other registered functions are
+		/// only verified.
 		/// function_id Function identifier.
 		/// input Function input.
 		/// output Function output.
@@ -407,17 +689,23 @@ pub mod pallet {
 			let config = ConfigurationStorage::<T>::get();
 			let input_hash = H256(sha2_256(input.as_slice()));
 			let output_hash = H256(sha2_256(output.as_slice()));
-			let (step_function_id, rotate_function_id) = Self::get_function_ids()?;
-			let verifier = Self::get_verifier(function_id, step_function_id, rotate_function_id)?;
-
-			let is_success = verifier
-				.verify(input_hash, output_hash, proof.to_vec())
-				.map_err(|_| Error::<T>::VerificationError)?;
+			let proof_system = FunctionProofSystems::<T>::get(function_id)
+				.ok_or(Error::<T>::FunctionIdNotKnown)?;
+			let vk = VerificationKeys::<T>::get(function_id)
+				.ok_or(Error::<T>::VerificationKeyIsNotSet)?;
+
+			let is_success = proof_system
+				.verify(vk.as_slice(), input_hash, output_hash, proof.to_vec())
+				.map_err(|e| match e {
+					VerificationError::InvalidVK => Error::<T>::MalformedVerificationKey,
+					VerificationError::InvalidProof => Error::<T>::VerificationError,
+				})?;
 
 			// make sure that verification call is valid
 			ensure!(is_success, Error::<T>::VerificationFailed);
 
 			// verification is success and, we can safely parse and validate output
+			let (step_function_id, rotate_function_id) = Self::get_function_ids()?;
 			if function_id == step_function_id {
 				let vs =
 					VerifiedStep::new(function_id, input_hash, parse_step_output(output.to_vec()));
@@ -442,7 +730,11 @@ pub mod pallet {
 					root: vr.sync_committee_poseidon,
 				});
 			} else {
-				return Err(Error::<T>::FunctionIdNotKnown.into());
+				Self::deposit_event(Event::FunctionCallVerified {
+					function_id,
+					input_hash,
+					output_hash,
+				});
 			}
 
 			Ok(().into())
@@ -473,61 +765,62 @@ pub mod pallet {
 				!SourceChainFrozen::<T>::get(addr_message.origin_domain),
 				Error::<T>::SourceChainFrozen
 			);
-			let root = ExecutionStateRoots::<T>::get(slot);
-			let broadcaster = Broadcasters::<T>::get(addr_message.origin_domain);
-
-			// extract contract address
-			let contract_broadcaster_address = H160::from_slice(broadcaster[..20].as_ref());
-			let account_proof_vec = account_proof
-				.iter()
-				.map(|inner_bounded_vec| inner_bounded_vec.iter().copied().collect())
-				.collect();
-
 			let storage_root =
-				get_storage_root(account_proof_vec, contract_broadcaster_address, root)
-					.map_err(|_| Error::<T>::CannotGetStorageRoot)?;
+				Self::broadcaster_storage_root(slot, addr_message.origin_domain, &account_proof)?;
 
-			let message_id = Uint(U256::from(addr_message.id));
-			let mm_idx = Uint(U256::from(T::MessageMappingStorageIndex::get()));
-			let slot_key = H256(keccak_256(ethabi::encode(&[message_id, mm_idx]).as_slice()));
-
-			let storage_proof_vec = storage_proof
-				.iter()
-				.map(|inner_bounded_vec| inner_bounded_vec.iter().copied().collect())
-				.collect();
+			Self::verify_and_execute_message(
+				storage_root,
+				addr_message,
+				message_root,
+				&storage_proof,
+			)?;
 
-			let slot_value = get_storage_value(slot_key, storage_root, storage_proof_vec)
-				.map_err(|_| Error::<T>::CannotGetStorageValue)?;
+			Ok(().into())
+		}
 
-			ensure!(slot_value == message_root, Error::<T>::InvalidMessageHash);
+		/// Executes a message emitted by the broadcaster as a `MessageSent` event, proven through the
+		/// receipts trie of the execution block of `slot` instead of the broadcaster storage.
+		//
+		// Test names: execute_via_receipt_works(), execute_via_receipt_does_not_work_with_invalid_log(),
+		// execute_via_receipt_does_not_work_with_invalid_receipts_root()
+		#[pallet::call_index(21)]
+		#[pallet::weight(weight_helper::execute_via_receipt::<T>(addr_message, proof))]
+		pub fn execute_via_receipt(
+			origin: OriginFor<T>,
+			#[pallet::compact] slot: u64,
+			addr_message: AddressedMessage,
+			proof: ReceiptProof,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let encoded_data = addr_message.clone().abi_encode();
+			let message_root = H256(keccak_256(encoded_data.as_slice()));
 
-			if let Message::FungibleToken { asset_id, amount } = &addr_message.message {
-				ensure!(
-					SUPPORTED_ASSET_ID == *asset_id,
-					Error::<T>::AssetNotSupported
-				);
+			Self::check_preconditions(&addr_message, message_root)?;
 
-				let destination_account_id =
-					T::AccountId::decode(&mut &addr_message.to.encode()[..])
-						.map_err(|_| Error::<T>::CannotDecodeDestinationAccountId)?;
+			ensure!(
+				!SourceChainFrozen::<T>::get(addr_message.origin_domain),
+				Error::<T>::SourceChainFrozen
+			);
 
-				T::Currency::transfer(
-					&Self::account_id(),
-					&destination_account_id,
-					(*amount).saturated_into(),
-					ExistenceRequirement::AllowDeath,
-				)?;
-			}
+			Self::verify_receipt_and_execute_message(slot, addr_message, message_root, &proof)
+		}
 
-			MessageStatus::<T>::set(message_root, MessageStatusEnum::ExecutionSucceeded);
-			Self::deposit_event(Event::<T>::MessageExecuted {
-				from: addr_message.from,
-				to: addr_message.to,
-				message_id: addr_message.id,
-				message_root,
-			});
+		/// Enforces in-order execution of the messages of `domain`, starting from the message
+		/// with id `next_nonce`, or lifts it if `next_nonce` is `None`. The change is staged and
+		/// takes effect after `GovernanceDelay` blocks.
+		//
+		// Test names: set_inbound_ordering_works_with_root(), set_inbound_ordering_does_not_work_with_non_root(),
+		// execute_does_not_work_out_of_order()
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::set_inbound_ordering())]
+		pub fn set_inbound_ordering(
+			origin: OriginFor<T>,
+			#[pallet::compact] domain: u32,
+			next_nonce: Option<u64>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
 
-			Ok(().into())
+			Self::schedule_update(GovernanceUpdate::InboundOrdering { domain, next_nonce })
 		}
 
 		/// source_chain_froze froze source chain and prevent messages to be executed.
@@ -575,15 +868,7 @@ pub mod pallet {
 
 			let dispatch = Self::do_send_message(who, message, to, domain);
 			if dispatch.is_err() {
-				let _ = MemoryTemporaryStorage::update::<Vec<Compact<u32>>, _>(
-					FAILED_SEND_MSG_ID.to_vec(),
-					|failed| {
-						let tx_idx =
-							<frame_system::Pallet<T>>::extrinsic_index().unwrap_or_default();
-						failed.push(tx_idx.into());
-						log::trace!(target: LOG_TARGET, "Send Message failed txs: {failed:?}");
-					},
-				);
+				Self::note_failed_send_message();
 			}
 
 			dispatch
@@ -609,6 +894,7 @@ pub mod pallet {
 		}
 
 		/// set_broadcaster sets the broadcaster address of the message from the origin chain.
+		/// The change is staged and takes effect after `GovernanceDelay` blocks.
 		//
 		// Test names: set_broadcaster_works_with_root(), set_broadcaster_does_not_work_with_non_root()
 		#[pallet::call_index(5)]
@@ -619,17 +905,11 @@ pub mod pallet {
 			broadcaster: H256,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			let old_bc = Broadcasters::<T>::get(broadcaster_domain);
-
-			Broadcasters::<T>::set(broadcaster_domain, broadcaster);
 
-			Self::deposit_event(Event::BroadcasterUpdated {
-				old: old_bc,
-				new: broadcaster,
+			Self::schedule_update(GovernanceUpdate::Broadcaster {
 				domain: broadcaster_domain,
-			});
-
-			Ok(())
+				broadcaster,
+			})
 		}
 
 		/// The set_whitelisted_domains function allows the root (administrator) to set the whitelisted domains. It is a
@@ -652,22 +932,19 @@ pub mod pallet {
 
 		/// The set_configuration function allows the root (administrator) to set the configuration. It is a
 		/// privileged function intended for administrative purposes, used to manage slots_per_period and finality_threshold values.
+		/// The change is staged and takes effect after `GovernanceDelay` blocks.
 		//
 		// Test names: set_configuration_works_with_root(), set_configuration_does_not_work_with_non_root()
 		#[pallet::call_index(7)]
 		#[pallet::weight(T::WeightInfo::set_configuration())]
 		pub fn set_configuration(origin: OriginFor<T>, value: Configuration) -> DispatchResult {
 			ensure_root(origin)?;
-			ConfigurationStorage::<T>::put(value);
-
-			Self::deposit_event(Event::ConfigurationUpdated {
-				slots_per_period: value.slots_per_period,
-				finality_threshold: value.finality_threshold,
-			});
 
-			Ok(())
+			Self::schedule_update(GovernanceUpdate::Configuration(value))
 		}
 
+		/// Sets the step and rotate function ids.
+		/// The change is staged and takes effect after `GovernanceDelay` blocks.
 		#[pallet::call_index(8)]
 		#[pallet::weight(T::WeightInfo::set_function_ids())]
 		pub fn set_function_ids(
@@ -675,13 +952,13 @@ pub mod pallet {
 			value: Option<(H256, H256)>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			FunctionIds::<T>::put(value);
-
-			Self::deposit_event(Event::FunctionIdsUpdated { value });
 
-			Ok(())
+			Self::schedule_update(GovernanceUpdate::FunctionIds(value))
 		}
 
+		/// Sets the verification key of the current step function id. The key is validated and
+		/// stored in the encoding of the proof system registered for the function, and takes effect
+		/// after `GovernanceDelay` blocks.
 		#[pallet::call_index(9)]
 		#[pallet::weight(T::WeightInfo::set_step_verification_key())]
 		pub fn set_step_verification_key(
@@ -689,18 +966,15 @@ pub mod pallet {
 			value: Option<BoundedVec<u8, ConstU32<10_000>>>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			if let Some(vk) = value.clone() {
-				let _ = Verifier::from_json_u8_slice(vk.as_slice())
-					.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-			}
+			let (step_function_id, _) = Self::get_function_ids()?;
+			let proof_system = FunctionProofSystems::<T>::get(step_function_id).unwrap_or_default();
 
-			StepVerificationKey::<T>::put(value.clone());
-
-			Self::deposit_event(Event::StepVerificationKeyUpdated { value });
-
-			Ok(())
+			Self::schedule_verification_key(step_function_id, proof_system, value)
 		}
 
+		/// Sets the verification key of the current rotate function id. The key is validated and
+		/// stored in the encoding of the proof system registered for the function, and takes effect
+		/// after `GovernanceDelay` blocks.
 		#[pallet::call_index(10)]
 		#[pallet::weight(T::WeightInfo::set_rotate_verification_key())]
 		pub fn set_rotate_verification_key(
@@ -708,16 +982,11 @@ pub mod pallet {
 			value: Option<BoundedVec<u8, ConstU32<10_000>>>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			if let Some(vk) = value.clone() {
-				let _ = Verifier::from_json_u8_slice(vk.as_slice())
-					.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-			}
-
-			RotateVerificationKey::<T>::put(value.clone());
+			let (_, rotate_function_id) = Self::get_function_ids()?;
+			let proof_system =
+				FunctionProofSystems::<T>::get(rotate_function_id).unwrap_or_default();
 
-			Self::deposit_event(Event::RotateVerificationKeyUpdated { value });
-
-			Ok(())
+			Self::schedule_verification_key(rotate_function_id, proof_system, value)
 		}
 
 		#[pallet::call_index(11)]
@@ -751,6 +1020,344 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Registers the proof system used to verify proofs of the given function id, together with
+		/// the verification key of the function in the format of that proof system. `None` removes
+		/// the key. The change is staged and takes effect after `GovernanceDelay` blocks.
+		//
+		// Test names: set_function_proof_system_works_with_root(), set_function_proof_system_does_not_work_with_non_root(),
+		// set_function_proof_system_validates_key()
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::set_function_proof_system())]
+		pub fn set_function_proof_system(
+			origin: OriginFor<T>,
+			function_id: H256,
+			proof_system: ProofSystem,
+			verification_key: Option<BoundedVec<u8, ConstU32<10_000>>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::schedule_verification_key(function_id, proof_system, verification_key)
+		}
+
+		/// Executes a batch of messages from one origin domain, proven against the execution state root of `slot`.
+		///
+		/// The broadcaster account proof is verified once for the whole batch. Each message is then
+		/// checked against its own storage proof and executed independently, so a failing message
+		/// does not revert the others and is reported with a `MessageExecutionFailed` event.
+		#[pallet::call_index(13)]
+		#[pallet::weight(weight_helper::execute_batch::<T>(messages))]
+		pub fn execute_batch(
+			origin: OriginFor<T>,
+			#[pallet::compact] slot: u64,
+			messages: MessageBatch,
+			account_proof: ValidProof,
+			storage_proofs: StorageProofBatch,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(!messages.is_empty(), Error::<T>::EmptyBatch);
+			ensure!(
+				messages.len() == storage_proofs.len(),
+				Error::<T>::BatchLengthMismatch
+			);
+
+			// The account proof is shared, so all messages need to come from the same broadcaster.
+			let origin_domain = messages[0].origin_domain;
+			ensure!(
+				messages.iter().all(|m| m.origin_domain == origin_domain),
+				Error::<T>::BatchOriginDomainMismatch
+			);
+			ensure!(
+				!SourceChainFrozen::<T>::get(origin_domain),
+				Error::<T>::SourceChainFrozen
+			);
+			let storage_root = Self::broadcaster_storage_root(slot, origin_domain, &account_proof)?;
+
+			for (addr_message, storage_proof) in messages.into_iter().zip(storage_proofs.iter()) {
+				let message_id = addr_message.id;
+				let encoded_data = addr_message.clone().abi_encode();
+				let message_root = H256(keccak_256(encoded_data.as_slice()));
+
+				let result = with_storage_layer(|| {
+					Self::check_preconditions(&addr_message, message_root)?;
+					Self::verify_and_execute_message(
+						storage_root,
+						addr_message,
+						message_root,
+						storage_proof,
+					)
+				});
+
+				if let Err(error) = result {
+					Self::deposit_event(Event::<T>::MessageExecutionFailed {
+						message_id,
+						message_root,
+						error,
+					});
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Sends a message like `send_message` and escrows `relayer_fee` in the pallet pot.
+		/// The fee goes to the relayer recorded at the delivery of the message with `claim_relayer_fee`,
+		/// or back to the sender with `refund_relayer_fee` once `RelayerFeeTimeout` has passed.
+		//
+		// Test names:
+		//	send_message_with_fee_works(), send_message_with_fee_does_not_accept_zero_fee()
+		#[pallet::call_index(14)]
+		#[pallet::weight({
+			match message {
+				Message::ArbitraryMessage(ref data) => T::WeightInfo::send_message_with_fee_arbitrary_message(data.len() as u32),
+				Message::FungibleToken{..} => T::WeightInfo::send_message_with_fee_fungible_token(),
+			}
+		})]
+		pub fn send_message_with_fee(
+			origin: OriginFor<T>,
+			message: Message,
+			to: H256,
+			#[pallet::compact] domain: u32,
+			relayer_fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let message_id = Self::fetch_curr_message_id();
+			let from: [u8; 32] = who.clone().into();
+			let addr_message = AddressedMessage::new(
+				message.clone(),
+				H256(from),
+				to,
+				T::AvailDomain::get(),
+				domain,
+				message_id,
+			);
+			let message_root = H256(keccak_256(addr_message.abi_encode().as_slice()));
+
+			let dispatch =
+				Self::do_send_message(who.clone(), message, to, domain).and_then(|post_info| {
+					Self::escrow_relayer_fee(who, message_id, message_root, domain, relayer_fee)?;
+					Ok(post_info)
+				});
+			if dispatch.is_err() {
+				Self::note_failed_send_message();
+			}
+
+			dispatch
+		}
+
+		/// Pays the relayer fee of message `message_id` to the relayer that delivered the message.
+		/// The caller proves the delivery record of the message root in the storage of the
+		/// destination bridge contract, at the execution state root of `slot`. The record holds the
+		/// account of the relayer, so the fee cannot be taken by whoever submits the proof first.
+		//
+		// Test names:
+		//	claim_relayer_fee_works(), claim_relayer_fee_does_not_work_without_fee(),
+		//	claim_relayer_fee_does_not_work_with_faulty_account_proof()
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::claim_relayer_fee())]
+		pub fn claim_relayer_fee(
+			origin: OriginFor<T>,
+			#[pallet::compact] message_id: u64,
+			#[pallet::compact] slot: u64,
+			account_proof: ValidProof,
+			storage_proof: ValidProof,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let fee = RelayerFees::<T>::get(message_id).ok_or(Error::<T>::RelayerFeeNotFound)?;
+
+			let storage_root =
+				Self::broadcaster_storage_root(slot, fee.destination_domain, &account_proof)?;
+			let slot_key = Self::delivery_slot_key(fee.message_root);
+			let delivered =
+				get_storage_value(slot_key, storage_root, Self::proof_nodes(&storage_proof))
+					.map_err(|_| Error::<T>::CannotGetStorageValue)?;
+			ensure!(!delivered.is_zero(), Error::<T>::MessageNotDelivered);
+			let relayer = T::AccountId::decode(&mut delivered.as_bytes())
+				.map_err(|_| Error::<T>::CannotDecodeRelayerAccountId)?;
+
+			RelayerFees::<T>::remove(message_id);
+			T::Currency::transfer(
+				&Self::account_id(),
+				&relayer,
+				fee.amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(Event::RelayerFeeClaimed {
+				message_id,
+				relayer,
+				amount: fee.amount,
+			});
+
+			Ok(())
+		}
+
+		/// Refunds the relayer fee of an undelivered message `message_id` to its payer,
+		/// once `RelayerFeeTimeout` blocks have passed since it was sent.
+		//
+		// Test names:
+		//	refund_relayer_fee_works_after_timeout(), refund_relayer_fee_does_not_work_before_timeout()
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::refund_relayer_fee())]
+		pub fn refund_relayer_fee(
+			origin: OriginFor<T>,
+			#[pallet::compact] message_id: u64,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let fee = RelayerFees::<T>::get(message_id).ok_or(Error::<T>::RelayerFeeNotFound)?;
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() >= fee.refundable_at,
+				Error::<T>::RelayerFeeNotRefundable
+			);
+
+			RelayerFees::<T>::remove(message_id);
+			T::Currency::transfer(
+				&Self::account_id(),
+				&fee.payer,
+				fee.amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(Event::RelayerFeeRefunded {
+				message_id,
+				payer: fee.payer,
+				amount: fee.amount,
+			});
+
+			Ok(())
+		}
+
+		/// Cancels a pending governance update before it takes effect.
+		//
+		// Test names: cancel_update_works(), cancel_update_does_not_work_with_wrong_origin()
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::cancel_update())]
+		pub fn cancel_update(origin: OriginFor<T>, #[pallet::compact] id: u32) -> DispatchResult {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let pending = PendingUpdates::<T>::take(id).ok_or(Error::<T>::UpdateNotFound)?;
+			UpdateAgenda::<T>::mutate(pending.activates_at, |ids| ids.retain(|i| *i != id));
+
+			Self::deposit_event(Event::UpdateCancelled { id });
+
+			Ok(())
+		}
+
+		/// Sets the number of seconds that must pass after a slot is finalized before messages can
+		/// be executed against it. The change is staged and takes effect after `GovernanceDelay` blocks.
+		//
+		// Test names: set_challenge_delay_works_with_root(), set_challenge_delay_does_not_work_with_non_root()
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::set_challenge_delay())]
+		pub fn set_challenge_delay(
+			origin: OriginFor<T>,
+			#[pallet::compact] value: u64,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::schedule_update(GovernanceUpdate::ChallengeDelay(value))
+		}
+
+		/// Removes the header and execution state roots of a slot whose light-client update turned
+		/// out to be faulty. Only possible while the slot is still in its challenge window.
+		/// If the slot is the head, the head moves back so that a step update can fulfill the slot
+		/// again. A slot behind the head can only be fulfilled again with `fulfill_historical_slot`.
+		//
+		// Test names: invalidate_slot_works_with_root(), invalidate_slot_does_not_work_with_non_root(),
+		// invalidate_slot_does_not_work_after_challenge_window(), invalidate_slot_allows_refulfilling_the_head()
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::invalidate_slot())]
+		pub fn invalidate_slot(
+			origin: OriginFor<T>,
+			#[pallet::compact] slot: u64,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				ExecutionStateRoots::<T>::contains_key(slot),
+				Error::<T>::SlotRootsNotSet
+			);
+			ensure!(
+				T::TimeProvider::now().as_secs() < Self::executable_at(slot),
+				Error::<T>::ChallengeWindowClosed
+			);
+
+			Headers::<T>::remove(slot);
+			ExecutionStateRoots::<T>::remove(slot);
+			Timestamps::<T>::remove(slot);
+			if Head::<T>::get() == slot {
+				Head::<T>::set(slot.saturating_sub(1));
+			}
+
+			Self::deposit_event(Event::SlotInvalidated { slot });
+
+			Ok(())
+		}
+
+		/// Stores the header and execution state roots of `slot`, proven through the `block_roots`
+		/// of the stored header of `anchor_slot`. The anchor has to be past its challenge window, so
+		/// that it can no longer be invalidated, and the roots get their own challenge window.
+		//
+		// Test names: fulfill_historical_slot_works(), fulfill_historical_slot_does_not_work_with_invalid_proof(),
+		// fulfill_historical_slot_does_not_work_out_of_range(),
+		// fulfill_historical_slot_does_not_work_with_anchor_in_challenge_window()
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::fulfill_historical_slot())]
+		pub fn fulfill_historical_slot(
+			origin: OriginFor<T>,
+			#[pallet::compact] anchor_slot: u64,
+			#[pallet::compact] slot: u64,
+			proof: HistoricalSlotProof,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(
+				slot < anchor_slot && anchor_slot - slot <= ssz::SLOTS_PER_HISTORICAL_ROOT,
+				Error::<T>::SlotOutOfHistoricalRange
+			);
+			ensure!(
+				T::TimeProvider::now().as_secs() >= Self::executable_at(anchor_slot),
+				Error::<T>::ChallengePeriodNotElapsed
+			);
+			ensure!(
+				Headers::<T>::get(slot) == H256::zero(),
+				Error::<T>::HeaderRootAlreadySet
+			);
+			ensure!(
+				ExecutionStateRoots::<T>::get(slot) == H256::zero(),
+				Error::<T>::StateRootAlreadySet
+			);
+
+			Self::verify_header_field(
+				anchor_slot,
+				ssz::block_root_gindex(slot),
+				proof.header_root,
+				&proof.block_root_branch,
+			)?;
+			ssz::verify_merkle_proof(
+				ssz::uint64_leaf(slot),
+				&proof.slot_branch,
+				ssz::HEADER_SLOT_GINDEX,
+				proof.header_root,
+			)
+			.map_err(|_| Error::<T>::InvalidSszProof)?;
+			ssz::verify_merkle_proof(
+				proof.execution_state_root,
+				&proof.execution_state_root_branch,
+				ssz::EXECUTION_STATE_ROOT_GINDEX,
+				proof.header_root,
+			)
+			.map_err(|_| Error::<T>::InvalidSszProof)?;
+
+			Headers::<T>::insert(slot, proof.header_root);
+			ExecutionStateRoots::<T>::insert(slot, proof.execution_state_root);
+			Timestamps::<T>::insert(slot, T::TimeProvider::now().as_secs());
+
+			Self::deposit_event(Event::HistoricalSlotProven {
+				slot,
+				anchor_slot,
+				header_root: proof.header_root,
+				execution_state_root: proof.execution_state_root,
+			});
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -790,17 +1397,195 @@ pub mod pallet {
 			};
 
 			let message_id = Self::fetch_curr_message_id();
+			let nonce = OutboundNonces::<T>::mutate(T::AvailDomain::get(), domain, |next| {
+				let nonce = *next;
+				*next = next.saturating_add(1);
+				nonce
+			});
 			Self::deposit_event(Event::MessageSubmitted {
 				from: who,
 				to,
 				message_type,
 				destination_domain: domain,
 				message_id,
+				nonce,
 			});
 
 			Ok(().into())
 		}
 
+		/// Stages `update` to take effect after `GovernanceDelay` blocks.
+		fn schedule_update(update: GovernanceUpdate) -> DispatchResult {
+			let id = NextUpdateId::<T>::get();
+			let activates_at =
+				<frame_system::Pallet<T>>::block_number().saturating_add(T::GovernanceDelay::get());
+
+			UpdateAgenda::<T>::try_append(activates_at, id)
+				.map_err(|_| Error::<T>::TooManyPendingUpdates)?;
+			PendingUpdates::<T>::insert(
+				id,
+				PendingUpdate {
+					update: update.clone(),
+					activates_at,
+				},
+			);
+			NextUpdateId::<T>::put(id.wrapping_add(1));
+
+			Self::deposit_event(Event::UpdateScheduled {
+				id,
+				update,
+				activates_at,
+			});
+
+			Ok(())
+		}
+
+		/// Stages the verification key `value` of `function_id`, validated and converted for
+		/// `proof_system`.
+		fn schedule_verification_key(
+			function_id: H256,
+			proof_system: ProofSystem,
+			value: Option<BoundedVec<u8, ConstU32<10_000>>>,
+		) -> DispatchResult {
+			let value = value
+				.map(|vk| Self::prepare_verification_key(proof_system, vk.as_slice()))
+				.transpose()?;
+
+			Self::schedule_update(GovernanceUpdate::VerificationKey {
+				function_id,
+				proof_system,
+				value,
+			})
+		}
+
+		/// Applies the pending governance updates that activate at block `n`.
+		fn enact_updates(n: BlockNumberFor<T>) -> Weight {
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+			for id in UpdateAgenda::<T>::take(n) {
+				weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+				if let Some(pending) = PendingUpdates::<T>::take(id) {
+					weight.saturating_accrue(Self::apply_update(pending.update));
+				}
+			}
+
+			weight
+		}
+
+		fn apply_update(update: GovernanceUpdate) -> Weight {
+			match update {
+				GovernanceUpdate::VerificationKey {
+					function_id,
+					proof_system,
+					value,
+				} => {
+					// The key is always replaced along with its proof system, so that it is never
+					// verified with a proof system it was not prepared for.
+					FunctionProofSystems::<T>::insert(function_id, proof_system);
+					VerificationKeys::<T>::set(function_id, value.clone());
+					Self::deposit_event(Event::VerificationKeyUpdated {
+						function_id,
+						proof_system,
+						value,
+					});
+					T::DbWeight::get().writes(2)
+				},
+				GovernanceUpdate::FunctionIds(value) => {
+					FunctionIds::<T>::put(value);
+					Self::deposit_event(Event::FunctionIdsUpdated { value });
+					T::WeightInfo::set_function_ids()
+				},
+				GovernanceUpdate::Broadcaster {
+					domain,
+					broadcaster,
+				} => {
+					let old = Broadcasters::<T>::get(domain);
+					Broadcasters::<T>::set(domain, broadcaster);
+					Self::deposit_event(Event::BroadcasterUpdated {
+						old,
+						new: broadcaster,
+						domain,
+					});
+					T::WeightInfo::set_broadcaster()
+				},
+				GovernanceUpdate::Configuration(value) => {
+					ConfigurationStorage::<T>::put(value);
+					Self::deposit_event(Event::ConfigurationUpdated {
+						slots_per_period: value.slots_per_period,
+						finality_threshold: value.finality_threshold,
+					});
+					T::WeightInfo::set_configuration()
+				},
+				GovernanceUpdate::ChallengeDelay(value) => {
+					ChallengeDelay::<T>::put(value);
+					Self::deposit_event(Event::ChallengeDelayUpdated { value });
+					T::WeightInfo::set_challenge_delay()
+				},
+				GovernanceUpdate::InboundOrdering { domain, next_nonce } => {
+					InboundNonces::<T>::set(domain, next_nonce);
+					Self::deposit_event(Event::InboundOrderingUpdated { domain, next_nonce });
+					T::WeightInfo::set_inbound_ordering()
+				},
+			}
+		}
+
+		/// Records the current extrinsic as failed, so its message is excluded from the bridge root.
+		fn note_failed_send_message() {
+			let _ = MemoryTemporaryStorage::update::<Vec<Compact<u32>>, _>(
+				FAILED_SEND_MSG_ID.to_vec(),
+				|failed| {
+					let tx_idx = <frame_system::Pallet<T>>::extrinsic_index().unwrap_or_default();
+					failed.push(tx_idx.into());
+					log::trace!(target: LOG_TARGET, "Send Message failed txs: {failed:?}");
+				},
+			);
+		}
+
+		fn escrow_relayer_fee(
+			payer: T::AccountId,
+			message_id: u64,
+			message_root: H256,
+			destination_domain: u32,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(!amount.is_zero(), Error::<T>::InvalidRelayerFee);
+			T::Currency::transfer(
+				&payer,
+				&Self::account_id(),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			let refundable_at = <frame_system::Pallet<T>>::block_number()
+				.saturating_add(T::RelayerFeeTimeout::get());
+			RelayerFees::<T>::insert(
+				message_id,
+				RelayerFee {
+					payer: payer.clone(),
+					amount,
+					message_root,
+					destination_domain,
+					refundable_at,
+				},
+			);
+			Self::deposit_event(Event::RelayerFeeEscrowed {
+				message_id,
+				payer,
+				amount,
+			});
+
+			Ok(())
+		}
+
+		/// Key of the destination bridge storage slot that marks `message_root` as delivered.
+		fn delivery_slot_key(message_root: H256) -> H256 {
+			let message_root = Token::FixedBytes(message_root.as_bytes().to_vec());
+			let dm_idx = Uint(U256::from(T::DeliveryMappingStorageIndex::get()));
+			H256(keccak_256(
+				ethabi::encode(&[message_root, dm_idx]).as_slice(),
+			))
+		}
+
 		fn fetch_curr_message_id() -> u64 {
 			let number = <frame_system::Pallet<T>>::block_number().saturated_into::<u32>();
 			let tx_index = <frame_system::Pallet<T>>::extrinsic_index().unwrap_or_default();
@@ -838,6 +1623,213 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Verifies that `leaf` is at generalized index `gindex` under the stored header of `slot`.
+		pub fn verify_header_field(
+			slot: u64,
+			gindex: u64,
+			leaf: H256,
+			branch: &[H256],
+		) -> DispatchResult {
+			let header = Headers::<T>::get(slot);
+			ensure!(header != H256::zero(), Error::<T>::HeaderRootNotSet);
+
+			ssz::verify_merkle_proof(leaf, branch, gindex, header)
+				.map_err(|_| Error::<T>::InvalidSszProof)?;
+
+			Ok(())
+		}
+
+		/// Returns the unix time in seconds from which messages can be proven against `slot`.
+		pub fn executable_at(slot: u64) -> u64 {
+			Timestamps::<T>::get(slot).saturating_add(ChallengeDelay::<T>::get())
+		}
+
+		/// Verifies the account proof of the `domain` broadcaster against the execution state root
+		/// of `slot` and returns the storage root of the broadcaster contract.
+		fn broadcaster_storage_root(
+			slot: u64,
+			domain: u32,
+			account_proof: &ValidProof,
+		) -> Result<H256, DispatchError> {
+			ensure!(
+				T::TimeProvider::now().as_secs() >= Self::executable_at(slot),
+				Error::<T>::ChallengePeriodNotElapsed
+			);
+
+			let root = ExecutionStateRoots::<T>::get(slot);
+			let broadcaster = Broadcasters::<T>::get(domain);
+
+			// extract contract address
+			let contract_broadcaster_address = H160::from_slice(broadcaster[..20].as_ref());
+
+			let storage_root = get_storage_root(
+				Self::proof_nodes(account_proof),
+				contract_broadcaster_address,
+				root,
+			)
+			.map_err(|_| Error::<T>::CannotGetStorageRoot)?;
+
+			Ok(storage_root)
+		}
+
+		/// Checks that `message_root` is stored for the message in the broadcaster storage and
+		/// executes the message.
+		fn verify_and_execute_message(
+			storage_root: H256,
+			addr_message: AddressedMessage,
+			message_root: H256,
+			storage_proof: &ValidProof,
+		) -> DispatchResult {
+			let slot_key = Self::message_slot_key(addr_message.id);
+
+			let slot_value =
+				get_storage_value(slot_key, storage_root, Self::proof_nodes(storage_proof))
+					.map_err(|_| Error::<T>::CannotGetStorageValue)?;
+
+			ensure!(slot_value == message_root, Error::<T>::InvalidMessageHash);
+
+			Self::execute_message(addr_message, message_root)
+		}
+
+		/// Verifies that the broadcaster of `addr_message` emitted it in the log `log_index` of a
+		/// successful transaction receipt and executes the message.
+		fn verify_receipt_and_execute_message(
+			slot: u64,
+			addr_message: AddressedMessage,
+			message_root: H256,
+			proof: &ReceiptProof,
+		) -> DispatchResult {
+			ensure!(
+				T::TimeProvider::now().as_secs() >= Self::executable_at(slot),
+				Error::<T>::ChallengePeriodNotElapsed
+			);
+			Self::verify_header_field(
+				slot,
+				ssz::RECEIPTS_ROOT_GINDEX,
+				proof.receipts_root,
+				&proof.receipts_root_branch,
+			)?;
+
+			let receipt = get_receipt(
+				proof.receipts_root,
+				proof.tx_index,
+				proof
+					.receipt_proof
+					.iter()
+					.map(|node| node.to_vec())
+					.collect(),
+			)
+			.map_err(|_| Error::<T>::CannotGetReceipt)?;
+			ensure!(receipt.success, Error::<T>::ReceiptNotSuccessful);
+
+			let broadcaster = Broadcasters::<T>::get(addr_message.origin_domain);
+			let log = receipt
+				.logs
+				.get(proof.log_index as usize)
+				.ok_or(Error::<T>::InvalidMessageLog)?;
+			ensure!(
+				log.address == H160::from_slice(&broadcaster[..20])
+					&& log.topics.first() == Some(&MESSAGE_SENT_TOPIC),
+				Error::<T>::InvalidMessageLog
+			);
+
+			let message = match ethabi::decode(&[ethabi::ParamType::Bytes], &log.data)
+				.map_err(|_| Error::<T>::InvalidMessageLog)?
+				.pop()
+			{
+				Some(Token::Bytes(message)) => message,
+				_ => return Err(Error::<T>::InvalidMessageLog.into()),
+			};
+			ensure!(
+				H256(keccak_256(&message)) == message_root,
+				Error::<T>::InvalidMessageHash
+			);
+
+			Self::execute_message(addr_message, message_root)
+		}
+
+		/// Executes a message whose inclusion on the origin chain is already verified.
+		fn execute_message(addr_message: AddressedMessage, message_root: H256) -> DispatchResult {
+			if let Some(expected) = InboundNonces::<T>::get(addr_message.origin_domain) {
+				ensure!(addr_message.id == expected, Error::<T>::MessageOutOfOrder);
+				InboundNonces::<T>::insert(addr_message.origin_domain, expected.saturating_add(1));
+			}
+
+			if let Message::FungibleToken { asset_id, amount } = &addr_message.message {
+				ensure!(
+					SUPPORTED_ASSET_ID == *asset_id,
+					Error::<T>::AssetNotSupported
+				);
+
+				let destination_account_id =
+					T::AccountId::decode(&mut &addr_message.to.encode()[..])
+						.map_err(|_| Error::<T>::CannotDecodeDestinationAccountId)?;
+
+				T::Currency::transfer(
+					&Self::account_id(),
+					&destination_account_id,
+					(*amount).saturated_into(),
+					ExistenceRequirement::AllowDeath,
+				)?;
+			}
+
+			MessageStatus::<T>::set(message_root, MessageStatusEnum::ExecutionSucceeded);
+			ExecutedMessageRoots::<T>::insert(
+				addr_message.origin_domain,
+				addr_message.id,
+				message_root,
+			);
+			Self::deposit_event(Event::<T>::MessageExecuted {
+				from: addr_message.from,
+				to: addr_message.to,
+				message_id: addr_message.id,
+				message_root,
+			});
+
+			Ok(())
+		}
+
+		fn proof_nodes(proof: &ValidProof) -> Vec<Vec<u8>> {
+			proof.iter().map(|node| node.to_vec()).collect()
+		}
+
+		/// Key of the broadcaster storage slot under which the root of message `message_id` is kept.
+		pub fn message_slot_key(message_id: u64) -> H256 {
+			let message_id = Uint(U256::from(message_id));
+			let mm_idx = Uint(U256::from(T::MessageMappingStorageIndex::get()));
+			H256(keccak_256(ethabi::encode(&[message_id, mm_idx]).as_slice()))
+		}
+
+		/// Execution status of the message `id` sent from `origin_domain`.
+		pub fn message_status_by_id(origin_domain: u32, id: u64) -> MessageStatusEnum {
+			ExecutedMessageRoots::<T>::get(origin_domain, id)
+				.map(MessageStatus::<T>::get)
+				.unwrap_or_default()
+		}
+
+		/// Nonce of the next message sent from `origin_domain` to `destination_domain`.
+		pub fn next_outbound_nonce(origin_domain: u32, destination_domain: u32) -> u64 {
+			OutboundNonces::<T>::get(origin_domain, destination_domain)
+		}
+
+		/// Id of the next message to execute from `origin_domain`, if its messages are executed
+		/// in order.
+		pub fn next_inbound_nonce(origin_domain: u32) -> Option<u64> {
+			InboundNonces::<T>::get(origin_domain)
+		}
+
+		/// Domains whose messages cannot be executed at the moment.
+		pub fn frozen_domains() -> Vec<u32> {
+			SourceChainFrozen::<T>::iter()
+				.filter_map(|(domain, frozen)| frozen.then_some(domain))
+				.collect()
+		}
+
+		/// Broadcaster addresses of all known domains.
+		pub fn broadcasters() -> Vec<(u32, H256)> {
+			Broadcasters::<T>::iter().collect()
+		}
+
 		/// # TODO
 		/// - Remove `dead_code` here.
 		#[allow(dead_code)]
@@ -969,39 +1961,17 @@ pub mod pallet {
 			Ok(())
 		}
 
-		/// get_verifier returns verifier based on the provided function id.
-		fn get_verifier(
-			function_id: H256,
-			step_function_id: H256,
-			rotate_function_id: H256,
-		) -> Result<Verifier, Error<T>> {
-			if function_id == step_function_id {
-				Self::get_step_verifier()
-			} else if function_id == rotate_function_id {
-				Self::get_rotate_verifier()
-			} else {
-				Err(Error::<T>::FunctionIdNotKnown)
-			}
-		}
-
-		fn get_step_verifier() -> Result<Verifier, Error<T>> {
-			if let Some(vk) = StepVerificationKey::<T>::get() {
-				let deserialized_vk = Verifier::from_json_u8_slice(vk.as_slice())
-					.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-				Ok(deserialized_vk)
-			} else {
-				Err(Error::<T>::VerificationKeyIsNotSet)
-			}
-		}
-
-		fn get_rotate_verifier() -> Result<Verifier, Error<T>> {
-			if let Some(vk) = RotateVerificationKey::<T>::get() {
-				let deserialized_vk = Verifier::from_json_u8_slice(vk.as_slice())
-					.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-				Ok(deserialized_vk)
-			} else {
-				Err(Error::<T>::VerificationKeyIsNotSet)
-			}
+		/// Validates a verification key and converts it into the encoding of the given proof
+		/// system, which is the form kept in storage.
+		pub(crate) fn prepare_verification_key(
+			proof_system: ProofSystem,
+			vk: &[u8],
+		) -> Result<BoundedVec<u8, ConstU32<10_000>>, Error<T>> {
+			let prepared_vk = proof_system
+				.prepare_verification_key(vk)
+				.map_err(|_| Error::<T>::MalformedVerificationKey)?;
+
+			BoundedVec::try_from(prepared_vk).map_err(|_| Error::<T>::MalformedVerificationKey)
 		}
 
 		fn verified_step_call(
@@ -1085,13 +2055,49 @@ where
 pub mod weight_helper {
 	use super::*;
 
-	/// Weight for `dataAvailability::submit_data`.
+	/// Weight for `vector::fulfill_call`, the weight of the proof system registered for the
+	/// function. Unknown functions are rejected before any verification.
 	pub fn fulfill_call<T: Config>(function_id: H256) -> (Weight, DispatchClass) {
-		if let Some((step_function_id, _)) = FunctionIds::<T>::get() {
-			if step_function_id == function_id {
-				return (T::WeightInfo::fulfill_call_step(), DispatchClass::Normal);
-			}
-		}
-		(T::WeightInfo::fulfill_call_rotate(), DispatchClass::Normal)
+		let weight = FunctionProofSystems::<T>::get(function_id)
+			.unwrap_or_default()
+			.fulfill_call_weight::<T::WeightInfo>();
+
+		(weight, DispatchClass::Normal)
+	}
+
+	/// Weight for `vector::execute_via_receipt`, including the receipts trie proof by its number of
+	/// nodes and encoded length.
+	pub fn execute_via_receipt<T: Config>(
+		addr_message: &AddressedMessage,
+		proof: &ReceiptProof,
+	) -> Weight {
+		let execute = match addr_message.message {
+			Message::ArbitraryMessage(ref data) => {
+				T::WeightInfo::execute_via_receipt_arbitrary_message(data.len() as u32)
+			},
+			Message::FungibleToken { .. } => T::WeightInfo::execute_via_receipt_fungible_token(),
+		};
+		let nodes = proof.receipt_proof.len() as u32;
+		let length = proof
+			.receipt_proof
+			.iter()
+			.fold(0u32, |total, node| total.saturating_add(node.len() as u32));
+
+		execute.saturating_add(T::WeightInfo::verify_receipt_proof(nodes, length))
+	}
+
+	/// Weight for `vector::execute_batch`, the sum of the benchmarked `execute` weights of its
+	/// messages. This is an upper bound: each `execute` weight includes the account proof
+	/// verification, which the batch only does once.
+	pub fn execute_batch<T: Config>(messages: &MessageBatch) -> Weight {
+		messages.iter().fold(Weight::zero(), |total, addr_message| {
+			let weight = match addr_message.message {
+				Message::ArbitraryMessage(ref data) => {
+					T::WeightInfo::execute_arbitrary_message(data.len() as u32)
+				},
+				Message::FungibleToken { .. } => T::WeightInfo::execute_fungible_token(),
+			};
+			total.saturating_add(weight)
+		})
 	}
 }
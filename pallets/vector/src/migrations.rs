@@ -0,0 +1,245 @@
+use crate::{
+	verifier::ProofSystem, Config, FunctionIds, FunctionProofSystems, Pallet, VerificationKeys,
+	WeightInfo, LOG_TARGET,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+#[cfg(feature = "try-runtime")]
+use sp_core::H256;
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+pub mod v1 {
+	use super::*;
+
+	/// Step verification key, kept in its own storage value up to storage version 1.
+	#[frame_support::storage_alias]
+	pub type StepVerificationKey<T: Config> =
+		StorageValue<Pallet<T>, Option<BoundedVec<u8, ConstU32<10_000>>>, ValueQuery>;
+
+	/// Rotate verification key, kept in its own storage value up to storage version 1.
+	#[frame_support::storage_alias]
+	pub type RotateVerificationKey<T: Config> =
+		StorageValue<Pallet<T>, Option<BoundedVec<u8, ConstU32<10_000>>>, ValueQuery>;
+
+	/// Converts the json verification keys that are kept in `StepVerificationKey` and
+	/// `RotateVerificationKey` into the compact binary encoding.
+	///
+	/// A key that cannot be converted is removed and an error is logged, as it could not be used
+	/// to verify a proof anyway.
+	pub struct MigrateToCompactVerificationKeys<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToCompactVerificationKeys<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 0 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping verification key migration, on-chain storage version is {on_chain_version:?}"
+				);
+				return T::DbWeight::get().reads(1);
+			}
+
+			StepVerificationKey::<T>::mutate(|vk| migrate_key::<T>(vk, "step"));
+			RotateVerificationKey::<T>::mutate(|vk| migrate_key::<T>(vk, "rotate"));
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			log::info!(target: LOG_TARGET, "Migrated verification keys to the compact encoding");
+
+			// Conversion cost is the same as setting both keys via their calls.
+			T::WeightInfo::set_step_verification_key()
+				.saturating_add(T::WeightInfo::set_rotate_verification_key())
+				.saturating_add(T::DbWeight::get().reads_writes(3, 1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			let is_set = (
+				StepVerificationKey::<T>::get().is_some(),
+				RotateVerificationKey::<T>::get().is_some(),
+			);
+			Ok(is_set.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			use crate::verifier::PreparedVerifier;
+
+			let (step_was_set, rotate_was_set) = <(bool, bool)>::decode(&mut state.as_slice())
+				.map_err(|_| "Cannot decode pre upgrade state")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"Storage version should be 1 after the migration"
+			);
+
+			for (was_set, vk) in [
+				(step_was_set, StepVerificationKey::<T>::get()),
+				(rotate_was_set, RotateVerificationKey::<T>::get()),
+			] {
+				if let Some(vk) = vk {
+					PreparedVerifier::from_compact_bytes(vk.as_slice())
+						.map_err(|_| "Migrated verification key cannot be decoded")?;
+				} else {
+					ensure!(!was_set, "Verification key got removed by the migration");
+				}
+			}
+
+			Ok(())
+		}
+	}
+
+	fn migrate_key<T: Config>(maybe_vk: &mut Option<BoundedVec<u8, ConstU32<10_000>>>, kind: &str) {
+		*maybe_vk = maybe_vk.take().and_then(|vk| {
+			Pallet::<T>::prepare_verification_key(ProofSystem::Groth16Bn254, vk.as_slice())
+				.map_err(
+					|_| log::error!(target: LOG_TARGET, "Cannot migrate {kind} verification key"),
+				)
+				.ok()
+		});
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Moves the step and rotate verification keys into `VerificationKeys`, under the function
+	/// ids they are used for.
+	///
+	/// Keys are dropped and an error is logged if the function ids are not set, as they could not
+	/// be used to verify a proof anyway.
+	pub struct MigrateToVerificationKeysByFunctionId<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToVerificationKeysByFunctionId<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping verification key migration, on-chain storage version is {on_chain_version:?}"
+				);
+				return T::DbWeight::get().reads(1);
+			}
+
+			let step_vk = v1::StepVerificationKey::<T>::take();
+			let rotate_vk = v1::RotateVerificationKey::<T>::take();
+			match FunctionIds::<T>::get() {
+				Some((step_function_id, rotate_function_id)) => {
+					VerificationKeys::<T>::set(step_function_id, step_vk);
+					VerificationKeys::<T>::set(rotate_function_id, rotate_vk);
+				},
+				None if step_vk.is_some() || rotate_vk.is_some() => {
+					log::error!(
+						target: LOG_TARGET,
+						"Cannot migrate verification keys, function ids are not set"
+					);
+				},
+				None => (),
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			log::info!(target: LOG_TARGET, "Migrated verification keys to function id keys");
+
+			T::DbWeight::get().reads_writes(4, 5)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			let keys = (
+				FunctionIds::<T>::get(),
+				v1::StepVerificationKey::<T>::get(),
+				v1::RotateVerificationKey::<T>::get(),
+			);
+			Ok(keys.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			type Key = Option<BoundedVec<u8, ConstU32<10_000>>>;
+			let (function_ids, step_vk, rotate_vk) =
+				<(Option<(H256, H256)>, Key, Key)>::decode(&mut state.as_slice())
+					.map_err(|_| "Cannot decode pre upgrade state")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"Storage version should be 2 after the migration"
+			);
+			ensure!(
+				v1::StepVerificationKey::<T>::get().is_none()
+					&& v1::RotateVerificationKey::<T>::get().is_none(),
+				"Old verification keys should be removed"
+			);
+			if let Some((step_function_id, rotate_function_id)) = function_ids {
+				ensure!(
+					VerificationKeys::<T>::get(step_function_id) == step_vk
+						&& VerificationKeys::<T>::get(rotate_function_id) == rotate_vk,
+					"Verification keys should be kept under their function ids"
+				);
+			}
+
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// Registers the step and rotate function ids with the Groth16 proof system, which verified
+	/// them before function ids had to be registered.
+	pub struct RegisterFunctionProofSystems<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for RegisterFunctionProofSystems<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 2 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping function registration migration, on-chain storage version is {on_chain_version:?}"
+				);
+				return T::DbWeight::get().reads(1);
+			}
+
+			if let Some((step_function_id, rotate_function_id)) = FunctionIds::<T>::get() {
+				for function_id in [step_function_id, rotate_function_id] {
+					if !FunctionProofSystems::<T>::contains_key(function_id) {
+						FunctionProofSystems::<T>::insert(function_id, ProofSystem::Groth16Bn254);
+					}
+				}
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			log::info!(target: LOG_TARGET, "Registered the proof systems of the function ids");
+
+			T::DbWeight::get().reads_writes(4, 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+			Ok(FunctionIds::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let function_ids = <Option<(H256, H256)>>::decode(&mut state.as_slice())
+				.map_err(|_| "Cannot decode pre upgrade state")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"Storage version should be 3 after the migration"
+			);
+			if let Some((step_function_id, rotate_function_id)) = function_ids {
+				ensure!(
+					FunctionProofSystems::<T>::contains_key(step_function_id)
+						&& FunctionProofSystems::<T>::contains_key(rotate_function_id),
+					"Step and rotate function ids should be registered"
+				);
+			}
+
+			Ok(())
+		}
+	}
+}
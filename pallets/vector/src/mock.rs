@@ -1,5 +1,9 @@
-use frame_support::{derive_impl, parameter_types, traits::ConstU64, PalletId};
-use frame_system::{header_builder::da, test_utils::TestRandomness};
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+	PalletId,
+};
+use frame_system::{header_builder::da, test_utils::TestRandomness, EnsureRoot};
 use hex_literal::hex;
 use primitive_types::H256;
 use sp_runtime::{
@@ -210,12 +214,18 @@ impl pallet_timestamp::Config for Test {
 
 parameter_types! {
 	pub const BridgePalletId: PalletId = PalletId(*b"avl/brdg");
+	pub const GovernanceDelay: u32 = 10;
 }
 
 #[derive_impl(crate::config_preludes::TestDefaultConfig as crate::DefaultConfig)]
 impl vector_bridge::Config for Test {
 	type TimeProvider = Timestamp;
 	type Currency = Balances;
+	type RelayerFeeTimeout = ConstU32<100>;
+	type GovernanceDelay = GovernanceDelay;
+	type CancelOrigin = EnsureRoot<AccountId32>;
+	// Shares the message mapping slot, so the proofs of sent messages also prove their delivery.
+	type DeliveryMappingStorageIndex = ConstU64<1>;
 }
 
 /// Create new externalities for `Vector` module tests.
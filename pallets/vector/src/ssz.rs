@@ -0,0 +1,121 @@
+use sp_core::H256;
+use sp_io::hashing::sha2_256;
+
+/// Generalized index of `slot` in a `BeaconBlockHeader`.
+pub const HEADER_SLOT_GINDEX: u64 = 8;
+/// Generalized index of `state_root` in a `BeaconBlockHeader`.
+pub const HEADER_STATE_ROOT_GINDEX: u64 = 11;
+/// Generalized index of `body_root` in a `BeaconBlockHeader`.
+pub const HEADER_BODY_ROOT_GINDEX: u64 = 12;
+/// Generalized index of `block_roots` in a Deneb `BeaconState`.
+pub const STATE_BLOCK_ROOTS_GINDEX: u64 = 37;
+/// Generalized index of `execution_payload` in a Deneb `BeaconBlockBody`.
+pub const BODY_EXECUTION_PAYLOAD_GINDEX: u64 = 25;
+/// Generalized index of `state_root` in a Deneb `ExecutionPayload`.
+pub const PAYLOAD_STATE_ROOT_GINDEX: u64 = 34;
+/// Generalized index of `receipts_root` in a Deneb `ExecutionPayload`.
+pub const PAYLOAD_RECEIPTS_ROOT_GINDEX: u64 = 35;
+/// Generalized index of `block_number` in a Deneb `ExecutionPayload`.
+pub const PAYLOAD_BLOCK_NUMBER_GINDEX: u64 = 38;
+/// Generalized index of `block_hash` in a Deneb `ExecutionPayload`.
+pub const PAYLOAD_BLOCK_HASH_GINDEX: u64 = 44;
+/// Generalized index of `withdrawals` in a Deneb `ExecutionPayload`.
+pub const PAYLOAD_WITHDRAWALS_GINDEX: u64 = 46;
+/// Length of the `block_roots` vector of the `BeaconState`.
+pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+
+/// Generalized index of the execution state root in a `BeaconBlockHeader`.
+pub const EXECUTION_STATE_ROOT_GINDEX: u64 = concat_generalized_indices(&[
+	HEADER_BODY_ROOT_GINDEX,
+	BODY_EXECUTION_PAYLOAD_GINDEX,
+	PAYLOAD_STATE_ROOT_GINDEX,
+]);
+
+/// Generalized index of the execution receipts root in a `BeaconBlockHeader`.
+pub const RECEIPTS_ROOT_GINDEX: u64 = concat_generalized_indices(&[
+	HEADER_BODY_ROOT_GINDEX,
+	BODY_EXECUTION_PAYLOAD_GINDEX,
+	PAYLOAD_RECEIPTS_ROOT_GINDEX,
+]);
+
+/// Maximum depth of a branch accepted by the pallet.
+pub const MAX_BRANCH_DEPTH: u32 = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SszError {
+	InvalidGeneralizedIndex,
+	BranchLengthMismatch,
+	RootMismatch,
+}
+
+/// Combines the generalized indices of nested fields, outermost first, into the generalized
+/// index of the innermost field relative to the outermost root.
+pub const fn concat_generalized_indices(indices: &[u64]) -> u64 {
+	let mut result = 1u64;
+	let mut i = 0;
+	while i < indices.len() {
+		let depth = indices[i].ilog2();
+		result = (result << depth) | (indices[i] ^ (1 << depth));
+		i += 1;
+	}
+	result
+}
+
+/// Generalized index of the block root of `slot` in a `BeaconBlockHeader`, through the
+/// `block_roots` vector of its state.
+pub fn block_root_gindex(slot: u64) -> u64 {
+	concat_generalized_indices(&[
+		HEADER_STATE_ROOT_GINDEX,
+		STATE_BLOCK_ROOTS_GINDEX,
+		SLOTS_PER_HISTORICAL_ROOT + slot % SLOTS_PER_HISTORICAL_ROOT,
+	])
+}
+
+/// SSZ leaf of a `uint64` value.
+pub fn uint64_leaf(value: u64) -> H256 {
+	let mut leaf = H256::zero();
+	leaf[..8].copy_from_slice(&value.to_le_bytes());
+	leaf
+}
+
+/// Computes the root of the tree containing `leaf` at `gindex`, `branch` being ordered from the
+/// leaf sibling up to the child of the root.
+pub fn compute_root(leaf: H256, branch: &[H256], gindex: u64) -> Result<H256, SszError> {
+	if gindex == 0 {
+		return Err(SszError::InvalidGeneralizedIndex);
+	}
+	if branch.len() != gindex.ilog2() as usize {
+		return Err(SszError::BranchLengthMismatch);
+	}
+
+	let mut node = leaf;
+	let mut index = gindex;
+	let mut buf = [0u8; 64];
+	for sibling in branch {
+		let (left, right) = if index % 2 == 0 {
+			(&node, sibling)
+		} else {
+			(sibling, &node)
+		};
+		buf[..32].copy_from_slice(left.as_bytes());
+		buf[32..].copy_from_slice(right.as_bytes());
+		node = H256(sha2_256(&buf));
+		index /= 2;
+	}
+
+	Ok(node)
+}
+
+/// Verifies that `leaf` is at `gindex` in the tree with the given `root`.
+pub fn verify_merkle_proof(
+	leaf: H256,
+	branch: &[H256],
+	gindex: u64,
+	root: H256,
+) -> Result<(), SszError> {
+	if compute_root(leaf, branch, gindex)? != root {
+		return Err(SszError::RootMismatch);
+	}
+
+	Ok(())
+}
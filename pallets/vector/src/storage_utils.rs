@@ -1,5 +1,6 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use patricia_merkle_trie::{keccak256, EIP1186Layout, StorageProof};
+use frame_support::{Deserialize, Serialize};
+use patricia_merkle_trie::{keccak256, receipt_key, EIP1186Layout, Receipt, StorageProof};
 use primitive_types::{H160, H256};
 use rlp::Rlp;
 use scale_info::TypeInfo;
@@ -8,6 +9,7 @@ use sp_std::vec::Vec;
 use trie_db::{Trie, TrieDBBuilder};
 
 #[derive(Clone, Copy, Default, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum MessageStatusEnum {
 	#[default]
 	NotExecuted,
@@ -20,6 +22,7 @@ pub enum StorageError {
 	StorageValueError,
 	AccountNotFound,
 	CannotDecodeItems,
+	ReceiptNotFound,
 }
 
 /// get_storage_value returns a storage value based on the proof that is provided.
@@ -40,11 +43,15 @@ pub fn get_storage_value(
 		let r = Rlp::new(result.as_slice())
 			.data()
 			.map_err(|_| StorageError::CannotDecodeItems)?;
-		if r.is_empty() {
+		if r.is_empty() || r.len() > 32 {
 			return Err(StorageError::CannotDecodeItems);
 		}
 
-		Ok(H256::from_slice(r))
+		// Storage values are RLP encoded without leading zeros.
+		let mut value = H256::zero();
+		value.0[32 - r.len()..].copy_from_slice(r);
+
+		Ok(value)
 	} else {
 		Err(StorageError::StorageValueError)
 	}
@@ -196,3 +203,22 @@ mod test {
 		assert_eq!(expected_encoded_message, encoded);
 	}
 }
+
+/// get_receipt returns the receipt of the transaction at `tx_index` based on the receipts trie proof
+/// that is provided.
+pub fn get_receipt(
+	receipts_root: H256,
+	tx_index: u64,
+	proof: Vec<Vec<u8>>,
+) -> Result<Receipt, StorageError> {
+	let db = StorageProof::new(proof).into_memory_db::<keccak256::KeccakHasher>();
+	let trie =
+		TrieDBBuilder::<EIP1186Layout<keccak256::KeccakHasher>>::new(&db, &receipts_root).build();
+
+	let receipt = trie
+		.get(&receipt_key(tx_index))
+		.map_err(|_| StorageError::StorageValueError)?
+		.ok_or(StorageError::ReceiptNotFound)?;
+
+	Receipt::decode(&receipt).map_err(|_| StorageError::CannotDecodeItems)
+}
@@ -1,27 +1,41 @@
 use crate::{
+	migrations::{
+		v1::{MigrateToCompactVerificationKeys, RotateVerificationKey, StepVerificationKey},
+		v2::MigrateToVerificationKeysByFunctionId,
+		v3::RegisterFunctionProofSystems,
+	},
 	mock::{
-		new_test_ext, Balances, Bridge, RuntimeEvent, RuntimeOrigin, System, Test,
-		ROTATE_FUNCTION_ID, ROTATE_VK, STEP_FUNCTION_ID, STEP_VK,
+		new_test_ext, Balances, Bridge, GovernanceDelay, RuntimeEvent, RuntimeOrigin, System, Test,
+		Timestamp, ROTATE_FUNCTION_ID, ROTATE_VK, STEP_FUNCTION_ID, STEP_VK,
 	},
+	ssz,
 	state::Configuration,
 	storage_utils::MessageStatusEnum,
-	Broadcasters, ConfigurationStorage, Error, Event, ExecutionStateRoots, FunctionIds,
-	FunctionInput, FunctionOutput, FunctionProof, Head, Headers, MessageStatus,
-	RotateVerificationKey, SourceChainFrozen, StepVerificationKey, SyncCommitteePoseidons,
-	ValidProof, WhitelistedDomains,
+	verifier::{ProofSystem, Verifier},
+	Broadcasters, ChallengeDelay, ConfigurationStorage, Error, Event, ExecutedMessageRoots,
+	ExecutionStateRoots, FunctionIds, FunctionInput, FunctionOutput, FunctionProof,
+	FunctionProofSystems, GovernanceUpdate, Head, Headers, HistoricalSlotProof, InboundNonces,
+	MessageStatus, OutboundNonces, PendingUpdate, PendingUpdates, ReceiptProof, RelayerFee,
+	RelayerFees, SourceChainFrozen, SyncCommitteePoseidons, Timestamps, UpdateAgenda, ValidProof,
+	VerificationKeys, WhitelistedDomains,
 };
 use avail_core::data_proof::{tx_uid, AddressedMessage, Message};
 
+use ark_serialize::CanonicalSerialize;
 use frame_support::{
 	assert_err, assert_ok,
-	traits::{fungible::Inspect, DefensiveTruncateFrom},
+	traits::{
+		fungible::Inspect, DefensiveTruncateFrom, GetStorageVersion, Hooks, OnRuntimeUpgrade,
+		StorageVersion,
+	},
 	BoundedVec,
 };
 use frame_system::RawOrigin;
 use hex_literal::hex;
 use primitive_types::U256;
-use sp_core::{crypto::AccountId32, keccak_256, ByteArray};
-use sp_runtime::{testing::H256, traits::BadOrigin};
+use rlp::RlpStream;
+use sp_core::{crypto::AccountId32, keccak_256, ByteArray, ConstU32};
+use sp_runtime::{testing::H256, traits::BadOrigin, BuildStorage};
 
 const TEST_SENDER_VEC: [u8; 32] = [2u8; 32];
 const TEST_SENDER_ACCOUNT: AccountId32 = AccountId32::new(TEST_SENDER_VEC);
@@ -154,6 +168,13 @@ fn get_valid_amb_message() -> AddressedMessage {
 	}
 }
 
+/// Advances past the governance delay so that staged updates take effect.
+fn enact_pending_updates() {
+	let now = System::block_number() + GovernanceDelay::get();
+	System::set_block_number(now);
+	Bridge::on_initialize(now);
+}
+
 #[test]
 fn test_fulfill_step_call_proof_not_valid() {
 	new_test_ext().execute_with(|| {
@@ -684,6 +705,7 @@ fn test_fulfill_rotate_call_wrong_header() {
 fn test_fulfill_call_function_ids_not_set() {
 	new_test_ext().execute_with(|| {
 		Bridge::set_function_ids(RawOrigin::Root.into(), None).unwrap();
+		enact_pending_updates();
 		let slot = 7634942;
 		let err = Bridge::fulfill_call(
 			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
@@ -701,6 +723,7 @@ fn test_fulfill_call_function_ids_not_set() {
 fn test_fulfill_step_call_verification_key_is_not_set() {
 	new_test_ext().execute_with(|| {
 		Bridge::set_step_verification_key(RawOrigin::Root.into(), None).unwrap();
+		enact_pending_updates();
 
 		let slot = 7634942;
 
@@ -732,6 +755,7 @@ fn test_fulfill_step_call_verification_key_is_not_set() {
 fn test_fulfill_rotate_call_verification_key_is_not_set() {
 	new_test_ext().execute_with(|| {
 		Bridge::set_rotate_verification_key(RawOrigin::Root.into(), None).unwrap();
+		enact_pending_updates();
 		let slot = 7634942;
 
 		ConfigurationStorage::<Test>::set(Configuration {
@@ -793,6 +817,9 @@ fn set_configuration_works_with_root() {
 
 		let ok = Bridge::set_configuration(RawOrigin::Root.into(), conf);
 		assert_ok!(ok);
+		assert_ne!(ConfigurationStorage::<Test>::get(), conf);
+
+		enact_pending_updates();
 		assert_eq!(ConfigurationStorage::<Test>::get(), conf);
 
 		let expected_event = RuntimeEvent::Bridge(Event::ConfigurationUpdated {
@@ -826,6 +853,9 @@ fn set_broadcaster_works_with_root() {
 
 		let ok = Bridge::set_broadcaster(RawOrigin::Root.into(), domain, STEP_FUNCTION_ID);
 		assert_ok!(ok);
+		assert_eq!(Broadcasters::<Test>::get(domain), old);
+
+		enact_pending_updates();
 		assert_eq!(Broadcasters::<Test>::get(domain), STEP_FUNCTION_ID);
 
 		let expected_event = RuntimeEvent::Bridge(Event::BroadcasterUpdated {
@@ -846,6 +876,81 @@ fn set_broadcaster_does_not_work_with_non_root() {
 	});
 }
 
+#[test]
+fn pending_update_does_not_apply_before_delay() {
+	new_test_ext().execute_with(|| {
+		let domain = 2;
+		let old = Broadcasters::<Test>::get(domain);
+		let activates_at = System::block_number() + GovernanceDelay::get();
+
+		let ok = Bridge::set_broadcaster(RawOrigin::Root.into(), domain, STEP_FUNCTION_ID);
+		assert_ok!(ok);
+
+		let update = GovernanceUpdate::Broadcaster {
+			domain,
+			broadcaster: STEP_FUNCTION_ID,
+		};
+		let expected_event = RuntimeEvent::Bridge(Event::UpdateScheduled {
+			id: 0,
+			update: update.clone(),
+			activates_at,
+		});
+		System::assert_last_event(expected_event);
+		assert_eq!(
+			PendingUpdates::<Test>::get(0),
+			Some(PendingUpdate {
+				update,
+				activates_at
+			})
+		);
+
+		System::set_block_number(activates_at - 1);
+		Bridge::on_initialize(activates_at - 1);
+		assert_eq!(Broadcasters::<Test>::get(domain), old);
+
+		System::set_block_number(activates_at);
+		Bridge::on_initialize(activates_at);
+		assert_eq!(Broadcasters::<Test>::get(domain), STEP_FUNCTION_ID);
+		assert!(PendingUpdates::<Test>::get(0).is_none());
+		assert!(UpdateAgenda::<Test>::get(activates_at).is_empty());
+	});
+}
+
+#[test]
+fn cancel_update_works() {
+	new_test_ext().execute_with(|| {
+		let domain = 2;
+		let old = Broadcasters::<Test>::get(domain);
+		let ok = Bridge::set_broadcaster(RawOrigin::Root.into(), domain, STEP_FUNCTION_ID);
+		assert_ok!(ok);
+
+		let ok = Bridge::cancel_update(RawOrigin::Root.into(), 0);
+		assert_ok!(ok);
+		assert!(PendingUpdates::<Test>::get(0).is_none());
+
+		let expected_event = RuntimeEvent::Bridge(Event::UpdateCancelled { id: 0 });
+		System::assert_last_event(expected_event);
+
+		enact_pending_updates();
+		assert_eq!(Broadcasters::<Test>::get(domain), old);
+
+		let err = Bridge::cancel_update(RawOrigin::Root.into(), 0);
+		assert_err!(err, Error::<Test>::UpdateNotFound);
+	});
+}
+
+#[test]
+fn cancel_update_does_not_work_with_wrong_origin() {
+	new_test_ext().execute_with(|| {
+		let ok = Bridge::set_broadcaster(RawOrigin::Root.into(), 2, STEP_FUNCTION_ID);
+		assert_ok!(ok);
+
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let err = Bridge::cancel_update(origin, 0);
+		assert_err!(err, BadOrigin);
+	});
+}
+
 #[test]
 fn set_poseidon_hash_works_with_root() {
 	new_test_ext().execute_with(|| {
@@ -917,6 +1022,7 @@ fn send_message_arbitrary_message_works() {
 			message_type: message.r#type(),
 			destination_domain: domain,
 			message_id: tx_uid(1, 0),
+			nonce: 0,
 		};
 		let ok = Bridge::send_message(origin, message, to, domain);
 		assert_ok!(ok);
@@ -960,6 +1066,7 @@ fn send_message_fungible_token_works() {
 			message_type: message.r#type(),
 			destination_domain: domain,
 			message_id: tx_uid(1, 0),
+			nonce: 0,
 		};
 		let ok = Bridge::send_message(origin, message, to, domain);
 		assert_ok!(ok);
@@ -1108,11 +1215,13 @@ fn set_function_ids_works_with_root() {
 	new_test_ext().execute_with(|| {
 		let ok = Bridge::set_function_ids(RawOrigin::Root.into(), None);
 		assert_ok!(ok);
+		enact_pending_updates();
 		assert_eq!(FunctionIds::<Test>::get(), None);
 
 		let value = Some((STEP_FUNCTION_ID, ROTATE_FUNCTION_ID));
 		let ok = Bridge::set_function_ids(RawOrigin::Root.into(), value);
 		assert_ok!(ok);
+		enact_pending_updates();
 		assert_eq!(FunctionIds::<Test>::get(), value);
 
 		let expected_event = RuntimeEvent::Bridge(Event::FunctionIdsUpdated { value });
@@ -1134,14 +1243,27 @@ fn set_step_verification_key_works_with_root() {
 	new_test_ext().execute_with(|| {
 		let ok = Bridge::set_step_verification_key(RawOrigin::Root.into(), None);
 		assert_ok!(ok);
-		assert_eq!(StepVerificationKey::<Test>::get(), None);
+		enact_pending_updates();
+		assert_eq!(VerificationKeys::<Test>::get(STEP_FUNCTION_ID), None);
 
 		let value = Some(BoundedVec::try_from(STEP_VK.as_bytes().to_vec()).unwrap());
-		let ok = Bridge::set_step_verification_key(RawOrigin::Root.into(), value.clone());
+		let ok = Bridge::set_step_verification_key(RawOrigin::Root.into(), value);
 		assert_ok!(ok);
-		assert_eq!(StepVerificationKey::<Test>::get(), value.clone());
+		enact_pending_updates();
+		let compact_vk = Some(
+			Bridge::prepare_verification_key(ProofSystem::Groth16Bn254, STEP_VK.as_bytes())
+				.unwrap(),
+		);
+		assert_eq!(
+			VerificationKeys::<Test>::get(STEP_FUNCTION_ID),
+			compact_vk.clone()
+		);
 
-		let expected_event = RuntimeEvent::Bridge(Event::StepVerificationKeyUpdated { value });
+		let expected_event = RuntimeEvent::Bridge(Event::VerificationKeyUpdated {
+			function_id: STEP_FUNCTION_ID,
+			proof_system: ProofSystem::Groth16Bn254,
+			value: compact_vk,
+		});
 		System::assert_last_event(expected_event);
 	});
 }
@@ -1160,18 +1282,242 @@ fn set_rotate_verification_key_works_with_root() {
 	new_test_ext().execute_with(|| {
 		let ok = Bridge::set_rotate_verification_key(RawOrigin::Root.into(), None);
 		assert_ok!(ok);
-		assert_eq!(RotateVerificationKey::<Test>::get(), None);
+		enact_pending_updates();
+		assert_eq!(VerificationKeys::<Test>::get(ROTATE_FUNCTION_ID), None);
 
 		let value = Some(BoundedVec::try_from(ROTATE_VK.as_bytes().to_vec()).unwrap());
-		let ok = Bridge::set_rotate_verification_key(RawOrigin::Root.into(), value.clone());
+		let ok = Bridge::set_rotate_verification_key(RawOrigin::Root.into(), value);
+		assert_ok!(ok);
+		enact_pending_updates();
+		let compact_vk = Some(
+			Bridge::prepare_verification_key(ProofSystem::Groth16Bn254, ROTATE_VK.as_bytes())
+				.unwrap(),
+		);
+		assert_eq!(
+			VerificationKeys::<Test>::get(ROTATE_FUNCTION_ID),
+			compact_vk.clone()
+		);
+
+		let expected_event = RuntimeEvent::Bridge(Event::VerificationKeyUpdated {
+			function_id: ROTATE_FUNCTION_ID,
+			proof_system: ProofSystem::Groth16Bn254,
+			value: compact_vk,
+		});
+		System::assert_last_event(expected_event);
+	});
+}
+
+/// Function id registered for neither the step nor the rotate function.
+const OTHER_FUNCTION_ID: H256 = H256(hex!(
+	"bf44af6890508b3b7f6910d4a4570a0d524769a23ce340b2c7400e140ad168ab"
+));
+
+/// Step verification key in the compressed arkworks encoding.
+fn arkworks_step_vk() -> BoundedVec<u8, ConstU32<10_000>> {
+	let vk = Verifier::from_json_u8_slice(STEP_VK.as_bytes())
+		.unwrap()
+		.vk_json
+		.to_verifying_key()
+		.unwrap();
+	let mut bytes = Vec::new();
+	vk.serialize(&mut bytes).unwrap();
+	BoundedVec::try_from(bytes).unwrap()
+}
+
+#[test]
+fn set_function_proof_system_works_with_root() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			FunctionProofSystems::<Test>::get(STEP_FUNCTION_ID),
+			Some(ProofSystem::Groth16Bn254)
+		);
+		let json_vk = VerificationKeys::<Test>::get(STEP_FUNCTION_ID);
+
+		let ok = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			OTHER_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			Some(arkworks_step_vk()),
+		);
+		assert_ok!(ok);
+		// the change is only staged
+		assert_eq!(FunctionProofSystems::<Test>::get(OTHER_FUNCTION_ID), None);
+
+		enact_pending_updates();
+		assert_eq!(
+			FunctionProofSystems::<Test>::get(OTHER_FUNCTION_ID),
+			Some(ProofSystem::Groth16Bn254)
+		);
+		// keys in the json and arkworks encodings are stored the same way
+		assert_eq!(VerificationKeys::<Test>::get(OTHER_FUNCTION_ID), json_vk);
+
+		let expected_event = RuntimeEvent::Bridge(Event::VerificationKeyUpdated {
+			function_id: OTHER_FUNCTION_ID,
+			proof_system: ProofSystem::Groth16Bn254,
+			value: json_vk,
+		});
+		System::assert_last_event(expected_event);
+	});
+}
+
+#[test]
+fn set_function_proof_system_validates_key() {
+	new_test_ext().execute_with(|| {
+		let invalid_vk = BoundedVec::try_from(b"not a key".to_vec()).unwrap();
+		let err = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			STEP_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			Some(invalid_vk),
+		);
+		assert_err!(err, Error::<Test>::MalformedVerificationKey);
+
+		// an arkworks key followed by other bytes is rejected
+		let mut vk = arkworks_step_vk().into_inner();
+		vk.push(0);
+		let err = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			STEP_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			Some(BoundedVec::try_from(vk).unwrap()),
+		);
+		assert_err!(err, Error::<Test>::MalformedVerificationKey);
+
+		// without a key, the key of the function is removed
+		let ok = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			STEP_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			None,
+		);
+		assert_ok!(ok);
+		enact_pending_updates();
+		assert_eq!(VerificationKeys::<Test>::get(STEP_FUNCTION_ID), None);
+	});
+}
+
+#[test]
+fn test_fulfill_step_call_with_arkworks_key() {
+	new_test_ext().execute_with(|| {
+		let ok = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			STEP_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			Some(arkworks_step_vk()),
+		);
+		assert_ok!(ok);
+		enact_pending_updates();
+
+		SyncCommitteePoseidons::<Test>::insert(
+			931,
+			U256::from(hex!(
+				"0ab2afdc05c8b6ae1f2ab20874fb4159e25d5c1d4faa41aee232d6ab331332df"
+			)),
+		);
+
+		let result = Bridge::fulfill_call(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			STEP_FUNCTION_ID,
+			get_valid_step_input(),
+			get_valid_step_output(),
+			get_valid_step_proof(),
+			7634942,
+		);
+
+		assert_ok!(result);
+		assert_eq!(Head::<Test>::get(), 7634848);
+	});
+}
+
+#[test]
+fn test_fulfill_call_of_other_registered_function() {
+	new_test_ext().execute_with(|| {
+		let ok = Bridge::set_function_proof_system(
+			RawOrigin::Root.into(),
+			OTHER_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			Some(arkworks_step_vk()),
+		);
 		assert_ok!(ok);
-		assert_eq!(RotateVerificationKey::<Test>::get(), value.clone());
+		enact_pending_updates();
+
+		let input = get_valid_step_input();
+		let output = get_valid_step_output();
+		let result = Bridge::fulfill_call(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			OTHER_FUNCTION_ID,
+			input.clone(),
+			output.clone(),
+			get_valid_step_proof(),
+			7634942,
+		);
 
-		let expected_event = RuntimeEvent::Bridge(Event::RotateVerificationKeyUpdated { value });
+		assert_ok!(result);
+		// the light client is not updated by other functions
+		assert_eq!(Head::<Test>::get(), 0);
+		let expected_event = RuntimeEvent::Bridge(Event::FunctionCallVerified {
+			function_id: OTHER_FUNCTION_ID,
+			input_hash: H256(sp_io::hashing::sha2_256(input.as_slice())),
+			output_hash: H256(sp_io::hashing::sha2_256(output.as_slice())),
+		});
 		System::assert_last_event(expected_event);
 	});
 }
 
+#[test]
+fn test_fulfill_call_of_unregistered_function_with_key() {
+	new_test_ext().execute_with(|| {
+		VerificationKeys::<Test>::insert(
+			OTHER_FUNCTION_ID,
+			VerificationKeys::<Test>::get(STEP_FUNCTION_ID).unwrap(),
+		);
+
+		let err = Bridge::fulfill_call(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			OTHER_FUNCTION_ID,
+			get_valid_step_input(),
+			get_valid_step_output(),
+			get_valid_step_proof(),
+			7634942,
+		);
+		assert_err!(err, Error::<Test>::FunctionIdNotKnown);
+	});
+}
+
+#[test]
+fn set_function_proof_system_does_not_work_with_non_root() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let ok = Bridge::set_function_proof_system(
+			origin,
+			STEP_FUNCTION_ID,
+			ProofSystem::Groth16Bn254,
+			None,
+		);
+		assert_err!(ok, BadOrigin);
+	});
+}
+
+#[test]
+fn test_fulfill_step_call_with_malformed_stored_key() {
+	new_test_ext().execute_with(|| {
+		VerificationKeys::<Test>::insert(
+			STEP_FUNCTION_ID,
+			BoundedVec::try_from(STEP_VK.as_bytes().to_vec()).unwrap(),
+		);
+
+		let err = Bridge::fulfill_call(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			STEP_FUNCTION_ID,
+			get_valid_step_input(),
+			get_valid_step_output(),
+			get_valid_step_proof(),
+			7634942,
+		);
+		assert_err!(err, Error::<Test>::MalformedVerificationKey);
+	});
+}
+
 #[test]
 fn set_rotate_verification_key_does_not_work_with_non_root() {
 	new_test_ext().execute_with(|| {
@@ -1180,3 +1526,1108 @@ fn set_rotate_verification_key_does_not_work_with_non_root() {
 		assert_err!(ok, BadOrigin);
 	});
 }
+
+#[test]
+fn set_step_verification_key_rejects_malformed_key() {
+	new_test_ext().execute_with(|| {
+		let mut vk = STEP_VK.replace(
+			"20491192805390485299153009773594534940189261866228447918068658471970481763042",
+			"20491192805390485299153009773594534940189261866228447918068658471970481763043",
+		);
+		let value = Some(BoundedVec::try_from(vk.as_bytes().to_vec()).unwrap());
+		let err = Bridge::set_step_verification_key(RawOrigin::Root.into(), value);
+		assert_err!(err, Error::<Test>::MalformedVerificationKey);
+
+		vk = String::from("{}");
+		let value = Some(BoundedVec::try_from(vk.as_bytes().to_vec()).unwrap());
+		let err = Bridge::set_step_verification_key(RawOrigin::Root.into(), value);
+		assert_err!(err, Error::<Test>::MalformedVerificationKey);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Rotate verification key should be valid at genesis.")]
+fn genesis_build_fails_without_verification_key() {
+	crate::GenesisConfig::<Test> {
+		function_ids: (STEP_FUNCTION_ID, ROTATE_FUNCTION_ID),
+		step_verification_key: STEP_VK.as_bytes().to_vec(),
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap();
+}
+
+#[test]
+fn migrate_to_compact_verification_keys_works() {
+	new_test_ext().execute_with(|| {
+		let step_vk = BoundedVec::try_from(STEP_VK.as_bytes().to_vec()).unwrap();
+		let rotate_vk = BoundedVec::try_from(b"not a key".to_vec()).unwrap();
+		StepVerificationKey::<Test>::set(Some(step_vk));
+		RotateVerificationKey::<Test>::set(Some(rotate_vk));
+		StorageVersion::new(0).put::<Bridge>();
+
+		MigrateToCompactVerificationKeys::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Bridge::on_chain_storage_version(), StorageVersion::new(1));
+		assert_eq!(
+			StepVerificationKey::<Test>::get(),
+			Some(
+				Bridge::prepare_verification_key(ProofSystem::Groth16Bn254, STEP_VK.as_bytes())
+					.unwrap()
+			)
+		);
+		// invalid keys are dropped
+		assert_eq!(RotateVerificationKey::<Test>::get(), None);
+
+		// migration runs only once
+		let compact_vk = StepVerificationKey::<Test>::get();
+		MigrateToCompactVerificationKeys::<Test>::on_runtime_upgrade();
+		assert_eq!(StepVerificationKey::<Test>::get(), compact_vk);
+	});
+}
+
+#[test]
+fn migrate_to_verification_keys_by_function_id_works() {
+	new_test_ext().execute_with(|| {
+		let step_vk =
+			Bridge::prepare_verification_key(ProofSystem::Groth16Bn254, STEP_VK.as_bytes())
+				.unwrap();
+		VerificationKeys::<Test>::remove(STEP_FUNCTION_ID);
+		VerificationKeys::<Test>::remove(ROTATE_FUNCTION_ID);
+		StepVerificationKey::<Test>::set(Some(step_vk.clone()));
+		StorageVersion::new(1).put::<Bridge>();
+
+		MigrateToVerificationKeysByFunctionId::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Bridge::on_chain_storage_version(), StorageVersion::new(2));
+		assert_eq!(StepVerificationKey::<Test>::get(), None);
+		assert_eq!(
+			VerificationKeys::<Test>::get(STEP_FUNCTION_ID),
+			Some(step_vk)
+		);
+		assert_eq!(VerificationKeys::<Test>::get(ROTATE_FUNCTION_ID), None);
+	});
+}
+
+#[test]
+fn register_function_proof_systems_works() {
+	new_test_ext().execute_with(|| {
+		FunctionProofSystems::<Test>::remove(STEP_FUNCTION_ID);
+		FunctionProofSystems::<Test>::remove(ROTATE_FUNCTION_ID);
+		StorageVersion::new(2).put::<Bridge>();
+
+		RegisterFunctionProofSystems::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Bridge::on_chain_storage_version(), StorageVersion::new(3));
+		for function_id in [STEP_FUNCTION_ID, ROTATE_FUNCTION_ID] {
+			assert_eq!(
+				FunctionProofSystems::<Test>::get(function_id),
+				Some(ProofSystem::Groth16Bn254)
+			);
+		}
+
+		// migration runs only once
+		FunctionProofSystems::<Test>::remove(STEP_FUNCTION_ID);
+		RegisterFunctionProofSystems::<Test>::on_runtime_upgrade();
+		assert_eq!(FunctionProofSystems::<Test>::get(STEP_FUNCTION_ID), None);
+	});
+}
+
+#[test]
+fn test_fulfill_step_call_with_migrated_key() {
+	new_test_ext().execute_with(|| {
+		let step_vk = BoundedVec::try_from(STEP_VK.as_bytes().to_vec()).unwrap();
+		VerificationKeys::<Test>::remove(STEP_FUNCTION_ID);
+		StepVerificationKey::<Test>::set(Some(step_vk));
+		StorageVersion::new(0).put::<Bridge>();
+		FunctionProofSystems::<Test>::remove(STEP_FUNCTION_ID);
+		MigrateToCompactVerificationKeys::<Test>::on_runtime_upgrade();
+		MigrateToVerificationKeysByFunctionId::<Test>::on_runtime_upgrade();
+		RegisterFunctionProofSystems::<Test>::on_runtime_upgrade();
+
+		SyncCommitteePoseidons::<Test>::insert(
+			931,
+			U256::from(hex!(
+				"0ab2afdc05c8b6ae1f2ab20874fb4159e25d5c1d4faa41aee232d6ab331332df"
+			)),
+		);
+
+		let result = Bridge::fulfill_call(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			STEP_FUNCTION_ID,
+			get_valid_step_input(),
+			get_valid_step_output(),
+			get_valid_step_proof(),
+			7634942,
+		);
+
+		assert_ok!(result);
+		assert_eq!(Head::<Test>::get(), 7634848);
+	});
+}
+
+fn set_fungible_token_execution_state() -> u64 {
+	Broadcasters::<Test>::set(
+		2,
+		H256(hex!(
+			"DC3542b6fcC39dC0d51ecdCbc6Fbb130D5e48d95000000000000000000000000"
+		)),
+	);
+
+	let slot = 8581263;
+	ExecutionStateRoots::<Test>::set(
+		slot,
+		H256(hex!(
+			"c42310d65b1e953e8864480367a03179d6bd78d4ca522a5a977d2801b9b2e1d9"
+		)),
+	);
+
+	slot
+}
+
+#[test]
+fn execute_batch_executes_messages_independently() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let balance_before = Balances::balance(&Bridge::account_id());
+
+		let message = get_valid_message();
+		let message_root = H256(keccak_256(message.clone().abi_encode().as_slice()));
+		let messages = BoundedVec::truncate_from(vec![message.clone(), message.clone()]);
+		let storage_proofs =
+			BoundedVec::truncate_from(vec![get_valid_storage_proof(), get_valid_storage_proof()]);
+
+		let ok = Bridge::execute_batch(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			messages,
+			get_valid_account_proof(),
+			storage_proofs,
+		);
+		assert_ok!(ok);
+
+		// Only the first message is executed, the duplicate fails without reverting it.
+		assert_eq!(
+			balance_before.saturating_sub(1000000000000000000u128),
+			Balances::balance(&Bridge::account_id())
+		);
+		assert_eq!(
+			MessageStatus::<Test>::get(message_root),
+			MessageStatusEnum::ExecutionSucceeded
+		);
+		System::assert_has_event(RuntimeEvent::Bridge(Event::MessageExecuted {
+			from: message.from,
+			to: message.to,
+			message_id: message.id,
+			message_root,
+		}));
+		System::assert_last_event(RuntimeEvent::Bridge(Event::MessageExecutionFailed {
+			message_id: message.id,
+			message_root,
+			error: Error::<Test>::MessageAlreadyExecuted.into(),
+		}));
+	});
+}
+
+#[test]
+fn execute_batch_with_faulty_storage_proof() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+
+		let message = get_valid_message();
+		let message_root = H256(keccak_256(message.clone().abi_encode().as_slice()));
+
+		let ok = Bridge::execute_batch(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			BoundedVec::truncate_from(vec![message.clone()]),
+			get_valid_account_proof(),
+			BoundedVec::truncate_from(vec![get_invalid_storage_proof()]),
+		);
+		assert_ok!(ok);
+
+		assert_eq!(
+			MessageStatus::<Test>::get(message_root),
+			MessageStatusEnum::NotExecuted
+		);
+		System::assert_last_event(RuntimeEvent::Bridge(Event::MessageExecutionFailed {
+			message_id: message.id,
+			message_root,
+			error: Error::<Test>::CannotGetStorageValue.into(),
+		}));
+	});
+}
+
+#[test]
+fn execute_batch_with_faulty_account_proof() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+
+		let fail = Bridge::execute_batch(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			BoundedVec::truncate_from(vec![get_valid_message()]),
+			get_invalid_account_proof(),
+			BoundedVec::truncate_from(vec![get_valid_storage_proof()]),
+		);
+
+		assert_err!(fail, Error::<Test>::CannotGetStorageRoot);
+	});
+}
+
+#[test]
+fn execute_batch_rejects_malformed_batches() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let origin = RuntimeOrigin::signed(TEST_SENDER_ACCOUNT);
+
+		let empty = Bridge::execute_batch(
+			origin.clone(),
+			slot,
+			BoundedVec::new(),
+			get_valid_account_proof(),
+			BoundedVec::new(),
+		);
+		assert_err!(empty, Error::<Test>::EmptyBatch);
+
+		let missing_proof = Bridge::execute_batch(
+			origin.clone(),
+			slot,
+			BoundedVec::truncate_from(vec![get_valid_message(), get_valid_message()]),
+			get_valid_account_proof(),
+			BoundedVec::truncate_from(vec![get_valid_storage_proof()]),
+		);
+		assert_err!(missing_proof, Error::<Test>::BatchLengthMismatch);
+
+		let mut other_domain = get_valid_message();
+		other_domain.origin_domain = 3;
+		let mixed_domains = Bridge::execute_batch(
+			origin.clone(),
+			slot,
+			BoundedVec::truncate_from(vec![get_valid_message(), other_domain]),
+			get_valid_account_proof(),
+			BoundedVec::truncate_from(vec![get_valid_storage_proof(), get_valid_storage_proof()]),
+		);
+		assert_err!(mixed_domains, Error::<Test>::BatchOriginDomainMismatch);
+
+		SourceChainFrozen::<Test>::set(2, true);
+		let frozen = Bridge::execute_batch(
+			origin,
+			slot,
+			BoundedVec::truncate_from(vec![get_valid_message()]),
+			get_valid_account_proof(),
+			BoundedVec::truncate_from(vec![get_valid_storage_proof()]),
+		);
+		assert_err!(frozen, Error::<Test>::SourceChainFrozen);
+	});
+}
+
+#[test]
+fn message_status_by_id_works() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let message = get_valid_message();
+		let message_root = H256(keccak_256(message.clone().abi_encode().as_slice()));
+
+		assert_eq!(
+			Bridge::message_status_by_id(message.origin_domain, message.id),
+			MessageStatusEnum::NotExecuted
+		);
+
+		assert_ok!(Bridge::execute(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message.clone(),
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		));
+
+		assert_eq!(
+			ExecutedMessageRoots::<Test>::get(message.origin_domain, message.id),
+			Some(message_root)
+		);
+		assert_eq!(
+			Bridge::message_status_by_id(message.origin_domain, message.id),
+			MessageStatusEnum::ExecutionSucceeded
+		);
+	});
+}
+
+#[test]
+fn message_slot_key_works() {
+	new_test_ext().execute_with(|| {
+		// Storage slot of message 0 in the broadcaster's message mapping.
+		let expected = H256(hex!(
+			"a6eef7e35abe7026729641147f7915573c7e97b47efa546f5f6e3230263bcb49"
+		));
+
+		assert_eq!(Bridge::message_slot_key(0), expected);
+	});
+}
+
+#[test]
+fn frozen_domains_works() {
+	new_test_ext().execute_with(|| {
+		SourceChainFrozen::<Test>::set(2, true);
+		SourceChainFrozen::<Test>::set(3, false);
+
+		assert_eq!(Bridge::frozen_domains(), vec![2]);
+	});
+}
+
+fn send_message_with_fee(fee: u128) -> (u64, H256) {
+	use crate::BalanceOf;
+	use frame_support::traits::Currency;
+
+	let message = Message::ArbitraryMessage(BoundedVec::truncate_from(b"Hello".to_vec()));
+	let to = ROTATE_FUNCTION_ID;
+	let domain = 2;
+	Balances::make_free_balance_be(&TEST_SENDER_ACCOUNT, BalanceOf::<Test>::max_value() / 2u128);
+
+	assert_ok!(Bridge::send_message_with_fee(
+		RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+		message.clone(),
+		to,
+		domain,
+		fee,
+	));
+
+	let message_id = tx_uid(1, 0);
+	let addr_message =
+		AddressedMessage::new(message, H256(TEST_SENDER_VEC), to, 1, domain, message_id);
+	let message_root = H256(keccak_256(addr_message.abi_encode().as_slice()));
+
+	(message_id, message_root)
+}
+
+#[test]
+fn send_message_with_fee_works() {
+	new_test_ext().execute_with(|| {
+		let pot_before = Balances::balance(&Bridge::account_id());
+		let fee = 1_000u128;
+
+		let (message_id, message_root) = send_message_with_fee(fee);
+
+		assert_eq!(Balances::balance(&Bridge::account_id()), pot_before + fee);
+		assert_eq!(
+			RelayerFees::<Test>::get(message_id),
+			Some(RelayerFee {
+				payer: TEST_SENDER_ACCOUNT,
+				amount: fee,
+				message_root,
+				destination_domain: 2,
+				refundable_at: 101,
+			})
+		);
+		System::assert_last_event(RuntimeEvent::Bridge(Event::RelayerFeeEscrowed {
+			message_id,
+			payer: TEST_SENDER_ACCOUNT,
+			amount: fee,
+		}));
+	});
+}
+
+#[test]
+fn send_message_with_fee_does_not_accept_zero_fee() {
+	new_test_ext().execute_with(|| {
+		let message = Message::ArbitraryMessage(BoundedVec::truncate_from(b"Hello".to_vec()));
+
+		let err = Bridge::send_message_with_fee(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			message,
+			ROTATE_FUNCTION_ID,
+			2,
+			0,
+		);
+		assert_err!(err, Error::<Test>::InvalidRelayerFee);
+	});
+}
+
+#[test]
+fn refund_relayer_fee_works_after_timeout() {
+	new_test_ext().execute_with(|| {
+		let fee = 1_000u128;
+		let (message_id, _) = send_message_with_fee(fee);
+		let sender_balance = Balances::balance(&TEST_SENDER_ACCOUNT);
+
+		System::set_block_number(101);
+		assert_ok!(Bridge::refund_relayer_fee(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			message_id
+		));
+
+		assert_eq!(
+			Balances::balance(&TEST_SENDER_ACCOUNT),
+			sender_balance + fee
+		);
+		assert_eq!(RelayerFees::<Test>::get(message_id), None);
+		System::assert_last_event(RuntimeEvent::Bridge(Event::RelayerFeeRefunded {
+			message_id,
+			payer: TEST_SENDER_ACCOUNT,
+			amount: fee,
+		}));
+	});
+}
+
+#[test]
+fn refund_relayer_fee_does_not_work_before_timeout() {
+	new_test_ext().execute_with(|| {
+		let (message_id, _) = send_message_with_fee(1_000);
+
+		System::set_block_number(100);
+		let err =
+			Bridge::refund_relayer_fee(RuntimeOrigin::signed(TEST_SENDER_ACCOUNT), message_id);
+		assert_err!(err, Error::<Test>::RelayerFeeNotRefundable);
+	});
+}
+
+#[test]
+fn claim_relayer_fee_works() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let fee = 1_000u128;
+		let message_id = 1;
+		// The valid storage proof proves message 0 in the message mapping, which the mock also
+		// uses as delivery mapping, so it proves the delivery of the message root zero. The proven
+		// value, the root of the valid message, is read as the account of the relayer.
+		RelayerFees::<Test>::insert(
+			message_id,
+			RelayerFee {
+				payer: TEST_SENDER_ACCOUNT,
+				amount: fee,
+				message_root: H256::zero(),
+				destination_domain: 2,
+				refundable_at: 101,
+			},
+		);
+		let relayer = AccountId32::new(keccak_256(get_valid_message().abi_encode().as_slice()));
+		let pot_before = Balances::balance(&Bridge::account_id());
+
+		// the proof is submitted by another account than the relayer
+		assert_ok!(Bridge::claim_relayer_fee(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			message_id,
+			slot,
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		));
+
+		assert_eq!(Balances::balance(&relayer), fee);
+		assert_eq!(Balances::balance(&Bridge::account_id()), pot_before - fee);
+		assert_eq!(RelayerFees::<Test>::get(message_id), None);
+		System::assert_last_event(RuntimeEvent::Bridge(Event::RelayerFeeClaimed {
+			message_id,
+			relayer,
+			amount: fee,
+		}));
+	});
+}
+
+#[test]
+fn claim_relayer_fee_does_not_work_without_fee() {
+	new_test_ext().execute_with(|| {
+		let err = Bridge::claim_relayer_fee(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			tx_uid(1, 0),
+			8581263,
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_err!(err, Error::<Test>::RelayerFeeNotFound);
+	});
+}
+
+#[test]
+fn claim_relayer_fee_does_not_work_with_faulty_account_proof() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let (message_id, _) = send_message_with_fee(1_000);
+
+		let err = Bridge::claim_relayer_fee(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			message_id,
+			slot,
+			get_invalid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_err!(err, Error::<Test>::CannotGetStorageRoot);
+		assert!(RelayerFees::<Test>::get(message_id).is_some());
+	});
+}
+
+#[test]
+fn execute_does_not_work_before_challenge_delay() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		ChallengeDelay::<Test>::put(100);
+		Timestamps::<Test>::insert(slot, 1_000);
+		Timestamp::set_timestamp(1_099_000);
+
+		let err = Bridge::execute(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			get_valid_message(),
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_err!(err, Error::<Test>::ChallengePeriodNotElapsed);
+
+		Timestamp::set_timestamp(1_100_000);
+		let ok = Bridge::execute(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			get_valid_message(),
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_ok!(ok);
+	});
+}
+
+#[test]
+fn send_message_increments_outbound_nonce() {
+	new_test_ext().execute_with(|| {
+		let message = Message::ArbitraryMessage(BoundedVec::truncate_from([0, 1, 2, 3].to_vec()));
+		assert_eq!(Bridge::next_outbound_nonce(1, 2), 0);
+
+		for nonce in 0..3 {
+			let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+			assert_ok!(Bridge::send_message(
+				origin,
+				message.clone(),
+				ROTATE_FUNCTION_ID,
+				2
+			));
+
+			let expected_event = RuntimeEvent::Bridge(Event::MessageSubmitted {
+				from: TEST_SENDER_VEC.into(),
+				to: ROTATE_FUNCTION_ID,
+				message_type: message.r#type(),
+				destination_domain: 2,
+				message_id: tx_uid(1, 0),
+				nonce,
+			});
+			System::assert_last_event(expected_event);
+		}
+		assert_eq!(OutboundNonces::<Test>::get(1, 2), 3);
+
+		// Failed sends do not use a nonce.
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let err = Bridge::send_message(origin, message, ROTATE_FUNCTION_ID, 3);
+		assert_err!(err, Error::<Test>::DomainNotSupported);
+		assert_eq!(Bridge::next_outbound_nonce(1, 3), 0);
+	});
+}
+
+#[test]
+fn set_inbound_ordering_works_with_root() {
+	new_test_ext().execute_with(|| {
+		let ok = Bridge::set_inbound_ordering(RawOrigin::Root.into(), 2, Some(5));
+		assert_ok!(ok);
+		assert_eq!(Bridge::next_inbound_nonce(2), None);
+
+		enact_pending_updates();
+		assert_eq!(Bridge::next_inbound_nonce(2), Some(5));
+
+		let expected_event = RuntimeEvent::Bridge(Event::InboundOrderingUpdated {
+			domain: 2,
+			next_nonce: Some(5),
+		});
+		System::assert_last_event(expected_event);
+
+		assert_ok!(Bridge::set_inbound_ordering(
+			RawOrigin::Root.into(),
+			2,
+			None
+		));
+		enact_pending_updates();
+		assert_eq!(InboundNonces::<Test>::get(2), None);
+	});
+}
+
+#[test]
+fn set_inbound_ordering_does_not_work_with_non_root() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let ok = Bridge::set_inbound_ordering(origin, 2, Some(0));
+		assert_err!(ok, BadOrigin);
+	});
+}
+
+#[test]
+fn execute_does_not_work_out_of_order() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let message = get_valid_message();
+		InboundNonces::<Test>::insert(message.origin_domain, message.id + 1);
+
+		let err = Bridge::execute(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message.clone(),
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_err!(err, Error::<Test>::MessageOutOfOrder);
+
+		InboundNonces::<Test>::insert(message.origin_domain, message.id);
+		let ok = Bridge::execute(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message.clone(),
+			get_valid_account_proof(),
+			get_valid_storage_proof(),
+		);
+		assert_ok!(ok);
+		assert_eq!(
+			InboundNonces::<Test>::get(message.origin_domain),
+			Some(message.id + 1)
+		);
+	});
+}
+
+#[test]
+fn set_challenge_delay_works_with_root() {
+	new_test_ext().execute_with(|| {
+		let ok = Bridge::set_challenge_delay(RawOrigin::Root.into(), 1_800);
+		assert_ok!(ok);
+		assert_eq!(ChallengeDelay::<Test>::get(), 0);
+
+		enact_pending_updates();
+		assert_eq!(ChallengeDelay::<Test>::get(), 1_800);
+
+		let expected_event = RuntimeEvent::Bridge(Event::ChallengeDelayUpdated { value: 1_800 });
+		System::assert_last_event(expected_event);
+	});
+}
+
+#[test]
+fn set_challenge_delay_does_not_work_with_non_root() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let ok = Bridge::set_challenge_delay(origin, 1_800);
+		assert_err!(ok, BadOrigin);
+	});
+}
+
+#[test]
+fn invalidate_slot_works_with_root() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		Headers::<Test>::insert(slot, H256::repeat_byte(1));
+		ChallengeDelay::<Test>::put(100);
+		Timestamps::<Test>::insert(slot, 1_000);
+		Timestamp::set_timestamp(1_099_000);
+
+		let ok = Bridge::invalidate_slot(RawOrigin::Root.into(), slot);
+		assert_ok!(ok);
+		assert_eq!(Headers::<Test>::get(slot), H256::zero());
+		assert!(!ExecutionStateRoots::<Test>::contains_key(slot));
+		assert!(!Timestamps::<Test>::contains_key(slot));
+
+		let expected_event = RuntimeEvent::Bridge(Event::SlotInvalidated { slot });
+		System::assert_last_event(expected_event);
+
+		let err = Bridge::invalidate_slot(RawOrigin::Root.into(), slot);
+		assert_err!(err, Error::<Test>::SlotRootsNotSet);
+	});
+}
+
+#[test]
+fn invalidate_slot_allows_refulfilling_the_head() {
+	new_test_ext().execute_with(|| {
+		SyncCommitteePoseidons::<Test>::insert(
+			931,
+			U256::from(hex!(
+				"0ab2afdc05c8b6ae1f2ab20874fb4159e25d5c1d4faa41aee232d6ab331332df"
+			)),
+		);
+		ConfigurationStorage::<Test>::set(Configuration {
+			slots_per_period: 8192,
+			finality_threshold: 461,
+		});
+		ChallengeDelay::<Test>::put(100);
+		let fulfill_step = || {
+			Bridge::fulfill_call(
+				RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+				STEP_FUNCTION_ID,
+				get_valid_step_input(),
+				get_valid_step_output(),
+				get_valid_step_proof(),
+				7634942,
+			)
+		};
+		let finalized_slot = 7634848;
+		assert_ok!(fulfill_step());
+
+		assert_ok!(Bridge::invalidate_slot(
+			RawOrigin::Root.into(),
+			finalized_slot
+		));
+		assert_eq!(Head::<Test>::get(), finalized_slot - 1);
+
+		assert_ok!(fulfill_step());
+		assert_eq!(Head::<Test>::get(), finalized_slot);
+		assert!(ExecutionStateRoots::<Test>::contains_key(finalized_slot));
+	});
+}
+
+#[test]
+fn invalidate_slot_does_not_work_after_challenge_window() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		ChallengeDelay::<Test>::put(100);
+		Timestamps::<Test>::insert(slot, 1_000);
+		Timestamp::set_timestamp(1_100_000);
+
+		let err = Bridge::invalidate_slot(RawOrigin::Root.into(), slot);
+		assert_err!(err, Error::<Test>::ChallengeWindowClosed);
+		assert!(ExecutionStateRoots::<Test>::contains_key(slot));
+	});
+}
+
+#[test]
+fn invalidate_slot_does_not_work_with_non_root() {
+	new_test_ext().execute_with(|| {
+		let origin = RuntimeOrigin::signed(TEST_SENDER_VEC.into());
+		let err = Bridge::invalidate_slot(origin, 1);
+		assert_err!(err, BadOrigin);
+	});
+}
+
+fn sha256_pair(left: H256, right: H256) -> H256 {
+	H256(sp_io::hashing::sha2_256(
+		&[left.as_bytes(), right.as_bytes()].concat(),
+	))
+}
+
+/// Builds a beacon block header root for `slot` with the given execution state root, returning
+/// it together with the slot and execution state root branches.
+fn build_header(slot: u64, execution_state_root: H256) -> (H256, Vec<H256>, Vec<H256>) {
+	let zero = H256::zero();
+	let body_gindex = ssz::concat_generalized_indices(&[
+		ssz::BODY_EXECUTION_PAYLOAD_GINDEX,
+		ssz::PAYLOAD_STATE_ROOT_GINDEX,
+	]);
+	let body_branch = vec![zero; body_gindex.ilog2() as usize];
+	let body_root = ssz::compute_root(execution_state_root, &body_branch, body_gindex).unwrap();
+
+	let slot_leaf = ssz::uint64_leaf(slot);
+	let left = sha256_pair(sha256_pair(slot_leaf, zero), sha256_pair(zero, zero));
+	let right = sha256_pair(sha256_pair(body_root, zero), sha256_pair(zero, zero));
+	let header_root = sha256_pair(left, right);
+
+	let slot_branch = vec![zero, sha256_pair(zero, zero), right];
+	let mut execution_state_root_branch = body_branch;
+	execution_state_root_branch.extend([zero, sha256_pair(zero, zero), left]);
+
+	(header_root, slot_branch, execution_state_root_branch)
+}
+
+fn historical_slot_proof(anchor_slot: u64, slot: u64) -> HistoricalSlotProof {
+	let execution_state_root = H256::repeat_byte(7);
+	let (header_root, slot_branch, execution_state_root_branch) =
+		build_header(slot, execution_state_root);
+
+	let gindex = ssz::block_root_gindex(slot);
+	let block_root_branch = vec![H256::zero(); gindex.ilog2() as usize];
+	let anchor_root = ssz::compute_root(header_root, &block_root_branch, gindex).unwrap();
+	Headers::<Test>::insert(anchor_slot, anchor_root);
+
+	HistoricalSlotProof {
+		header_root,
+		block_root_branch: BoundedVec::truncate_from(block_root_branch),
+		slot_branch: BoundedVec::truncate_from(slot_branch),
+		execution_state_root,
+		execution_state_root_branch: BoundedVec::truncate_from(execution_state_root_branch),
+	}
+}
+
+#[test]
+fn ssz_compute_root_works() {
+	let zero = H256::zero();
+	let expected = H256(hex!(
+		"f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b"
+	));
+	assert_eq!(ssz::compute_root(zero, &[zero], 2), Ok(expected));
+	assert_eq!(ssz::compute_root(zero, &[zero], 3), Ok(expected));
+	assert_eq!(
+		ssz::compute_root(zero, &[zero], 4),
+		Err(ssz::SszError::BranchLengthMismatch)
+	);
+	assert_eq!(
+		ssz::compute_root(zero, &[], 0),
+		Err(ssz::SszError::InvalidGeneralizedIndex)
+	);
+	assert_eq!(
+		ssz::verify_merkle_proof(zero, &[zero], 2, H256::repeat_byte(1)),
+		Err(ssz::SszError::RootMismatch)
+	);
+}
+
+#[test]
+fn ssz_generalized_indices_work() {
+	// Capella execution state root index relative to the block body.
+	assert_eq!(ssz::concat_generalized_indices(&[25, 18]), 402);
+	assert_eq!(ssz::EXECUTION_STATE_ROOT_GINDEX, 6434);
+	assert_eq!(ssz::block_root_gindex(8192 + 5), 357 * 8192 + 5);
+}
+
+#[test]
+fn fulfill_historical_slot_works() {
+	new_test_ext().execute_with(|| {
+		let (anchor_slot, slot) = (9000, 8000);
+		let proof = historical_slot_proof(anchor_slot, slot);
+		Timestamp::set_timestamp(1_000_000);
+
+		let ok = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof.clone(),
+		);
+		assert_ok!(ok);
+		assert_eq!(Headers::<Test>::get(slot), proof.header_root);
+		assert_eq!(
+			ExecutionStateRoots::<Test>::get(slot),
+			proof.execution_state_root
+		);
+		assert_eq!(Timestamps::<Test>::get(slot), 1_000);
+
+		let expected_event = RuntimeEvent::Bridge(Event::HistoricalSlotProven {
+			slot,
+			anchor_slot,
+			header_root: proof.header_root,
+			execution_state_root: proof.execution_state_root,
+		});
+		System::assert_last_event(expected_event);
+
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::HeaderRootAlreadySet);
+	});
+}
+
+#[test]
+fn fulfill_historical_slot_does_not_work_with_invalid_proof() {
+	new_test_ext().execute_with(|| {
+		let (anchor_slot, slot) = (9000, 8000);
+		let mut proof = historical_slot_proof(anchor_slot, slot);
+		proof.execution_state_root = H256::repeat_byte(8);
+
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::InvalidSszProof);
+
+		// The header root is proven for `slot`, not for the previous one.
+		let proof = historical_slot_proof(anchor_slot, slot);
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot - 1,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::InvalidSszProof);
+		assert_eq!(ExecutionStateRoots::<Test>::get(slot), H256::zero());
+	});
+}
+
+#[test]
+fn fulfill_historical_slot_does_not_work_out_of_range() {
+	new_test_ext().execute_with(|| {
+		let anchor_slot = 9000;
+		let slot = anchor_slot - ssz::SLOTS_PER_HISTORICAL_ROOT - 1;
+		let proof = historical_slot_proof(anchor_slot, slot);
+
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof.clone(),
+		);
+		assert_err!(err, Error::<Test>::SlotOutOfHistoricalRange);
+
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			anchor_slot,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::SlotOutOfHistoricalRange);
+	});
+}
+
+#[test]
+fn fulfill_historical_slot_does_not_work_with_anchor_in_challenge_window() {
+	new_test_ext().execute_with(|| {
+		let (anchor_slot, slot) = (9000, 8000);
+		let proof = historical_slot_proof(anchor_slot, slot);
+		ChallengeDelay::<Test>::put(100);
+		Timestamps::<Test>::insert(anchor_slot, 1_000);
+		Timestamp::set_timestamp(1_099_000);
+
+		let err = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof.clone(),
+		);
+		assert_err!(err, Error::<Test>::ChallengePeriodNotElapsed);
+		assert_eq!(Headers::<Test>::get(slot), H256::zero());
+
+		Timestamp::set_timestamp(1_100_000);
+		let ok = Bridge::fulfill_historical_slot(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			anchor_slot,
+			slot,
+			proof,
+		);
+		assert_ok!(ok);
+	});
+}
+
+/// Stores a header for `slot` whose receipts root proves a receipt emitting `message`.
+fn receipt_proof(slot: u64, message: &AddressedMessage) -> ReceiptProof {
+	let broadcaster = hex!("DC3542b6fcC39dC0d51ecdCbc6Fbb130D5e48d95");
+	let data = ethabi::encode(&[ethabi::Token::Bytes(message.clone().abi_encode())]);
+
+	let mut log = RlpStream::new_list(3);
+	log.append(&broadcaster.as_slice());
+	log.begin_list(1);
+	log.append(&MESSAGE_SENT_TOPIC.as_bytes());
+	log.append(&data);
+
+	let mut receipt = RlpStream::new_list(4);
+	receipt.append(&1u8);
+	receipt.append(&21_000u64);
+	receipt.append(&vec![0u8; 256]);
+	receipt.begin_list(1);
+	receipt.append_raw(&log.out(), 1);
+	let receipt = [&[2u8][..], &receipt.out()].concat();
+
+	// Receipts trie with the receipt of the first transaction as its only leaf.
+	let mut leaf = RlpStream::new_list(2);
+	leaf.append(&vec![0x20u8, 0x80]);
+	leaf.append(&receipt);
+	let leaf = leaf.out().to_vec();
+	let receipts_root = H256(keccak_256(&leaf));
+
+	let branch = vec![H256::zero(); ssz::RECEIPTS_ROOT_GINDEX.ilog2() as usize];
+	let header = ssz::compute_root(receipts_root, &branch, ssz::RECEIPTS_ROOT_GINDEX).unwrap();
+	Headers::<Test>::insert(slot, header);
+
+	ReceiptProof {
+		receipts_root,
+		receipts_root_branch: BoundedVec::truncate_from(branch),
+		tx_index: 0,
+		receipt_proof: BoundedVec::truncate_from(vec![BoundedVec::truncate_from(leaf)]),
+		log_index: 0,
+	}
+}
+
+#[test]
+fn execute_via_receipt_works() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let balance_before = Balances::balance(&Bridge::account_id());
+		let message = get_valid_message();
+		let message_root = H256(keccak_256(&message.clone().abi_encode()));
+		let proof = receipt_proof(slot, &message);
+
+		let ok = Bridge::execute_via_receipt(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message.clone(),
+			proof.clone(),
+		);
+		assert_ok!(ok);
+		assert_eq!(
+			Balances::balance(&Bridge::account_id()),
+			balance_before - 1_000_000_000_000_000_000u128
+		);
+		assert_eq!(
+			MessageStatus::<Test>::get(message_root),
+			MessageStatusEnum::ExecutionSucceeded
+		);
+
+		let err = Bridge::execute_via_receipt(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::MessageAlreadyExecuted);
+	});
+}
+
+#[test]
+fn execute_via_receipt_does_not_work_with_invalid_log() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let message = get_valid_message();
+		let mut proof = receipt_proof(slot, &message);
+		proof.log_index = 1;
+
+		let err = Bridge::execute_via_receipt(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message.clone(),
+			proof,
+		);
+		assert_err!(err, Error::<Test>::InvalidMessageLog);
+
+		// The receipt emits a message with another id.
+		let mut other = message.clone();
+		other.id = 1;
+		let proof = receipt_proof(slot, &other);
+		let err = Bridge::execute_via_receipt(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::InvalidMessageHash);
+	});
+}
+
+#[test]
+fn execute_via_receipt_does_not_work_with_invalid_receipts_root() {
+	new_test_ext().execute_with(|| {
+		let slot = set_fungible_token_execution_state();
+		let message = get_valid_message();
+		let mut proof = receipt_proof(slot, &message);
+		proof.receipts_root = H256::repeat_byte(1);
+
+		let err = Bridge::execute_via_receipt(
+			RuntimeOrigin::signed(TEST_SENDER_ACCOUNT),
+			slot,
+			message,
+			proof,
+		);
+		assert_err!(err, Error::<Test>::InvalidSszProof);
+	});
+}
+
+#[test]
+fn execute_via_receipt_weight_grows_with_the_proof() {
+	new_test_ext().execute_with(|| {
+		let message = get_valid_message();
+		let mut proof = receipt_proof(1, &message);
+		let weight = crate::weight_helper::execute_via_receipt::<Test>(&message, &proof);
+
+		proof
+			.receipt_proof
+			.try_insert(0, BoundedVec::truncate_from(vec![0u8; 532]))
+			.unwrap();
+		let larger_weight = crate::weight_helper::execute_via_receipt::<Test>(&message, &proof);
+		assert!(larger_weight.ref_time() > weight.ref_time());
+	});
+}
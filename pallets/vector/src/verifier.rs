@@ -1,18 +1,23 @@
-use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
-use ark_groth16::{prepare_verifying_key, verify_proof, Proof, VerifyingKey};
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_groth16::{prepare_verifying_key, verify_proof, PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::boxed::Box;
 use ark_std::str::FromStr;
 use ark_std::string::String;
 use ark_std::string::ToString;
 use ark_std::vec;
 use ark_std::vec::Vec;
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, MaxEncodedLen};
 use ethabi::ParamType;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_core::{H256, U256};
 
-use crate::state::{CircomProof, PublicSignals};
+use crate::{
+	state::{CircomProof, PublicSignals},
+	weights::WeightInfo,
+};
+use frame_support::weights::Weight;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode, TypeInfo)]
 pub enum VerificationError {
@@ -141,6 +146,21 @@ impl Verifier {
 		serde_json::from_slice(slice).map_err(|_| VKeyDeserializationError::SerdeError)
 	}
 
+	/// Converts the json verification key into its compact binary encoding.
+	///
+	/// All points are checked to be on the curve and in the correct subgroup, and the
+	/// `e(alpha, beta)` pairing is precomputed, so that the result can be loaded with
+	/// [`PreparedVerifier::from_compact_bytes`] without any further validation.
+	pub fn to_compact_bytes(&self) -> Result<Vec<u8>, VerificationError> {
+		let vk = self.vk_json.to_verifying_key()?;
+
+		if vk.gamma_abc_g1.len() != self.vk_json.inputs_count as usize + 1 {
+			return Err(VerificationError::InvalidVK);
+		}
+
+		compact_bytes(&vk)
+	}
+
 	/// Verifies input based on the supplied proof and hashes.
 	pub fn verify(
 		self,
@@ -148,40 +168,210 @@ impl Verifier {
 		output_hash: H256,
 		proof: Vec<u8>,
 	) -> Result<bool, VerificationError> {
-		// remove first 3 bits from input_hash and output_hash
-		let bits_mask = 0b00011111;
-		let mut input_swap = input_hash.to_fixed_bytes();
-		let input_hash_byte_swap = input_hash[0] & bits_mask;
-		input_swap[0] = input_hash_byte_swap;
+		let proof = parse_proof(proof)?;
+		let inputs = public_inputs(input_hash, output_hash)?;
 
-		let mut output_swap = output_hash.to_fixed_bytes();
-		let output_hash_byte_swap = output_hash[0] & bits_mask;
-		output_swap[0] = output_hash_byte_swap;
+		self.verify_proof(proof, &inputs)
+	}
 
-		let decoded: (Vec<String>, Vec<Vec<String>>, Vec<String>) = decode_proof(proof)?;
+	fn verify_proof(self, proof: Proof<Bn254>, inputs: &[Fr]) -> Result<bool, VerificationError> {
+		let vk = self.vk_json.to_verifying_key()?;
+		let pvk = prepare_verifying_key(&vk);
 
-		let circom_proof = CircomProof::new(decoded.0, decoded.1, decoded.2);
-		let proof = circom_proof.proof()?;
+		let result = verify_proof(&pvk, &proof, inputs);
+		result.map_err(|_| VerificationError::InvalidProof)
+	}
+}
 
-		let mut input = vec!["0".to_string(); 2];
-		input[0] = U256::from_big_endian(output_swap.as_slice()).to_string();
-		input[1] = U256::from_big_endian(input_swap.as_slice()).to_string();
+/// PreparedVerifier holds a verification key that was loaded from its compact binary encoding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedVerifier {
+	pvk: PreparedVerifyingKey<Bn254>,
+}
 
-		let public_signals = PublicSignals::from(input);
+impl PreparedVerifier {
+	/// Creates `PreparedVerifier` from the encoding produced by [`Verifier::to_compact_bytes`].
+	/// Points are not validated again, they were checked before the key got stored.
+	pub fn from_compact_bytes(mut bytes: &[u8]) -> Result<Self, VerificationError> {
+		let vk = VerifyingKey::<Bn254>::deserialize_unchecked(&mut bytes)
+			.map_err(|_| VerificationError::InvalidVK)?;
+		let alpha_g1_beta_g2 =
+			Fq12::deserialize_unchecked(&mut bytes).map_err(|_| VerificationError::InvalidVK)?;
+		if !bytes.is_empty() {
+			return Err(VerificationError::InvalidVK);
+		}
 
-		let result = self.verify_proof(proof.clone(), &public_signals.get()?);
+		let pvk = PreparedVerifyingKey {
+			alpha_g1_beta_g2,
+			gamma_g2_neg_pc: (-vk.gamma_g2).into(),
+			delta_g2_neg_pc: (-vk.delta_g2).into(),
+			vk,
+		};
 
-		result.map_err(|_| VerificationError::InvalidProof)
+		Ok(Self { pvk })
 	}
-	fn verify_proof(self, proof: Proof<Bn254>, inputs: &[Fr]) -> Result<bool, VerificationError> {
-		let vk = self.vk_json.to_verifying_key()?;
-		let pvk = prepare_verifying_key(&vk);
 
-		let result = verify_proof(&pvk, &proof, inputs);
-		result.map_err(|_| VerificationError::InvalidProof)
+	/// Verifies input based on the supplied proof and hashes.
+	pub fn verify(
+		&self,
+		input_hash: H256,
+		output_hash: H256,
+		proof: Vec<u8>,
+	) -> Result<bool, VerificationError> {
+		let proof = parse_proof(proof)?;
+		let inputs = public_inputs(input_hash, output_hash)?;
+
+		verify_proof(&self.pvk, &proof, &inputs).map_err(|_| VerificationError::InvalidProof)
+	}
+}
+
+/// ProofVerifier is implemented by every proof system that can be used by `fulfill_call`.
+pub trait ProofVerifier {
+	/// Validates a verification key and converts it into the encoding that is kept in storage.
+	fn prepare_verification_key(vk: &[u8]) -> Result<Vec<u8>, VerificationError>;
+
+	/// Verifies the proof against a verification key produced by `prepare_verification_key`.
+	fn verify(
+		vk: &[u8],
+		input_hash: H256,
+		output_hash: H256,
+		proof: Vec<u8>,
+	) -> Result<bool, VerificationError>;
+
+	/// Weight of `fulfill_call` for a function verified with this proof system.
+	fn fulfill_call_weight<W: WeightInfo>() -> Weight;
+}
+
+/// Groth16 proof system over the BN254 curve.
+///
+/// Keys are given either in the circom json format or in the compressed arkworks encoding, and
+/// proofs are abi encoded.
+pub struct Groth16Bn254;
+
+impl ProofVerifier for Groth16Bn254 {
+	fn prepare_verification_key(vk: &[u8]) -> Result<Vec<u8>, VerificationError> {
+		// Binary keys are never valid json, so a key is only read as arkworks if it is not json.
+		if let Ok(verifier) = Verifier::from_json_u8_slice(vk) {
+			return verifier.to_compact_bytes();
+		}
+
+		let mut bytes = vk;
+		let key = VerifyingKey::<Bn254>::deserialize(&mut bytes)
+			.map_err(|_| VerificationError::InvalidVK)?;
+		if !bytes.is_empty() || key.gamma_abc_g1.len() != PUBLIC_INPUTS_COUNT + 1 {
+			return Err(VerificationError::InvalidVK);
+		}
+
+		compact_bytes(&key)
+	}
+
+	fn verify(
+		vk: &[u8],
+		input_hash: H256,
+		output_hash: H256,
+		proof: Vec<u8>,
+	) -> Result<bool, VerificationError> {
+		PreparedVerifier::from_compact_bytes(vk)?.verify(input_hash, output_hash, proof)
+	}
+
+	/// The step and rotate benchmarks both verify a Groth16 proof, the weight covers either.
+	fn fulfill_call_weight<W: WeightInfo>() -> Weight {
+		W::fulfill_call_step().max(W::fulfill_call_rotate())
 	}
 }
 
+/// ProofSystem is the proof system registered for a function id.
+#[derive(Clone, Copy, Default, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum ProofSystem {
+	/// Groth16 over the BN254 curve, see [`Groth16Bn254`].
+	#[default]
+	Groth16Bn254,
+}
+
+impl ProofSystem {
+	/// Validates a verification key and converts it into the encoding of this proof system.
+	pub fn prepare_verification_key(&self, vk: &[u8]) -> Result<Vec<u8>, VerificationError> {
+		match self {
+			ProofSystem::Groth16Bn254 => Groth16Bn254::prepare_verification_key(vk),
+		}
+	}
+
+	/// Verifies the proof with this proof system.
+	pub fn verify(
+		&self,
+		vk: &[u8],
+		input_hash: H256,
+		output_hash: H256,
+		proof: Vec<u8>,
+	) -> Result<bool, VerificationError> {
+		match self {
+			ProofSystem::Groth16Bn254 => Groth16Bn254::verify(vk, input_hash, output_hash, proof),
+		}
+	}
+
+	/// Weight of `fulfill_call` for a function verified with this proof system.
+	pub fn fulfill_call_weight<W: WeightInfo>(&self) -> Weight {
+		match self {
+			ProofSystem::Groth16Bn254 => Groth16Bn254::fulfill_call_weight::<W>(),
+		}
+	}
+}
+
+/// Number of public inputs of the proofs given to `fulfill_call`, the input and output hashes.
+const PUBLIC_INPUTS_COUNT: usize = 2;
+
+/// compact_bytes checks that all points of the key are on the curve and in the correct subgroup,
+/// and encodes the key together with the precomputed `e(alpha, beta)` pairing.
+fn compact_bytes(vk: &VerifyingKey<Bn254>) -> Result<Vec<u8>, VerificationError> {
+	let g1_valid = |p: &G1Affine| p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve();
+	let g2_valid = |p: &G2Affine| p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve();
+	let is_valid = g1_valid(&vk.alpha_g1)
+		&& g2_valid(&vk.beta_g2)
+		&& g2_valid(&vk.gamma_g2)
+		&& g2_valid(&vk.delta_g2)
+		&& vk.gamma_abc_g1.iter().all(g1_valid);
+	if !is_valid {
+		return Err(VerificationError::InvalidVK);
+	}
+
+	let pvk = prepare_verifying_key(vk);
+	let mut bytes = Vec::new();
+	vk.serialize_unchecked(&mut bytes)
+		.map_err(|_| VerificationError::InvalidVK)?;
+	pvk.alpha_g1_beta_g2
+		.serialize_unchecked(&mut bytes)
+		.map_err(|_| VerificationError::InvalidVK)?;
+
+	Ok(bytes)
+}
+
+/// parse_proof decodes abi encoded proof into a groth16 proof.
+fn parse_proof(proof: Vec<u8>) -> Result<Proof<Bn254>, VerificationError> {
+	let decoded: (Vec<String>, Vec<Vec<String>>, Vec<String>) = decode_proof(proof)?;
+
+	let circom_proof = CircomProof::new(decoded.0, decoded.1, decoded.2);
+	circom_proof.proof()
+}
+
+/// public_inputs builds public signals from the input and output hashes.
+fn public_inputs(input_hash: H256, output_hash: H256) -> Result<Vec<Fr>, VerificationError> {
+	// remove first 3 bits from input_hash and output_hash
+	let bits_mask = 0b00011111;
+	let mut input_swap = input_hash.to_fixed_bytes();
+	let input_hash_byte_swap = input_hash[0] & bits_mask;
+	input_swap[0] = input_hash_byte_swap;
+
+	let mut output_swap = output_hash.to_fixed_bytes();
+	let output_hash_byte_swap = output_hash[0] & bits_mask;
+	output_swap[0] = output_hash_byte_swap;
+
+	let mut input = vec!["0".to_string(); 2];
+	input[0] = U256::from_big_endian(output_swap.as_slice()).to_string();
+	input[1] = U256::from_big_endian(input_swap.as_slice()).to_string();
+
+	PublicSignals::from(input).get()
+}
+
 /// decode_proof decodes proof into points.
 #[allow(clippy::type_complexity)]
 pub fn decode_proof(
@@ -277,7 +467,8 @@ mod tests {
 	use sp_core::{H256, U256};
 	use sp_io::hashing::sha2_256;
 
-	use crate::verifier::{decode_proof, encode_packed, Verifier};
+	use crate::mock::STEP_VK;
+	use crate::verifier::{decode_proof, encode_packed, PreparedVerifier, Verifier};
 
 	#[test]
 	fn test_zk_step_with_serde() {
@@ -543,6 +734,40 @@ mod tests {
 		assert!(result.unwrap());
 	}
 
+	#[test]
+	fn test_zk_step_with_compact_key() {
+		let v = Verifier::from_json_u8_slice(STEP_VK.as_bytes()).unwrap();
+		let compact_vk = v.to_compact_bytes().unwrap();
+		let pv = PreparedVerifier::from_compact_bytes(compact_vk.as_slice()).unwrap();
+
+		let inp = hex!(
+			"0ab2afdc05c8b6ae1f2ab20874fb4159e25d5c1d4faa41aee232d6ab331332df0000000000747ffe"
+		);
+		let out = hex!("e4566e0cf4edb171a3eedd59f9943bbcd0b1f6b648f1a6e26d5264b668ab41ec51e76629b32b943497207e7b7ccff8fbc12e9e6d758cc7eed972422c4cad02b90000000000747fa001fd");
+		let inp_hash = H256(sha2_256(inp.as_slice()));
+		let out_hash = H256(sha2_256(out.as_slice()));
+
+		let proof = hex!("0b496d04c0e12206bc846edd2077a20b8b55f65fc0e40bb8cf617d9b79ce39e508281ad49432300b3b7c8a95a0a63544f93f553fcfdeba38c82460888f4030ed1f67a1be666c12ee00658109c802042c58f645474fcee7d128277a4e35c1dd1504d33cb652ec23407cd3580eda0196dd97054eb5c2a817163d6997832d9abd422729b3e85a15941722baeb5ca8a42567a91c6a0b0cd64ac15431fde05071e90e0d30c12013d5803336cc2f433c16eaa5434e30b89ce7395c3c3cda29dde3be062281095f143d728486c71203b24fa6068e69aabf29d457ffadc6d682d51a4f08179d3240bc561ae7e2c005bb772a4d4c5ba6644986052fad554f042ab0074a8f");
+
+		let result = pv.verify(inp_hash, out_hash, proof.to_vec());
+		assert_ok!(result.clone());
+		assert!(result.unwrap());
+
+		// a wrong output must not verify with the prepared key
+		let wrong_out_hash = H256(sha2_256(&out[1..]));
+		let result = pv.verify(inp_hash, wrong_out_hash, proof.to_vec());
+		assert_eq!(result, Ok(false));
+	}
+
+	#[test]
+	fn test_compact_key_rejects_trailing_bytes() {
+		let v = Verifier::from_json_u8_slice(STEP_VK.as_bytes()).unwrap();
+		let mut compact_vk = v.to_compact_bytes().unwrap();
+		compact_vk.push(0);
+
+		assert!(PreparedVerifier::from_compact_bytes(compact_vk.as_slice()).is_err());
+	}
+
 	#[test]
 	fn test_decode_proof() {
 		let proof = hex!("1332c772a8f9a02f304b5472d3b6b75f1a494bd9b137fc663fd5b9b475992bc829ba08f7cfa745e340938e356b139224d0288b9511a5cec83235f969f61a94ed16a14579fa0adcc3bf8da36209f64547fd5ff4e1c7e8b5b151335b5b4a471de3115f83b696517ac68ae7620f7d3840e44aff4781c0a4d265a2905ef9bcaa04432a660197790e60d1135946ae0603ef69a5ecb45b6f8046167f902dc6d8a35cf716bce116484dfa4fcd5d8f4c2fda26d68754b56e68f1a877d95dc171accc34d71285068693fe3d8d28e66342c31292ceee5c6d87fcb8ad8c132363565f2aeff905726b2d35def5c9636dd5ec402d8d6f6c9a7be7977e7e5727da327ea5b079ad");
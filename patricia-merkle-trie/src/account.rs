@@ -0,0 +1,87 @@
+//! Ethereum accounts as stored in the state trie.
+
+use alloc::vec::Vec;
+use primitive_types::{H256, U256};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+/// Root of an empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT: H256 = H256([
+	0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+	0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Hash of empty contract code, `keccak256("")`.
+pub const EMPTY_CODE_HASH: H256 = H256([
+	0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+	0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+/// Ethereum account stored in the global state trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+	pub nonce: u64,
+	pub balance: U256,
+	pub storage_root: H256,
+	pub code_hash: H256,
+}
+
+impl Default for Account {
+	/// An account without code nor storage, as reported for accounts missing from the state.
+	fn default() -> Self {
+		Account {
+			nonce: 0,
+			balance: U256::zero(),
+			storage_root: EMPTY_ROOT,
+			code_hash: EMPTY_CODE_HASH,
+		}
+	}
+}
+
+impl Decodable for Account {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 4 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		let balance = rlp.at(1)?.data()?;
+		if balance.len() > 32 {
+			return Err(DecoderError::RlpIsTooBig);
+		}
+		if balance.first() == Some(&0) {
+			return Err(DecoderError::RlpInvalidIndirection);
+		}
+
+		Ok(Account {
+			nonce: rlp.val_at(0)?,
+			balance: U256::from_big_endian(balance),
+			storage_root: hash_at(rlp, 2)?,
+			code_hash: hash_at(rlp, 3)?,
+		})
+	}
+}
+
+impl Encodable for Account {
+	fn rlp_append(&self, stream: &mut RlpStream) {
+		stream.begin_list(4);
+		stream.append(&self.nonce);
+		stream.append(&trimmed_u256(&self.balance).as_slice());
+		stream.append(&self.storage_root.as_bytes());
+		stream.append(&self.code_hash.as_bytes());
+	}
+}
+
+/// Big endian encoding of `value` without leading zeros, as used for RLP integers.
+pub(crate) fn trimmed_u256(value: &U256) -> Vec<u8> {
+	let mut bytes = [0u8; 32];
+	value.to_big_endian(&mut bytes);
+	let start = bytes.iter().position(|b| *b != 0).unwrap_or(32);
+	bytes[start..].to_vec()
+}
+
+fn hash_at(rlp: &Rlp, index: usize) -> Result<H256, DecoderError> {
+	let data = rlp.at(index)?.data()?;
+	if data.len() != 32 {
+		return Err(DecoderError::Custom("Unexpected length."));
+	}
+	Ok(H256::from_slice(data))
+}
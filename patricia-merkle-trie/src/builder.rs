@@ -0,0 +1,228 @@
+//! In-memory construction of Ethereum tries and generation of
+//! [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) proofs, for tests and off-chain tooling.
+
+use crate::{
+	account::trimmed_u256,
+	keccak::{keccak_256, KeccakHasher},
+	proof::decode_storage_value,
+	Account, EIP1186Layout, MemoryDB, StorageProof, EMPTY_ROOT,
+};
+use primitive_types::{H160, H256, U256};
+use std::collections::BTreeMap;
+use trie_db::{Recorder, Trie, TrieDBBuilder, TrieDBMutBuilder, TrieMut};
+
+type Layout = EIP1186Layout<KeccakHasher>;
+
+/// Ethereum style Merkle Patricia trie held in memory. Keys are used as is, see [`StateBuilder`]
+/// for the secure state and storage tries.
+pub struct TrieBuilder {
+	db: MemoryDB<KeccakHasher>,
+	root: H256,
+}
+
+impl Default for TrieBuilder {
+	fn default() -> Self {
+		// The empty trie is the RLP encoding of an empty string.
+		TrieBuilder { db: MemoryDB::new(&[0x80]), root: EMPTY_ROOT }
+	}
+}
+
+impl TrieBuilder {
+	/// Creates an empty trie.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the root of the trie.
+	pub fn root(&self) -> H256 {
+		self.root
+	}
+
+	/// Sets the value of `key`.
+	pub fn insert(&mut self, key: &[u8], value: &[u8]) {
+		TrieDBMutBuilder::<Layout>::from_existing(&mut self.db, &mut self.root)
+			.build()
+			.insert(key, value)
+			.expect("all nodes of the trie are in memory; qed");
+	}
+
+	/// Removes `key` from the trie.
+	pub fn remove(&mut self, key: &[u8]) {
+		TrieDBMutBuilder::<Layout>::from_existing(&mut self.db, &mut self.root)
+			.build()
+			.remove(key)
+			.expect("all nodes of the trie are in memory; qed");
+	}
+
+	/// Returns the value of `key`.
+	pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+		TrieDBBuilder::<Layout>::new(&self.db, &self.root)
+			.build()
+			.get(key)
+			.expect("all nodes of the trie are in memory; qed")
+	}
+
+	/// Returns the nodes on the path to `key`, from the root down, as found in the proofs
+	/// returned by `eth_getProof`. The path of an absent key proves its absence.
+	pub fn prove(&self, key: &[u8]) -> Vec<Vec<u8>> {
+		self.record(&[key])
+	}
+
+	/// Returns the deduplicated set of nodes on the paths to all `keys`.
+	pub fn prove_keys<K: AsRef<[u8]>>(&self, keys: &[K]) -> StorageProof {
+		StorageProof::new(self.record(keys))
+	}
+
+	fn record<K: AsRef<[u8]>>(&self, keys: &[K]) -> Vec<Vec<u8>> {
+		// Absence from an empty trie is proven by its root alone.
+		if self.root == EMPTY_ROOT {
+			return Vec::new();
+		}
+
+		let mut recorder = Recorder::<Layout>::new();
+		{
+			let trie = TrieDBBuilder::<Layout>::new(&self.db, &self.root)
+				.with_recorder(&mut recorder)
+				.build();
+			for key in keys {
+				trie.get(key.as_ref()).expect("all nodes of the trie are in memory; qed");
+			}
+		}
+		recorder.drain().into_iter().map(|record| record.data).collect()
+	}
+}
+
+/// Proof of an account and some of its storage slots, following the response of `eth_getProof`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+	pub address: H160,
+	/// The proven account, or an empty account if it does not exist.
+	pub account: Account,
+	pub account_proof: Vec<Vec<u8>>,
+	pub storage_proof: Vec<StorageSlotProof>,
+}
+
+/// Proof of a storage slot, whose value is zero when the slot is not set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageSlotProof {
+	pub key: H256,
+	pub value: H256,
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Collects accounts and their storage to build an Ethereum world state.
+#[derive(Default)]
+pub struct StateBuilder {
+	accounts: BTreeMap<H160, (Account, BTreeMap<H256, H256>)>,
+}
+
+impl StateBuilder {
+	/// Creates an empty state.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets an account. Its storage root is replaced by the root of the storage set through
+	/// [`Self::insert_storage`] when building the state.
+	pub fn insert_account(&mut self, address: H160, account: Account) -> &mut Self {
+		self.accounts.entry(address).or_default().0 = account;
+		self
+	}
+
+	/// Sets a storage slot, creating an empty account if needed. As in Ethereum, zero values are
+	/// not stored.
+	pub fn insert_storage(&mut self, address: H160, slot: H256, value: H256) -> &mut Self {
+		let storage = &mut self.accounts.entry(address).or_default().1;
+		if value.is_zero() {
+			storage.remove(&slot);
+		} else {
+			storage.insert(slot, value);
+		}
+		self
+	}
+
+	/// Builds the storage tries of all accounts, then the state trie.
+	pub fn build(self) -> State {
+		let mut state = TrieBuilder::new();
+		let mut accounts = BTreeMap::new();
+
+		for (address, (mut account, slots)) in self.accounts {
+			let mut storage = TrieBuilder::new();
+			for (slot, value) in slots {
+				let value = rlp::encode(&trimmed_u256(&U256::from_big_endian(value.as_bytes())));
+				storage.insert(&keccak_256(slot.as_bytes()), &value);
+			}
+
+			account.storage_root = storage.root();
+			state.insert(&keccak_256(address.as_bytes()), &rlp::encode(&account));
+			accounts.insert(address, (account, storage));
+		}
+
+		State { state, accounts }
+	}
+}
+
+/// Ethereum world state from which account and storage proofs can be generated.
+pub struct State {
+	state: TrieBuilder,
+	accounts: BTreeMap<H160, (Account, TrieBuilder)>,
+}
+
+impl State {
+	/// Returns the state root.
+	pub fn root(&self) -> H256 {
+		self.state.root()
+	}
+
+	/// Returns the account at `address`, with its final storage root.
+	pub fn account(&self, address: &H160) -> Option<&Account> {
+		self.accounts.get(address).map(|(account, _)| account)
+	}
+
+	/// Proves the account at `address` and its storage `slots`, as `eth_getProof` does.
+	pub fn prove(&self, address: H160, slots: &[H256]) -> AccountProof {
+		let account_proof = self.state.prove(&keccak_256(address.as_bytes()));
+		let (account, storage) = match self.accounts.get(&address) {
+			Some((account, storage)) => (account.clone(), Some(storage)),
+			None => (Account::default(), None),
+		};
+
+		let storage_proof = slots
+			.iter()
+			.map(|slot| {
+				let key = keccak_256(slot.as_bytes());
+				let (value, proof) = match storage {
+					Some(storage) => (storage.get(&key), storage.prove(&key)),
+					None => (None, Vec::new()),
+				};
+				let value = value
+					.map(|value| decode_storage_value(&value).expect("encoded by `build`; qed"))
+					.unwrap_or_default();
+
+				StorageSlotProof { key: *slot, value, proof }
+			})
+			.collect();
+
+		AccountProof { address, account, account_proof, storage_proof }
+	}
+
+	/// Proves several storage `slots` of the account at `address` with a single deduplicated
+	/// set of nodes, to be verified with [`crate::verify_storage_proof`].
+	pub fn prove_storage(&self, address: &H160, slots: &[H256]) -> StorageProof {
+		let keys = slots.iter().map(|slot| keccak_256(slot.as_bytes())).collect::<Vec<_>>();
+		self.accounts
+			.get(address)
+			.map(|(_, storage)| storage.prove_keys(&keys))
+			.unwrap_or_else(StorageProof::empty)
+	}
+
+	/// Proves several accounts with a single deduplicated set of nodes, to be verified with
+	/// [`crate::verify_account_proof`].
+	pub fn prove_accounts(&self, addresses: &[H160]) -> StorageProof {
+		let keys = addresses
+			.iter()
+			.map(|address| keccak_256(address.as_bytes()))
+			.collect::<Vec<_>>();
+		self.state.prove_keys(&keys)
+	}
+}
@@ -1,5 +1,7 @@
 //! This crate exposes an implementation of [`trie_db::TrieLayout`] that allows [`trie_db::TrieDb`]
 //! be used for verifying Ethereum state proofs as per [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186)
+//! and receipt proofs against the receipts root of an execution block. With the `std` feature,
+//! tries can also be built in memory to generate such proofs.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -10,15 +12,25 @@ use hash_db::Hasher;
 use primitive_types::H256;
 use trie_db::TrieLayout;
 
+mod account;
+#[cfg(feature = "std")]
+mod builder;
 mod node_codec;
+mod proof;
+mod receipt;
 mod storage_proof;
 
 #[cfg(test)]
 mod tests;
 
+pub use account::{Account, EMPTY_CODE_HASH, EMPTY_ROOT};
+#[cfg(feature = "std")]
+pub use builder::{AccountProof, State, StateBuilder, StorageSlotProof, TrieBuilder};
+pub use proof::{verify_account_proof, verify_proof, verify_storage_proof, ProofError};
+pub use receipt::{receipt_key, Log, Receipt};
 pub use storage_proof::{MemoryDB, StorageProof};
 
-/// Trie layout for EIP-1186 state proof nodes.
+/// Trie layout for EIP-1186 state proof nodes, also used for transactions and receipts tries.
 #[derive(Default, Clone)]
 pub struct EIP1186Layout<H>(PhantomData<H>);
 
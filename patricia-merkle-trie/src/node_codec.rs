@@ -37,6 +37,36 @@ const HASHED_NULL_NODE: [u8; 32] = [
 	0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
 ];
 
+/// Plans the reference to a child node found at `offset`. Nodes whose encoding is shorter than
+/// 32 bytes are embedded in their parent instead of being referenced by hash, which is common in
+/// tries with short keys such as the transactions and receipts tries.
+fn child_handle_plan(item: &Rlp, offset: usize) -> Result<NodeHandlePlan, DecoderError> {
+	let i = item.payload_info()?;
+	if item.is_list() {
+		Ok(NodeHandlePlan::Inline(offset..(offset + i.header_len + i.value_len)))
+	} else {
+		Ok(NodeHandlePlan::Hash((offset + i.header_len)..(offset + i.header_len + i.value_len)))
+	}
+}
+
+/// Hex-prefix encodes a partial key given as right-aligned nibbles, flagging whether it belongs to
+/// a leaf or to an extension node.
+fn encode_partial(
+	mut partial: impl Iterator<Item = u8>,
+	number_nibble: usize,
+	is_leaf: bool,
+) -> Vec<u8> {
+	let flag = if is_leaf { 0x20 } else { 0x00 };
+	let mut encoded = Vec::with_capacity(number_nibble / 2 + 1);
+	if number_nibble % 2 == 1 {
+		encoded.push(flag | 0x10 | partial.next().unwrap_or_default());
+	} else {
+		encoded.push(flag);
+	}
+	encoded.extend(partial);
+	encoded
+}
+
 // NOTE: what we'd really like here is:
 // `impl<H: Hasher> NodeCodec<H> for RlpNodeCodec<H> where H::Out: Decodable`
 // but due to the current limitations of Rust const evaluation we can't
@@ -90,10 +120,7 @@ where
 						partial: slice,
 						child: {
 							let (item, offset) = r.at_with_offset(1)?;
-							let i = item.payload_info()?;
-							NodeHandlePlan::Hash(
-								(offset + i.header_len)..(offset + i.header_len + i.value_len),
-							)
+							child_handle_plan(&item, offset)?
 						},
 					}),
 				}
@@ -108,13 +135,10 @@ where
 				#[allow(clippy::needless_range_loop)]
 				for index in 0..16 {
 					let (item, offset) = r.at_with_offset(index)?;
-					let i = item.payload_info()?;
 					if item.is_empty() {
 						nodes[index] = None;
 					} else {
-						nodes[index] = Some(NodeHandlePlan::Hash(
-							(offset + i.header_len)..(offset + i.header_len + i.value_len),
-						));
+						nodes[index] = Some(child_handle_plan(&item, offset)?);
 					}
 				}
 
@@ -148,14 +172,9 @@ where
 		&[0x80]
 	}
 
-	fn leaf_node(
-		partial: impl Iterator<Item = u8>,
-		_number_nibble: usize,
-		value: Value,
-	) -> Vec<u8> {
+	fn leaf_node(partial: impl Iterator<Item = u8>, number_nibble: usize, value: Value) -> Vec<u8> {
 		let mut stream = RlpStream::new_list(2);
-		let partial = partial.collect::<Vec<_>>();
-		stream.append(&partial);
+		stream.append(&encode_partial(partial, number_nibble, true));
 		let value = match value {
 			Value::Node(bytes) => bytes,
 			Value::Inline(bytes) => bytes,
@@ -166,11 +185,11 @@ where
 
 	fn extension_node(
 		partial: impl Iterator<Item = u8>,
-		_number_nibble: usize,
+		number_nibble: usize,
 		child_ref: ChildReference<Self::HashOut>,
 	) -> Vec<u8> {
 		let mut stream = RlpStream::new_list(2);
-		stream.append(&partial.collect::<Vec<_>>());
+		stream.append(&encode_partial(partial, number_nibble, false));
 		match child_ref {
 			ChildReference::Hash(h) => stream.append(&h.as_ref()),
 			ChildReference::Inline(inline_data, len) => {
@@ -0,0 +1,93 @@
+//! Verification of several keys against a single set of proof nodes.
+
+use crate::{Account, EIP1186Layout, StorageProof, EMPTY_ROOT};
+use alloc::vec::Vec;
+use hash_db::Hasher;
+use primitive_types::{H160, H256};
+use rlp::{Decodable, Rlp};
+use trie_db::{Trie, TrieDBBuilder, TrieError};
+
+/// Errors that can occur while verifying a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+	/// A node needed to look up one of the keys is missing from the proof.
+	IncompleteProof,
+	/// A node or a proven value could not be decoded.
+	InvalidEncoding,
+}
+
+/// Looks up `keys` in the trie with the given `root`, using the nodes of `proof` only.
+///
+/// Returns, in the order of `keys`, the value stored under each key or `None` when the proof
+/// shows that the key is absent from the trie. Keys are used as is, so keys of secure tries such
+/// as the state and storage tries must already be hashed.
+pub fn verify_proof<H: Hasher<Out = H256>, K: AsRef<[u8]>>(
+	root: H256,
+	proof: StorageProof,
+	keys: &[K],
+) -> Result<Vec<Option<Vec<u8>>>, ProofError> {
+	if root == EMPTY_ROOT {
+		return Ok(keys.iter().map(|_| None).collect());
+	}
+
+	let db = proof.into_memory_db::<H>();
+	let trie = TrieDBBuilder::<EIP1186Layout<H>>::new(&db, &root).build();
+
+	keys.iter()
+		.map(|key| {
+			trie.get(key.as_ref()).map_err(|err| match *err {
+				TrieError::IncompleteDatabase(_) | TrieError::InvalidStateRoot(_) => {
+					ProofError::IncompleteProof
+				},
+				_ => ProofError::InvalidEncoding,
+			})
+		})
+		.collect()
+}
+
+/// Proves the accounts of `addresses` against a state root, `None` being returned for accounts
+/// that do not exist.
+pub fn verify_account_proof<H: Hasher<Out = H256>>(
+	state_root: H256,
+	proof: StorageProof,
+	addresses: &[H160],
+) -> Result<Vec<Option<Account>>, ProofError> {
+	let keys = addresses.iter().map(|address| H::hash(address.as_bytes())).collect::<Vec<_>>();
+
+	verify_proof::<H, _>(state_root, proof, &keys)?
+		.into_iter()
+		.map(|value| {
+			value
+				.map(|value| Account::decode(&Rlp::new(&value)))
+				.transpose()
+				.map_err(|_| ProofError::InvalidEncoding)
+		})
+		.collect()
+}
+
+/// Proves the values of storage `slots` against the storage root of an account, `None` being
+/// returned for slots that are not set, which the EVM reads as zero.
+pub fn verify_storage_proof<H: Hasher<Out = H256>>(
+	storage_root: H256,
+	proof: StorageProof,
+	slots: &[H256],
+) -> Result<Vec<Option<H256>>, ProofError> {
+	let keys = slots.iter().map(|slot| H::hash(slot.as_bytes())).collect::<Vec<_>>();
+
+	verify_proof::<H, _>(storage_root, proof, &keys)?
+		.into_iter()
+		.map(|value| value.map(|value| decode_storage_value(&value)).transpose())
+		.collect()
+}
+
+/// Storage values are RLP encoded without leading zeros.
+pub(crate) fn decode_storage_value(value: &[u8]) -> Result<H256, ProofError> {
+	let data = Rlp::new(value).data().map_err(|_| ProofError::InvalidEncoding)?;
+	if data.is_empty() || data.len() > 32 {
+		return Err(ProofError::InvalidEncoding);
+	}
+
+	let mut value = H256::zero();
+	value.0[32 - data.len()..].copy_from_slice(data);
+	Ok(value)
+}
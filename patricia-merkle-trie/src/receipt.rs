@@ -0,0 +1,78 @@
+//! Decoding of Ethereum transaction receipts as stored in the receipts trie of an execution block,
+//! covering legacy and [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718) typed receipts.
+
+use alloc::vec::Vec;
+use primitive_types::{H160, H256};
+use rlp::{Decodable, DecoderError, Rlp, RlpStream};
+
+/// Event log emitted during the execution of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+	pub address: H160,
+	pub topics: Vec<H256>,
+	pub data: Vec<u8>,
+}
+
+/// Transaction receipt. `tx_type` is `0` for legacy receipts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+	pub tx_type: u8,
+	pub success: bool,
+	pub cumulative_gas_used: u64,
+	pub logs: Vec<Log>,
+}
+
+impl Decodable for Log {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 3 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		let address = fixed_bytes::<20>(&rlp.at(0)?)?;
+		let topics = rlp
+			.at(1)?
+			.iter()
+			.map(|topic| fixed_bytes::<32>(&topic).map(H256))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Log { address: H160(address), topics, data: rlp.val_at(2)? })
+	}
+}
+
+impl Receipt {
+	/// Decodes a receipt from its encoding in the receipts trie, which is either the RLP list of a
+	/// legacy receipt or the transaction type followed by the RLP list of a typed receipt.
+	pub fn decode(bytes: &[u8]) -> Result<Self, DecoderError> {
+		let (tx_type, payload) = match bytes.first() {
+			None => return Err(DecoderError::RlpIsTooShort),
+			Some(&first) if first >= 0xc0 => (0, bytes),
+			Some(&first) if first <= 0x7f => (first, &bytes[1..]),
+			Some(_) => return Err(DecoderError::Custom("Unknown receipt encoding.")),
+		};
+
+		let rlp = Rlp::new(payload);
+		if rlp.item_count()? != 4 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		// Pre-Byzantium receipts carry an intermediate state root instead of a status.
+		let success = match rlp.at(0)?.data()? {
+			[] => false,
+			[1] => true,
+			_ => return Err(DecoderError::Custom("Receipt status is not supported.")),
+		};
+
+		Ok(Receipt { tx_type, success, cumulative_gas_used: rlp.val_at(1)?, logs: rlp.list_at(3)? })
+	}
+}
+
+/// Key of the receipt of the transaction at `index` in the receipts trie.
+pub fn receipt_key(index: u64) -> Vec<u8> {
+	let mut stream = RlpStream::new();
+	stream.append(&index);
+	stream.out().to_vec()
+}
+
+fn fixed_bytes<const N: usize>(rlp: &Rlp) -> Result<[u8; N], DecoderError> {
+	rlp.data()?.try_into().map_err(|_| DecoderError::Custom("Unexpected length."))
+}
@@ -1,10 +1,11 @@
 use crate::{
 	keccak::{keccak_256, KeccakHasher},
-	EIP1186Layout, StorageProof,
+	receipt_key, verify_account_proof, verify_proof, verify_storage_proof, EIP1186Layout, Log,
+	ProofError, Receipt, StateBuilder, StorageProof, TrieBuilder, EMPTY_CODE_HASH, EMPTY_ROOT,
 };
 use hex_literal::hex;
-use primitive_types::{H256, U256};
-use rlp::{Decodable, Rlp};
+use primitive_types::{H160, H256, U256};
+use rlp::{Decodable, Rlp, RlpStream};
 use rlp_derive::RlpDecodable;
 use trie_db::{Trie, TrieDBBuilder};
 
@@ -53,3 +54,220 @@ fn test_can_verify_eip_1186_proofs() {
 	);
 	assert_eq!(account.nonce, 0x10);
 }
+
+fn encode_leaf(partial: &[u8], value: &[u8]) -> Vec<u8> {
+	let mut stream = RlpStream::new_list(2);
+	stream.append(&partial);
+	stream.append(&value);
+	stream.out().to_vec()
+}
+
+fn encode_log(log: &Log) -> Vec<u8> {
+	let mut stream = RlpStream::new_list(3);
+	stream.append(&log.address.as_bytes());
+	stream.begin_list(log.topics.len());
+	for topic in &log.topics {
+		stream.append(&topic.as_bytes());
+	}
+	stream.append(&log.data);
+	stream.out().to_vec()
+}
+
+#[test]
+fn test_can_verify_typed_receipt_proofs() {
+	let log = Log {
+		address: hex!("681257BED628425a28B469114Dc21A7c30205cFD").into(),
+		topics: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+		data: b"Hello, World!".to_vec(),
+	};
+
+	let mut stream = RlpStream::new_list(4);
+	stream.append(&1u8);
+	stream.append(&21_000u64);
+	stream.append(&vec![0u8; 256]);
+	stream.begin_list(1);
+	stream.append_raw(&encode_log(&log), 1);
+	let receipt = [&[2u8][..], &stream.out()].concat();
+
+	// Single leaf trie holding the receipt of the first transaction, whose key `rlp(0) = 0x80`
+	// is stored as an even leaf partial.
+	let leaf = encode_leaf(&[0x20, 0x80], &receipt);
+	let root = H256(keccak_256(&leaf));
+
+	let db = StorageProof::new(vec![leaf]).into_memory_db::<KeccakHasher>();
+	let trie = TrieDBBuilder::<EIP1186Layout<KeccakHasher>>::new(&db, &root).build();
+	let result = trie.get(&receipt_key(0)).unwrap().unwrap();
+	assert!(trie.get(&receipt_key(1)).unwrap().is_none());
+
+	let receipt = Receipt::decode(&result).unwrap();
+	assert_eq!(receipt.tx_type, 2);
+	assert!(receipt.success);
+	assert_eq!(receipt.cumulative_gas_used, 21_000);
+	assert_eq!(receipt.logs, vec![log]);
+}
+
+#[test]
+fn test_can_verify_proofs_with_inline_nodes() {
+	// Keys `0x01` and `0x80` diverge on their first nibble, leaving leaves short enough to be
+	// embedded in the branch node.
+	let first = encode_leaf(&[0x31], b"a");
+	let second = encode_leaf(&[0x30], b"b");
+	let mut stream = RlpStream::new_list(17);
+	for index in 0..16 {
+		match index {
+			0 => stream.append_raw(&first, 1),
+			8 => stream.append_raw(&second, 1),
+			_ => stream.append_empty_data(),
+		};
+	}
+	stream.append_empty_data();
+	let branch = stream.out().to_vec();
+	let root = H256(keccak_256(&branch));
+
+	let db = StorageProof::new(vec![branch]).into_memory_db::<KeccakHasher>();
+	let trie = TrieDBBuilder::<EIP1186Layout<KeccakHasher>>::new(&db, &root).build();
+	assert_eq!(trie.get(&[0x01]).unwrap(), Some(b"a".to_vec()));
+	assert_eq!(trie.get(&[0x80]).unwrap(), Some(b"b".to_vec()));
+	assert_eq!(trie.get(&[0x02]).unwrap(), None);
+}
+
+#[test]
+fn test_receipt_decoding_rejects_malformed_receipts() {
+	assert!(Receipt::decode(&[]).is_err());
+	assert!(Receipt::decode(&[0x02, 0xc0]).is_err());
+	assert!(Receipt::decode(&[0x80]).is_err());
+}
+
+#[test]
+fn test_builder_computes_ethereum_roots() {
+	let mut trie = TrieBuilder::new();
+	assert_eq!(trie.root(), EMPTY_ROOT);
+
+	trie.insert(b"do", b"verb");
+	trie.insert(b"dog", b"puppy");
+	trie.insert(b"doge", b"coin");
+	trie.insert(b"horse", b"stallion");
+	assert_eq!(
+		trie.root(),
+		H256(hex!("5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84"))
+	);
+
+	let proof = trie.prove(b"dog");
+	let db = StorageProof::new(proof).into_memory_db::<KeccakHasher>();
+	let root = trie.root();
+	let trie = TrieDBBuilder::<EIP1186Layout<KeccakHasher>>::new(&db, &root).build();
+	assert_eq!(trie.get(b"dog").unwrap(), Some(b"puppy".to_vec()));
+}
+
+#[test]
+fn test_builder_generates_eip_1186_proofs() {
+	let contract = H160::repeat_byte(1);
+	let user = H160::repeat_byte(2);
+	let mut builder = StateBuilder::new();
+	builder
+		.insert_account(
+			user,
+			crate::Account { nonce: 7, balance: U256::exp10(18), ..Default::default() },
+		)
+		.insert_storage(contract, H256::repeat_byte(1), H256::from_low_u64_be(42))
+		.insert_storage(contract, H256::repeat_byte(2), H256::repeat_byte(0xff))
+		.insert_storage(contract, H256::repeat_byte(3), H256::zero());
+	let state = builder.build();
+
+	let proof = state.prove(contract, &[H256::repeat_byte(1), H256::repeat_byte(3)]);
+	assert_eq!(proof.account.storage_root, state.account(&contract).unwrap().storage_root);
+	assert_eq!(proof.account.code_hash, EMPTY_CODE_HASH);
+	assert_eq!(proof.storage_proof[0].value, H256::from_low_u64_be(42));
+	assert_eq!(proof.storage_proof[1].value, H256::zero());
+
+	let accounts = verify_account_proof::<KeccakHasher>(
+		state.root(),
+		StorageProof::new(proof.account_proof),
+		&[contract],
+	)
+	.unwrap();
+	assert_eq!(accounts, vec![Some(proof.account.clone())]);
+
+	for slot in proof.storage_proof {
+		let values = verify_storage_proof::<KeccakHasher>(
+			proof.account.storage_root,
+			StorageProof::new(slot.proof),
+			&[slot.key],
+		)
+		.unwrap();
+		assert_eq!(values[0].unwrap_or_default(), slot.value);
+	}
+
+	let proof = state.prove(user, &[H256::repeat_byte(1)]);
+	assert_eq!(proof.account.nonce, 7);
+	assert_eq!(proof.account.storage_root, EMPTY_ROOT);
+	assert!(proof.storage_proof[0].proof.is_empty());
+}
+
+#[test]
+fn test_can_verify_multiple_keys_with_one_proof() {
+	let contract = H160::repeat_byte(1);
+	let mut builder = StateBuilder::new();
+	for index in 1..=64u64 {
+		builder.insert_storage(
+			contract,
+			H256::from_low_u64_be(index),
+			H256::from_low_u64_be(index),
+		);
+	}
+	for index in 1..=16u8 {
+		builder.insert_account(H160::repeat_byte(index), Default::default());
+	}
+	let state = builder.build();
+	let storage_root = state.account(&contract).unwrap().storage_root;
+
+	let slots = [H256::from_low_u64_be(3), H256::from_low_u64_be(100), H256::from_low_u64_be(64)];
+	let proof = state.prove_storage(&contract, &slots);
+	let values = verify_storage_proof::<KeccakHasher>(storage_root, proof.clone(), &slots).unwrap();
+	assert_eq!(values, vec![Some(H256::from_low_u64_be(3)), None, Some(H256::from_low_u64_be(64))]);
+
+	// Removing any node breaks the path to at least one of the keys.
+	let mut nodes = proof.into_nodes().into_iter().collect::<Vec<_>>();
+	nodes.pop();
+	assert_eq!(
+		verify_storage_proof::<KeccakHasher>(storage_root, StorageProof::new(nodes), &slots),
+		Err(ProofError::IncompleteProof)
+	);
+
+	let addresses = [H160::repeat_byte(3), H160::repeat_byte(0xaa), contract];
+	let proof = state.prove_accounts(&addresses);
+	let accounts = verify_account_proof::<KeccakHasher>(state.root(), proof, &addresses).unwrap();
+	assert_eq!(accounts[0], Some(Default::default()));
+	assert_eq!(accounts[1], None);
+	assert_eq!(accounts[2].as_ref().map(|account| account.storage_root), Some(storage_root));
+
+	// Every key is absent from an empty trie, which needs no proof.
+	assert_eq!(
+		verify_proof::<KeccakHasher, _>(EMPTY_ROOT, StorageProof::empty(), &[b"key"]).unwrap(),
+		vec![None]
+	);
+}
+
+#[test]
+fn test_account_decoding_matches_eip_1186_proofs() {
+	let account = crate::Account {
+		nonce: 0x10,
+		balance: U256::from(&hex!("4ef05b2fe9d8c8")[..]),
+		storage_root: EMPTY_ROOT,
+		code_hash: EMPTY_CODE_HASH,
+	};
+	let encoded = rlp::encode(&account);
+	assert_eq!(
+		encoded.to_vec(),
+		hex!("f84b10874ef05b2fe9d8c8a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").to_vec()
+	);
+	assert_eq!(crate::Account::decode(&Rlp::new(&encoded)).unwrap(), account);
+
+	// Balances must not have leading zeros.
+	let mut stream = RlpStream::new_list(4);
+	stream.append(&0u64);
+	stream.append(&vec![0u8, 1]);
+	stream.append(&EMPTY_ROOT.as_bytes());
+	stream.append(&EMPTY_CODE_HASH.as_bytes());
+	assert!(crate::Account::decode(&Rlp::new(&stream.out())).is_err());
+}
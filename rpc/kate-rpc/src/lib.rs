@@ -3,7 +3,7 @@ use avail_core::{
 	data_proof::ProofResponse, header::HeaderExtension, traits::ExtendedHeader, AppId,
 	OpaqueExtrinsic,
 };
-use da_control::kate::{GDataProof, GRow};
+use da_control::kate::{completeness::app_rows, AppDataProof, GDataProof, GRow};
 use da_runtime::apis::{DataAvailApi, KateApi as RTKateApi};
 use kate::com::Cell;
 
@@ -48,6 +48,13 @@ where
 		at: Option<HashOf<Block>>,
 	) -> RpcResult<Vec<Option<GRow>>>;
 
+	#[method(name = "kate_queryAppDataProof")]
+	async fn query_app_data_proof(
+		&self,
+		app_id: AppId,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<AppDataProof>;
+
 	#[method(name = "kate_queryProof")]
 	async fn query_proof(
 		&self,
@@ -226,6 +233,47 @@ where
 		Ok(app_data)
 	}
 
+	async fn query_app_data_proof(
+		&self,
+		app_id: AppId,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<AppDataProof> {
+		let (api, at, number, block_len, extrinsics, header) = self.scope(at)?;
+		let cells = match header.extension() {
+			HeaderExtension::V3(ext) => {
+				if ext.commitment.commitment.is_empty() {
+					return Err(internal_err!("Requested block {at} has empty commitments"));
+				}
+				// The proof contains every cell of the rows covering the application.
+				let cols = u32::from(ext.commitment.cols);
+				let rows = ext
+					.app_lookup
+					.range_of(app_id)
+					.and_then(|range| app_rows(&range, cols))
+					.map_or(0, |rows| rows.count());
+				rows.saturating_mul(cols as usize)
+			},
+		};
+		if cells > self.max_cells_size {
+			return Err(
+				internal_err!(
+					"Cannot prove ({}) more than {} amount of cells per request. Either increase the max cells size (--kate-max-cells-size) or query the rows of the application with kate_queryProof.",
+					cells,
+					self.max_cells_size
+				)
+			);
+		}
+
+		let execution_start = Instant::now();
+		let proof = api
+			.app_data_proof(at, number, extrinsics, block_len, app_id)
+			.map_err(|kate_err| internal_err!("KateApi::app_data_proof failed: {kate_err:?}"))?
+			.map_err(|api_err| internal_err!("Failed API: {api_err:?}"))?;
+		KateRpcMetrics::observe_query_app_data_proof_execution_time(execution_start.elapsed());
+
+		Ok(proof)
+	}
+
 	async fn query_proof(
 		&self,
 		cells: Cells,
@@ -4,6 +4,7 @@ use avail_core::{header::HeaderExtension, traits::ExtendedHeader, AppId, OpaqueE
 use da_runtime::apis::DataAvailApi;
 
 use crate::RTKateApi;
+use da_control::kate::AppDataProof;
 use da_control::kate::GDataProof;
 use da_control::kate::GRow;
 use frame_system::limits::BlockLength;
@@ -35,6 +36,13 @@ where
 		at: Option<HashOf<Block>>,
 	) -> RpcResult<(Vec<Option<GRow>>, u128)>;
 
+	#[method(name = "kate_queryAppDataProofMetrics")]
+	async fn query_app_data_proof_metrics(
+		&self,
+		app_id: AppId,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<(AppDataProof, u128)>;
+
 	#[method(name = "kate_queryProofMetrics")]
 	async fn query_proof_metrics(
 		&self,
@@ -89,6 +97,18 @@ where
 		result.map(|r| (r, elapsed.as_micros()))
 	}
 
+	async fn query_app_data_proof_metrics(
+		&self,
+		app_id: AppId,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<(AppDataProof, u128)> {
+		let start = std::time::Instant::now();
+		let result = self.query_app_data_proof(app_id, at).await;
+		let elapsed = start.elapsed();
+
+		result.map(|r| (r, elapsed.as_micros()))
+	}
+
 	async fn query_proof_metrics(
 		&self,
 		cells: Cells,
@@ -0,0 +1,247 @@
+use da_runtime::apis::VectorApi as RTVectorApi;
+use pallet_vector::{Configuration, MessageStatusEnum};
+
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::ErrorObject,
+};
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{H256, U256};
+use sp_runtime::traits::Block as BlockT;
+use std::{marker::PhantomData, sync::Arc};
+
+pub type HashOf<Block> = <Block as BlockT>::Hash;
+
+/// Read-only access to the state of the Vector bridge.
+#[rpc(client, server)]
+pub trait VectorApi<Block>
+where
+	Block: BlockT,
+{
+	#[method(name = "vector_head")]
+	async fn head(&self, at: Option<HashOf<Block>>) -> RpcResult<u64>;
+
+	#[method(name = "vector_header")]
+	async fn header(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<H256>;
+
+	#[method(name = "vector_syncCommitteePoseidon")]
+	async fn sync_committee_poseidon(
+		&self,
+		period: u64,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<U256>;
+
+	#[method(name = "vector_executionStateRoot")]
+	async fn execution_state_root(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<H256>;
+
+	#[method(name = "vector_timestamp")]
+	async fn timestamp(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<u64>;
+
+	#[method(name = "vector_messageStatus")]
+	async fn message_status(
+		&self,
+		message_root: H256,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<MessageStatusEnum>;
+
+	#[method(name = "vector_messageStatusById")]
+	async fn message_status_by_id(
+		&self,
+		origin_domain: u32,
+		id: u64,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<MessageStatusEnum>;
+
+	#[method(name = "vector_messageSlotKey")]
+	async fn message_slot_key(&self, id: u64, at: Option<HashOf<Block>>) -> RpcResult<H256>;
+
+	#[method(name = "vector_nextOutboundNonce")]
+	async fn next_outbound_nonce(
+		&self,
+		origin_domain: u32,
+		destination_domain: u32,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<u64>;
+
+	#[method(name = "vector_nextInboundNonce")]
+	async fn next_inbound_nonce(
+		&self,
+		origin_domain: u32,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<Option<u64>>;
+
+	#[method(name = "vector_frozenDomains")]
+	async fn frozen_domains(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<u32>>;
+
+	#[method(name = "vector_whitelistedDomains")]
+	async fn whitelisted_domains(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<u32>>;
+
+	#[method(name = "vector_broadcasters")]
+	async fn broadcasters(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<(u32, H256)>>;
+
+	#[method(name = "vector_configuration")]
+	async fn configuration(&self, at: Option<HashOf<Block>>) -> RpcResult<Configuration>;
+
+	#[method(name = "vector_functionIds")]
+	async fn function_ids(&self, at: Option<HashOf<Block>>) -> RpcResult<Option<(H256, H256)>>;
+}
+
+pub struct Vector<Client, Block: BlockT> {
+	client: Arc<Client>,
+	_block: PhantomData<Block>,
+}
+
+impl<Client, Block: BlockT> Vector<Client, Block> {
+	pub fn new(client: Arc<Client>) -> Self {
+		Self {
+			client,
+			_block: PhantomData,
+		}
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The runtime call failed.
+	VectorRPCError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::VectorRPCError => 1,
+		}
+	}
+}
+
+macro_rules! internal_err {
+	($($arg:tt)*) => {{
+		ErrorObject::owned(
+			Error::VectorRPCError.into(),
+			format!($($arg)*),
+			None::<()>
+		)
+	}}
+}
+
+type Api<'a, C, B> = ApiRef<'a, <C as ProvideRuntimeApi<B>>::Api>;
+
+impl<Client, Block> Vector<Client, Block>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+{
+	fn scope(&self, at: Option<Block::Hash>) -> (Api<'_, Client, Block>, Block::Hash) {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		(self.client.runtime_api(), at)
+	}
+}
+
+/// Calls `$method` of the runtime `VectorApi` at block `$at` (or the best block).
+macro_rules! runtime_call {
+	($self:ident, $at:ident, $method:ident $(, $arg:expr)*) => {{
+		let (api, at) = $self.scope($at);
+		api.$method(at $(, $arg)*).map_err(|e| {
+			internal_err!("Failed to call {} at {at:?}: {e:?}", stringify!($method))
+		})
+	}};
+}
+
+#[async_trait]
+impl<Client, Block> VectorApiServer<Block> for Vector<Client, Block>
+where
+	Block: BlockT,
+	Client: Send + Sync + 'static,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+	Client::Api: RTVectorApi<Block>,
+{
+	async fn head(&self, at: Option<HashOf<Block>>) -> RpcResult<u64> {
+		runtime_call!(self, at, head)
+	}
+
+	async fn header(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<H256> {
+		runtime_call!(self, at, headers, slot)
+	}
+
+	async fn sync_committee_poseidon(
+		&self,
+		period: u64,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<U256> {
+		runtime_call!(self, at, sync_committee_poseidons, period)
+	}
+
+	async fn execution_state_root(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<H256> {
+		runtime_call!(self, at, execution_state_root, slot)
+	}
+
+	async fn timestamp(&self, slot: u64, at: Option<HashOf<Block>>) -> RpcResult<u64> {
+		runtime_call!(self, at, timestamp, slot)
+	}
+
+	async fn message_status(
+		&self,
+		message_root: H256,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<MessageStatusEnum> {
+		runtime_call!(self, at, message_status, message_root)
+	}
+
+	async fn message_status_by_id(
+		&self,
+		origin_domain: u32,
+		id: u64,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<MessageStatusEnum> {
+		runtime_call!(self, at, message_status_by_id, origin_domain, id)
+	}
+
+	async fn message_slot_key(&self, id: u64, at: Option<HashOf<Block>>) -> RpcResult<H256> {
+		runtime_call!(self, at, message_slot_key, id)
+	}
+
+	async fn next_outbound_nonce(
+		&self,
+		origin_domain: u32,
+		destination_domain: u32,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<u64> {
+		runtime_call!(
+			self,
+			at,
+			next_outbound_nonce,
+			origin_domain,
+			destination_domain
+		)
+	}
+
+	async fn next_inbound_nonce(
+		&self,
+		origin_domain: u32,
+		at: Option<HashOf<Block>>,
+	) -> RpcResult<Option<u64>> {
+		runtime_call!(self, at, next_inbound_nonce, origin_domain)
+	}
+
+	async fn frozen_domains(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<u32>> {
+		runtime_call!(self, at, frozen_domains)
+	}
+
+	async fn whitelisted_domains(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<u32>> {
+		runtime_call!(self, at, whitelisted_domains)
+	}
+
+	async fn broadcasters(&self, at: Option<HashOf<Block>>) -> RpcResult<Vec<(u32, H256)>> {
+		runtime_call!(self, at, broadcasters)
+	}
+
+	async fn configuration(&self, at: Option<HashOf<Block>>) -> RpcResult<Configuration> {
+		runtime_call!(self, at, configuration)
+	}
+
+	async fn function_ids(&self, at: Option<HashOf<Block>>) -> RpcResult<Option<(H256, H256)>> {
+		runtime_call!(self, at, function_ids)
+	}
+}
@@ -11,7 +11,7 @@ use avail_core::{
 	header::HeaderExtension,
 	AppId, OpaqueExtrinsic,
 };
-use da_control::kate::{Error as RTKateError, GDataProof, GRow, RTKate};
+use da_control::kate::{AppDataProof, Error as RTKateError, GDataProof, GRow, RTKate};
 
 use frame_system::{
 	header_builder::da::HeaderExtensionBuilder, limits::BlockLength, HeaderExtensionBuilder as _,
@@ -23,6 +23,7 @@ use frame_support::{
 	weights::Weight,
 };
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
+use pallet_vector::{Configuration as VectorConfiguration, MessageStatusEnum};
 use sp_api::{decl_runtime_apis, impl_runtime_apis};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
@@ -58,17 +59,45 @@ decl_runtime_apis! {
 		fn check_if_extrinsic_is_post_inherent(uxt: &<Block as BlockT>::Extrinsic) -> bool;
 	}
 
+	#[api_version(3)]
 	pub trait VectorApi {
 		fn sync_committee_poseidons(slot: u64) -> U256;
 		fn head() -> u64;
 		fn headers(slot: u64) -> H256;
+		#[api_version(2)]
+		fn message_status(message_root: H256) -> MessageStatusEnum;
+		#[api_version(2)]
+		fn message_status_by_id(origin_domain: u32, id: u64) -> MessageStatusEnum;
+		#[api_version(2)]
+		fn frozen_domains() -> Vec<u32>;
+		#[api_version(2)]
+		fn whitelisted_domains() -> Vec<u32>;
+		#[api_version(2)]
+		fn broadcasters() -> Vec<(u32, H256)>;
+		#[api_version(2)]
+		fn configuration() -> VectorConfiguration;
+		#[api_version(2)]
+		fn function_ids() -> Option<(H256, H256)>;
+		#[api_version(2)]
+		fn execution_state_root(slot: u64) -> H256;
+		#[api_version(2)]
+		fn timestamp(slot: u64) -> u64;
+		#[api_version(2)]
+		fn message_slot_key(message_id: u64) -> H256;
+		#[api_version(3)]
+		fn next_outbound_nonce(origin_domain: u32, destination_domain: u32) -> u64;
+		#[api_version(3)]
+		fn next_inbound_nonce(origin_domain: u32) -> Option<u64>;
 	}
 
+	#[api_version(2)]
 	pub trait KateApi {
 		fn data_proof(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, tx_idx: u32) -> Option<ProofResponse>;
 		fn rows(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, block_len: BlockLength, rows: Vec<u32>) -> Result<Vec<GRow>, RTKateError >;
 		fn app_data(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, block_len: BlockLength, id: AppId) -> Result<Vec<Option<GRow>>, RTKateError>;
 		fn proof(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, block_len: BlockLength, cells: Vec<(u32,u32)> ) -> Result<Vec<GDataProof>, RTKateError>;
+		#[api_version(2)]
+		fn app_data_proof(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, block_len: BlockLength, id: AppId) -> Result<AppDataProof, RTKateError>;
 	}
 }
 
@@ -400,8 +429,57 @@ impl_runtime_apis! {
 		fn headers(slot: u64) -> H256 {
 			pallet_vector::Pallet::<Runtime>::headers(slot)
 		}
+
+		fn message_status(message_root: H256) -> MessageStatusEnum {
+			pallet_vector::MessageStatus::<Runtime>::get(message_root)
+		}
+
+		fn message_status_by_id(origin_domain: u32, id: u64) -> MessageStatusEnum {
+			pallet_vector::Pallet::<Runtime>::message_status_by_id(origin_domain, id)
+		}
+
+		fn frozen_domains() -> Vec<u32> {
+			pallet_vector::Pallet::<Runtime>::frozen_domains()
+		}
+
+		fn whitelisted_domains() -> Vec<u32> {
+			pallet_vector::WhitelistedDomains::<Runtime>::get().into_inner()
+		}
+
+		fn broadcasters() -> Vec<(u32, H256)> {
+			pallet_vector::Pallet::<Runtime>::broadcasters()
+		}
+
+		fn configuration() -> VectorConfiguration {
+			pallet_vector::ConfigurationStorage::<Runtime>::get()
+		}
+
+		fn function_ids() -> Option<(H256, H256)> {
+			pallet_vector::Pallet::<Runtime>::function_ids()
+		}
+
+		fn execution_state_root(slot: u64) -> H256 {
+			pallet_vector::ExecutionStateRoots::<Runtime>::get(slot)
+		}
+
+		fn timestamp(slot: u64) -> u64 {
+			pallet_vector::Timestamps::<Runtime>::get(slot)
+		}
+
+		fn message_slot_key(message_id: u64) -> H256 {
+			pallet_vector::Pallet::<Runtime>::message_slot_key(message_id)
+		}
+
+		fn next_outbound_nonce(origin_domain: u32, destination_domain: u32) -> u64 {
+			pallet_vector::Pallet::<Runtime>::next_outbound_nonce(origin_domain, destination_domain)
+		}
+
+		fn next_inbound_nonce(origin_domain: u32) -> Option<u64> {
+			pallet_vector::Pallet::<Runtime>::next_inbound_nonce(origin_domain)
+		}
 	}
 
+	#[api_version(2)]
 	impl crate::apis::KateApi<Block> for Runtime {
 		fn data_proof(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, tx_idx: u32) -> Option<ProofResponse> {
 
@@ -453,6 +531,13 @@ impl_runtime_apis! {
 			log::trace!(target: LOG_TARGET, "KateApi::proof: data_proofs={data_proofs:#?}");
 			Ok(data_proofs)
 		}
+
+		fn app_data_proof(block_number: u32, extrinsics: Vec<OpaqueExtrinsic>, block_len: BlockLength, id: AppId) -> Result<AppDataProof, RTKateError> {
+			let app_exts = build_tx_data_from_opaque::<RTExtractor, RTExtrinsic, _, _>(block_number, extrinsics).to_app_extrinsics();
+			let proof = RTKate::<Runtime>::app_data_proof(app_exts, block_len, id)?;
+			log::trace!(target: LOG_TARGET, "KateApi::app_data_proof: proof={proof:#?}");
+			Ok(proof)
+		}
 	}
 
 	impl avail_base::PostInherentsProvider<Block> for Runtime {
@@ -63,6 +63,12 @@ fn filter_vector_call(
 			message,
 			to,
 			domain,
+		}
+		| VectorCall::send_message_with_fee {
+			message,
+			to,
+			domain,
+			..
 		} if !message.is_empty() => {
 			metrics.bridge_leaves += 1;
 
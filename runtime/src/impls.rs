@@ -52,18 +52,39 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MandateMinDelay: BlockNumber = prod_or_fast!(DAYS, 10 * MINUTES);
+	pub const MandateMaxCallLength: u32 = 16 * 1024;
+	pub const MandateMaxScheduledPerBlock: u32 = 8;
+	pub const MandateMaxBatchCalls: u32 = 32;
+}
+
 impl pallet_mandate::Config for Runtime {
 	type ApprovedOrigin = EitherOfDiverse<
 		EnsureRoot<AccountId>,
 		pallet_collective::EnsureProportionMoreThan<AccountId, TechnicalCollective, 1, 2>,
 	>;
+	// Not Root: `ApprovedOrigin` dispatches as Root, so accepting it here would let a simple
+	// majority reach `mandate_as` through `mandate` or `mandate_batch`.
+	type DispatchAsOrigin =
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>;
+	type MaxBatchCalls = MandateMaxBatchCalls;
+	type MaxCallLength = MandateMaxCallLength;
+	type MaxScheduledPerBlock = MandateMaxScheduledPerBlock;
+	type MinDelay = MandateMinDelay;
 	type RuntimeCall = RuntimeCall;
 	type RuntimeEvent = RuntimeEvent;
+	type VetoOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 3>,
+	>;
 	type WeightInfo = weights::pallet_mandate::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub const BridgePalletId: PalletId = PalletId(*b"avl/brdg");
+	pub const RelayerFeeTimeout: BlockNumber = 7 * DAYS;
+	pub const VectorGovernanceDelay: BlockNumber = 2 * DAYS;
 }
 
 impl pallet_vector::Config for Runtime {
@@ -73,6 +94,13 @@ impl pallet_vector::Config for Runtime {
 	type TimeProvider = pallet_timestamp::Pallet<Runtime>;
 	type Currency = Balances;
 	type MessageMappingStorageIndex = ConstU64<1>;
+	type DeliveryMappingStorageIndex = ConstU64<0>;
+	type RelayerFeeTimeout = RelayerFeeTimeout;
+	type GovernanceDelay = VectorGovernanceDelay;
+	type CancelOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionMoreThan<AccountId, TechnicalCollective, 1, 2>,
+	>;
 	type PalletId = BridgePalletId;
 	type AvailDomain = ConstU32<1>;
 }
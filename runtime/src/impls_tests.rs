@@ -616,3 +616,16 @@ mod tests {
 		);
 	}
 }
+
+#[cfg(test)]
+mod mandate_tests {
+	use crate::{Runtime, RuntimeOrigin};
+	use frame_support::traits::EnsureOrigin;
+
+	#[test]
+	fn root_can_not_dispatch_as_an_account() {
+		// A mandate dispatches as Root, so Root must not pass the `mandate_as` origin check.
+		type DispatchAsOrigin = <Runtime as pallet_mandate::Config>::DispatchAsOrigin;
+		assert!(DispatchAsOrigin::try_origin(RuntimeOrigin::root()).is_err());
+	}
+}
@@ -72,8 +72,16 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Migrations executed on runtime upgrade.
+pub type Migrations = (
+	pallet_vector::migrations::v1::MigrateToCompactVerificationKeys<Runtime>,
+	pallet_vector::migrations::v2::MigrateToVerificationKeysByFunctionId<Runtime>,
+	pallet_vector::migrations::v3::RegisterFunctionProofSystems<Runtime>,
+);
+
 /// ID type for named reserves.
 pub type ReserveIdentifier = [u8; 8];
 
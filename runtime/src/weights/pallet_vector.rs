@@ -51,29 +51,28 @@ pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
 	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::OutboundNonces` (r:1 w:1)
+	/// Proof: `Vector::OutboundNonces` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
 	/// The range of component `l` is `[0, 102400]`.
 	fn send_message_arbitrary_message(_l: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `357`
-		//  Estimated: `41487`
-		// Minimum execution time: 19_088_000 picoseconds.
+		// Measured before outbound nonces, with their storage added by hand: an estimate until regenerated.
 		Weight::from_parts(20_213_539, 0)
 			.saturating_add(Weight::from_parts(0, 41487))
-			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
 	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
 	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::OutboundNonces` (r:1 w:1)
+	/// Proof: `Vector::OutboundNonces` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
 	fn send_message_fungible_token() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `586`
-		//  Estimated: `41487`
-		// Minimum execution time: 87_670_000 picoseconds.
+		// Measured before outbound nonces, with their storage added by hand: an estimate until regenerated.
 		Weight::from_parts(88_934_000, 0)
 			.saturating_add(Weight::from_parts(0, 41487))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 	/// Storage: `Vector::SyncCommitteePoseidons` (r:0 w:1)
 	/// Proof: `Vector::SyncCommitteePoseidons` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
@@ -86,17 +85,18 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 0))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
-	/// Storage: `Vector::Broadcasters` (r:1 w:1)
-	/// Proof: `Vector::Broadcasters` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
 	fn set_broadcaster() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `380`
-		//  Estimated: `3501`
-		// Minimum execution time: 22_860_000 picoseconds.
-		Weight::from_parts(23_531_000, 0)
-			.saturating_add(Weight::from_parts(0, 3501))
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Estimate, not benchmarked: measured when the value was written directly, now staged as a pending update.
+		Weight::from_parts(23_687_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 	/// Storage: `Vector::WhitelistedDomains` (r:0 w:1)
 	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
@@ -109,16 +109,18 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 0))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
-	/// Storage: `Vector::ConfigurationStorage` (r:0 w:1)
-	/// Proof: `Vector::ConfigurationStorage` (`max_values`: Some(1), `max_size`: Some(10), added: 505, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
 	fn set_configuration() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 11_193_000 picoseconds.
-		Weight::from_parts(11_624_000, 0)
-			.saturating_add(Weight::from_parts(0, 0))
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Estimate, not benchmarked: measured when the value was written directly, now staged as a pending update.
+		Weight::from_parts(11_880_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 	/// Storage: `Vector::SourceChainFrozen` (r:0 w:1)
 	/// Proof: `Vector::SourceChainFrozen` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
@@ -135,8 +137,10 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 	/// Proof: `Vector::ConfigurationStorage` (`max_values`: Some(1), `max_size`: Some(10), added: 505, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::FunctionIds` (r:1 w:0)
 	/// Proof: `Vector::FunctionIds` (`max_values`: Some(1), `max_size`: Some(65), added: 560, mode: `MaxEncodedLen`)
-	/// Storage: `Vector::StepVerificationKey` (r:1 w:0)
-	/// Proof: `Vector::StepVerificationKey` (`max_values`: Some(1), `max_size`: Some(10003), added: 10498, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::VerificationKeys` (r:1 w:0)
+	/// Proof: `Vector::VerificationKeys` (`max_values`: None, `max_size`: Some(10035), added: 12510, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionProofSystems` (r:1 w:0)
+	/// Proof: `Vector::FunctionProofSystems` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::SyncCommitteePoseidons` (r:1 w:0)
 	/// Proof: `Vector::SyncCommitteePoseidons` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::Head` (r:1 w:1)
@@ -150,33 +154,29 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 	/// Storage: `Vector::Timestamps` (r:0 w:1)
 	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
 	fn fulfill_call_step() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `2599`
-		//  Estimated: `11488`
-		// Minimum execution time: 25_375_236_000 picoseconds.
+		// Measured with json verification keys, before they were stored pre-validated: an upper bound until regenerated with `benchmark pallet`.
 		Weight::from_parts(25_538_659_000, 0)
-			.saturating_add(Weight::from_parts(0, 11488))
-			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(Weight::from_parts(0, 13500))
+			.saturating_add(T::DbWeight::get().reads(9))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	/// Storage: `Vector::ConfigurationStorage` (r:1 w:0)
 	/// Proof: `Vector::ConfigurationStorage` (`max_values`: Some(1), `max_size`: Some(10), added: 505, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::FunctionIds` (r:1 w:0)
 	/// Proof: `Vector::FunctionIds` (`max_values`: Some(1), `max_size`: Some(65), added: 560, mode: `MaxEncodedLen`)
-	/// Storage: `Vector::RotateVerificationKey` (r:1 w:0)
-	/// Proof: `Vector::RotateVerificationKey` (`max_values`: Some(1), `max_size`: Some(10003), added: 10498, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::VerificationKeys` (r:1 w:0)
+	/// Proof: `Vector::VerificationKeys` (`max_values`: None, `max_size`: Some(10035), added: 12510, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionProofSystems` (r:1 w:0)
+	/// Proof: `Vector::FunctionProofSystems` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::Headers` (r:1 w:0)
 	/// Proof: `Vector::Headers` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::SyncCommitteePoseidons` (r:1 w:1)
 	/// Proof: `Vector::SyncCommitteePoseidons` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
 	fn fulfill_call_rotate() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `3042`
-		//  Estimated: `11488`
-		// Minimum execution time: 25_427_483_000 picoseconds.
+		// Measured with json verification keys, before they were stored pre-validated: an upper bound until regenerated with `benchmark pallet`.
 		Weight::from_parts(25_617_463_000, 0)
-			.saturating_add(Weight::from_parts(0, 11488))
-			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(Weight::from_parts(0, 13500))
+			.saturating_add(T::DbWeight::get().reads(6))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// Storage: `Vector::MessageStatus` (r:1 w:1)
@@ -191,15 +191,20 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 	/// Proof: `Vector::ExecutionStateRoots` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
 	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::InboundNonces` (r:1 w:1)
+	/// Proof: `Vector::InboundNonces` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:0)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	fn execute_fungible_token() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `672`
-		//  Estimated: `41487`
-		// Minimum execution time: 201_610_000 picoseconds.
+		// Measured before the challenge delay check and inbound ordering, with their storage added by hand: an estimate until regenerated.
 		Weight::from_parts(209_679_000, 0)
 			.saturating_add(Weight::from_parts(0, 41487))
-			.saturating_add(T::DbWeight::get().reads(7))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	/// Storage: `Vector::MessageStatus` (r:1 w:1)
 	/// Proof: `Vector::MessageStatus` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
@@ -211,48 +216,280 @@ impl<T: frame_system::Config> pallet_vector::WeightInfo for WeightInfo<T> {
 	/// Proof: `Vector::SourceChainFrozen` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
 	/// Storage: `Vector::ExecutionStateRoots` (r:1 w:0)
 	/// Proof: `Vector::ExecutionStateRoots` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::InboundNonces` (r:1 w:1)
+	/// Proof: `Vector::InboundNonces` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:0)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	/// The range of component `l` is `[0, 102400]`.
 	fn execute_arbitrary_message(_l: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `494`
-		//  Estimated: `41487`
-		// Minimum execution time: 126_747_000 picoseconds.
+		// Measured before the challenge delay check and inbound ordering, with their storage added by hand: an estimate until regenerated.
 		Weight::from_parts(130_246_106, 0)
 			.saturating_add(Weight::from_parts(0, 41487))
-			.saturating_add(T::DbWeight::get().reads(5))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
-	/// Storage: `Vector::FunctionIds` (r:0 w:1)
-	/// Proof: `Vector::FunctionIds` (`max_values`: Some(1), `max_size`: Some(65), added: 560, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
 	fn set_function_ids() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 13_916_000 picoseconds.
-		Weight::from_parts(14_493_000, 0)
-			.saturating_add(Weight::from_parts(0, 0))
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Estimate, not benchmarked: measured when the value was written directly, now staged as a pending update.
+		Weight::from_parts(14_330_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
-	/// Storage: `Vector::StepVerificationKey` (r:0 w:1)
-	/// Proof: `Vector::StepVerificationKey` (`max_values`: Some(1), `max_size`: Some(10003), added: 10498, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionIds` (r:1 w:0)
+	/// Proof: `Vector::FunctionIds` (`max_values`: Some(1), `max_size`: Some(65), added: 560, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionProofSystems` (r:1 w:0)
+	/// Proof: `Vector::FunctionProofSystems` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
 	fn set_step_verification_key() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 38_572_000 picoseconds.
-		Weight::from_parts(39_816_000, 0)
-			.saturating_add(Weight::from_parts(0, 0))
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Estimate, not benchmarked: measured key validation, with the key now staged as a pending update.
+		Weight::from_parts(38_853_000, 0)
+			.saturating_add(Weight::from_parts(0, 6602))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
-	/// Storage: `Vector::RotateVerificationKey` (r:0 w:1)
-	/// Proof: `Vector::RotateVerificationKey` (`max_values`: Some(1), `max_size`: Some(10003), added: 10498, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionIds` (r:1 w:0)
+	/// Proof: `Vector::FunctionIds` (`max_values`: Some(1), `max_size`: Some(65), added: 560, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::FunctionProofSystems` (r:1 w:0)
+	/// Proof: `Vector::FunctionProofSystems` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
 	fn set_rotate_verification_key() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 39_842_000 picoseconds.
-		Weight::from_parts(41_367_000, 0)
-			.saturating_add(Weight::from_parts(0, 0))
-			.saturating_add(T::DbWeight::get().writes(1))
+		// Estimate, not benchmarked: measured key validation, with the key now staged as a pending update.
+		Weight::from_parts(40_176_000, 0)
+			.saturating_add(Weight::from_parts(0, 6602))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
+	fn set_function_proof_system() -> Weight {
+		// Estimate, not benchmarked: key validation of `set_step_verification_key` plus staging the update.
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
+	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::RelayerFees` (r:0 w:1)
+	/// Proof: `Vector::RelayerFees` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::OutboundNonces` (r:1 w:1)
+	/// Proof: `Vector::OutboundNonces` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 102400]`.
+	fn send_message_with_fee_arbitrary_message(_l: u32, ) -> Weight {
+		// Estimate, not benchmarked: `send_message` plus the transfer of the fee to the pallet pot.
+		Weight::from_parts(90_372_118, 0)
+			.saturating_add(Weight::from_parts(0, 41487))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
+	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::RelayerFees` (r:0 w:1)
+	/// Proof: `Vector::RelayerFees` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::OutboundNonces` (r:1 w:1)
+	/// Proof: `Vector::OutboundNonces` (`max_values`: None, `max_size`: Some(24), added: 2499, mode: `MaxEncodedLen`)
+	fn send_message_with_fee_fungible_token() -> Weight {
+		// Estimate, not benchmarked: `send_message` plus the transfer of the fee to the pallet pot.
+		Weight::from_parts(151_026_000, 0)
+			.saturating_add(Weight::from_parts(0, 41487))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Storage: `Vector::RelayerFees` (r:1 w:1)
+	/// Proof: `Vector::RelayerFees` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn refund_relayer_fee() -> Weight {
+		// Estimate, not benchmarked: a fee lookup and one transfer.
+		Weight::from_parts(74_118_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::RelayerFees` (r:1 w:1)
+	/// Proof: `Vector::RelayerFees` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:0)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ExecutionStateRoots` (r:1 w:0)
+	/// Proof: `Vector::ExecutionStateRoots` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Broadcasters` (r:1 w:0)
+	/// Proof: `Vector::Broadcasters` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn claim_relayer_fee() -> Weight {
+		// Estimate, not benchmarked: the account and storage proof verification of `execute_fungible_token`.
+		Weight::from_parts(204_185_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::PendingUpdates` (r:1 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	fn cancel_update() -> Weight {
+		// Estimate, not benchmarked: removal of a pending update from the agenda.
+		Weight::from_parts(25_102_000, 0)
+			.saturating_add(Weight::from_parts(0, 13478))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
+	fn set_challenge_delay() -> Weight {
+		// Estimate, not benchmarked: staging the update, as `set_function_ids`.
+		Weight::from_parts(18_601_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::ExecutionStateRoots` (r:1 w:1)
+	/// Proof: `Vector::ExecutionStateRoots` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:1)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Head` (r:1 w:1)
+	/// Proof: `Vector::Head` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Headers` (r:0 w:1)
+	/// Proof: `Vector::Headers` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	fn invalidate_slot() -> Weight {
+		// Estimate, not benchmarked: removal of the roots of a slot and the head rollback.
+		Weight::from_parts(22_187_000, 0)
+			.saturating_add(Weight::from_parts(0, 3505))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Storage: `Vector::Headers` (r:2 w:1)
+	/// Proof: `Vector::Headers` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ExecutionStateRoots` (r:1 w:1)
+	/// Proof: `Vector::ExecutionStateRoots` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:1)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	fn fulfill_historical_slot() -> Weight {
+		// Estimate, not benchmarked: three SSZ branch verifications and the anchor challenge window check.
+		Weight::from_parts(98_042_000, 0)
+			.saturating_add(Weight::from_parts(0, 6020))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: `Vector::MessageStatus` (r:1 w:1)
+	/// Proof: `Vector::MessageStatus` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
+	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::SourceChainFrozen` (r:1 w:0)
+	/// Proof: `Vector::SourceChainFrozen` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:0)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Headers` (r:1 w:0)
+	/// Proof: `Vector::Headers` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Broadcasters` (r:1 w:0)
+	/// Proof: `Vector::Broadcasters` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ExecutedMessageRoots` (r:0 w:1)
+	/// Proof: `Vector::ExecutedMessageRoots` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::InboundNonces` (r:1 w:1)
+	/// Proof: `Vector::InboundNonces` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn execute_via_receipt_fungible_token() -> Weight {
+		// Estimate, not benchmarked: `execute_*` with a receipt log in place of the storage proof. The receipts trie proof is charged separately by `verify_receipt_proof`.
+		Weight::from_parts(234_402_000, 0)
+			.saturating_add(Weight::from_parts(0, 41487))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: `Vector::MessageStatus` (r:1 w:1)
+	/// Proof: `Vector::MessageStatus` (`max_values`: None, `max_size`: Some(33), added: 2508, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::WhitelistedDomains` (r:1 w:0)
+	/// Proof: `Vector::WhitelistedDomains` (`max_values`: Some(1), `max_size`: Some(40002), added: 40497, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::SourceChainFrozen` (r:1 w:0)
+	/// Proof: `Vector::SourceChainFrozen` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Timestamps` (r:1 w:0)
+	/// Proof: `Vector::Timestamps` (`max_values`: None, `max_size`: Some(16), added: 2491, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ChallengeDelay` (r:1 w:0)
+	/// Proof: `Vector::ChallengeDelay` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Headers` (r:1 w:0)
+	/// Proof: `Vector::Headers` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::Broadcasters` (r:1 w:0)
+	/// Proof: `Vector::Broadcasters` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::ExecutedMessageRoots` (r:0 w:1)
+	/// Proof: `Vector::ExecutedMessageRoots` (`max_values`: None, `max_size`: Some(44), added: 2519, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::InboundNonces` (r:1 w:1)
+	/// Proof: `Vector::InboundNonces` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 102400]`.
+	fn execute_via_receipt_arbitrary_message(l: u32, ) -> Weight {
+		// Estimate, not benchmarked: `execute_*` with a receipt log in place of the storage proof. The receipts trie proof is charged separately by `verify_receipt_proof`.
+		Weight::from_parts(157_093_112, 0)
+			.saturating_add(Weight::from_parts(0, 41487))
+			.saturating_add(Weight::from_parts(31, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// The range of component `n` is `[1, 7]`.
+	/// The range of component `b` is `[0, 102400]`.
+	fn verify_receipt_proof(n: u32, b: u32, ) -> Weight {
+		// Estimate, not benchmarked: hashing and decoding of the proof nodes.
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(6_000, 0).saturating_mul(b.into()))
+	}
+	/// Storage: `Vector::NextUpdateId` (r:1 w:1)
+	/// Proof: `Vector::NextUpdateId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::UpdateAgenda` (r:1 w:1)
+	/// Proof: `Vector::UpdateAgenda` (`max_values`: None, `max_size`: Some(69), added: 2544, mode: `MaxEncodedLen`)
+	/// Storage: `Vector::PendingUpdates` (r:0 w:1)
+	/// Proof: `Vector::PendingUpdates` (`max_values`: None, `max_size`: Some(10042), added: 12517, mode: `MaxEncodedLen`)
+	fn set_inbound_ordering() -> Weight {
+		// Estimate, not benchmarked: staging the update, as `set_function_ids`.
+		Weight::from_parts(18_793_000, 0)
+			.saturating_add(Weight::from_parts(0, 3534))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 }